@@ -0,0 +1,46 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::ptr;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+/// Runs a future to completion on the current thread, by polling it in a
+/// busy loop.
+///
+/// This is a tiny executor, with no dependencies & no allocation, that is
+/// mostly useful for tests & for simple applications where the futures are
+/// expected to make progress on every poll, such as when reading from memory.
+/// Since the waker does nothing, a future that waits to be woken up by another
+/// task or by an interrupt is polled again & again until it is ready, which
+/// wastes CPU cycles.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::async_io::{AsyncReadExt, block_on};
+///
+/// let mut reader: &[u8] = b"hello";
+/// let mut buffer = [0; 3];
+/// let n = block_on(reader.read(&mut buffer)).unwrap();
+/// assert_eq!(&buffer[..n], b"hel");
+/// ```
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = future;
+    // SAFETY: `future` is shadowed, so it cannot be moved again after being pinned
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+    // SAFETY: all functions of the vtable are no-ops, which do not use the data pointer
+    let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        core::hint::spin_loop();
+    }
+}
+
+fn noop_raw_waker() -> RawWaker {
+    RawWaker::new(ptr::null(), &NOOP_WAKER_VTABLE)
+}
+
+const NOOP_WAKER_VTABLE: RawWakerVTable =
+    RawWakerVTable::new(|_| noop_raw_waker(), |_| {}, |_| {}, |_| {});
@@ -0,0 +1,173 @@
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
+
+use super::{AsyncBufRead, AsyncRead, AsyncWrite};
+use crate::{DEFAULT_BUF_SIZE, Error, ErrorKind, ReadBuf, Result};
+
+/// Copies the entire contents of a reader into a writer, asynchronously.
+///
+/// This is the asynchronous counterpart of [`copy`](crate::copy). The future
+/// copies by way of a buffer of its own, and flushes the writer once `reader`
+/// returns EOF. On success, it resolves to the total number of bytes that were
+/// copied from `reader` to `writer`.
+///
+/// # Errors
+///
+/// The future resolves to an error immediately if any call to
+/// [`poll_read`](AsyncRead::poll_read) or [`poll_write`](AsyncWrite::poll_write)
+/// returns an error. All instances of [`ErrorKind::Interrupted`] are handled
+/// by the future and the underlying operation is retried.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::async_io::{block_on, copy};
+///
+/// let mut reader: &[u8] = b"hello";
+/// let mut writer: Vec<u8> = vec![];
+///
+/// assert_eq!(block_on(copy(&mut reader, &mut writer)).unwrap(), 5);
+/// assert_eq!(&b"hello"[..], &writer[..]);
+/// ```
+pub fn copy<'a, R, W>(reader: &'a mut R, writer: &'a mut W) -> Copy<'a, R, W>
+where
+    R: AsyncRead + Unpin + ?Sized,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    Copy { reader, writer, buf: [0; DEFAULT_BUF_SIZE], pos: 0, cap: 0, amt: 0, read_done: false }
+}
+
+/// Copies the entire contents of a buffered reader into a writer,
+/// asynchronously.
+///
+/// This works like [`copy`], but writes the data straight out of the buffer
+/// returned by [`poll_fill_buf`](AsyncBufRead::poll_fill_buf), with no
+/// intermediate buffer.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::async_io::{block_on, copy_buf};
+/// use portable_io::Cursor;
+///
+/// let mut reader: &[u8] = b"hello";
+/// let mut storage = [0; 5];
+/// let mut writer = Cursor::new(&mut storage[..]);
+///
+/// assert_eq!(block_on(copy_buf(&mut reader, &mut writer)).unwrap(), 5);
+/// assert_eq!(&storage, b"hello");
+/// ```
+pub fn copy_buf<'a, R, W>(reader: &'a mut R, writer: &'a mut W) -> CopyBuf<'a, R, W>
+where
+    R: AsyncBufRead + Unpin + ?Sized,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    CopyBuf { reader, writer, amt: 0 }
+}
+
+/// Future for the [`copy`] function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Copy<'a, R: ?Sized, W: ?Sized> {
+    reader: &'a mut R,
+    writer: &'a mut W,
+    buf: [u8; DEFAULT_BUF_SIZE],
+    // (the data in `buf[pos..cap]` is yet to be written)
+    pos: usize,
+    cap: usize,
+    amt: u64,
+    read_done: bool,
+}
+
+impl<R, W> Future for Copy<'_, R, W>
+where
+    R: AsyncRead + Unpin + ?Sized,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    type Output = Result<u64>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            if this.pos == this.cap && !this.read_done {
+                let mut buf = ReadBuf::new(&mut this.buf);
+                match ready!(Pin::new(&mut *this.reader).poll_read(cx, &mut buf)) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Poll::Ready(Err(e)),
+                }
+                let n = buf.filled_len();
+                if n == 0 {
+                    this.read_done = true;
+                } else {
+                    this.pos = 0;
+                    this.cap = n;
+                }
+            }
+
+            while this.pos < this.cap {
+                let buf = &this.buf[this.pos..this.cap];
+                match ready!(Pin::new(&mut *this.writer).poll_write(cx, buf)) {
+                    Ok(0) => return Poll::Ready(Err(write_zero())),
+                    Ok(n) => {
+                        this.pos += n;
+                        this.amt += n as u64;
+                    }
+                    Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                    Err(e) => return Poll::Ready(Err(e)),
+                }
+            }
+
+            if this.read_done {
+                ready!(Pin::new(&mut *this.writer).poll_flush(cx))?;
+                return Poll::Ready(Ok(this.amt));
+            }
+        }
+    }
+}
+
+/// Future for the [`copy_buf`] function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct CopyBuf<'a, R: ?Sized, W: ?Sized> {
+    reader: &'a mut R,
+    writer: &'a mut W,
+    amt: u64,
+}
+
+impl<R, W> Future for CopyBuf<'_, R, W>
+where
+    R: AsyncBufRead + Unpin + ?Sized,
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    type Output = Result<u64>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            let buf = match ready!(Pin::new(&mut *this.reader).poll_fill_buf(cx)) {
+                Ok(buf) => buf,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            };
+            if buf.is_empty() {
+                ready!(Pin::new(&mut *this.writer).poll_flush(cx))?;
+                return Poll::Ready(Ok(this.amt));
+            }
+
+            let n = match ready!(Pin::new(&mut *this.writer).poll_write(cx, buf)) {
+                Ok(0) => return Poll::Ready(Err(write_zero())),
+                Ok(n) => n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            };
+            this.amt += n as u64;
+            Pin::new(&mut *this.reader).consume(n);
+        }
+    }
+}
+
+fn write_zero() -> Error {
+    Error::new_const(ErrorKind::WriteZero, &"failed to write whole buffer")
+}
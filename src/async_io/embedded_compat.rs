@@ -0,0 +1,547 @@
+//! Compatibility with the asynchronous traits of `embedded-io-async`.
+//!
+//! <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+//! <div class="warning">REQUIRES feature to be enabled: <code>embedded-io-async</code></div>
+
+// (ADDED in this fork) adapters between the async traits of this crate & the traits of `embedded-io-async`
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "alloc")]
+use core::cmp;
+#[cfg(feature = "alloc")]
+use core::fmt;
+use core::future::Future;
+#[cfg(feature = "alloc")]
+use core::mem;
+use core::pin::Pin;
+#[cfg(feature = "alloc")]
+use core::task::ready;
+use core::task::{Context, Poll};
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::{
+    AsyncBufRead, AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite, AsyncWriteExt,
+};
+use crate::embedded::from_embedded_seek_from;
+#[cfg(feature = "alloc")]
+use crate::embedded::{from_embedded_error, to_embedded_seek_from};
+#[cfg(feature = "alloc")]
+use crate::{DEFAULT_BUF_SIZE, ErrorKind, ReadBuf, SeekFrom};
+use crate::{Error, Result};
+
+/// Adapts a reader or writer with the async traits of this crate to the
+/// traits of `embedded-io-async`.
+///
+/// `ToEmbeddedAsync<T>` implements `embedded_io_async::{Read, Write, Seek, BufRead}`
+/// if `T` implements [`AsyncRead`], [`AsyncWrite`], [`AsyncSeek`] or
+/// [`AsyncBufRead`] of this crate (& [`Unpin`]), with [`Error`] as the error
+/// type.
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(feature = "embedded-io-async")] {
+/// use portable_io::Cursor;
+/// use portable_io::async_io::block_on;
+/// use portable_io::async_io::embedded_compat::ToEmbeddedAsync;
+///
+/// async fn send<W: embedded_io_async::Write>(mut writer: W) -> Result<(), W::Error> {
+///     writer.write_all(b"hello").await?;
+///     writer.flush().await
+/// }
+///
+/// let mut buffer = [0; 8];
+/// block_on(send(ToEmbeddedAsync::new(Cursor::new(&mut buffer[..])))).unwrap();
+/// assert_eq!(&buffer[..5], b"hello");
+/// # }
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>embedded-io-async</code></div>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ToEmbeddedAsync<T> {
+    inner: T,
+}
+
+impl<T> ToEmbeddedAsync<T> {
+    /// Creates a new `ToEmbeddedAsync` that wraps the given reader or writer of this crate.
+    pub const fn new(inner: T) -> ToEmbeddedAsync<T> {
+        ToEmbeddedAsync { inner }
+    }
+
+    /// Gets a reference to the underlying reader or writer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader or writer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this `ToEmbeddedAsync`, returning the underlying reader or writer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> embedded_io_async::ErrorType for ToEmbeddedAsync<T> {
+    type Error = Error;
+}
+
+impl<T: AsyncRead + Unpin> embedded_io_async::Read for ToEmbeddedAsync<T> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf).await
+    }
+}
+
+impl<T: AsyncBufRead + Unpin> embedded_io_async::BufRead for ToEmbeddedAsync<T> {
+    async fn fill_buf(&mut self) -> Result<&[u8]> {
+        FillBuf { reader: Some(&mut self.inner) }.await
+    }
+
+    fn consume(&mut self, amt: usize) {
+        Pin::new(&mut self.inner).consume(amt)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> embedded_io_async::Write for ToEmbeddedAsync<T> {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    // (returns an error of the kind `WriteZero` rather than panicking like the
+    // default method if no bytes are written)
+    async fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.inner.write_all(buf).await
+    }
+}
+
+impl<T: AsyncSeek + Unpin> embedded_io_async::Seek for ToEmbeddedAsync<T> {
+    async fn seek(&mut self, pos: embedded_io_async::SeekFrom) -> Result<u64> {
+        self.inner.seek(from_embedded_seek_from(pos)).await
+    }
+}
+
+// Future for `fill_buf` of `ToEmbeddedAsync`
+struct FillBuf<'a, R> {
+    reader: Option<&'a mut R>,
+}
+
+impl<'a, R: AsyncBufRead + Unpin> Future for FillBuf<'a, R> {
+    type Output = Result<&'a [u8]>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let reader = self.reader.take().expect("polled after completion");
+        match Pin::new(&mut *reader).poll_fill_buf(cx) {
+            Poll::Ready(result) => {
+                // SAFETY: the bytes are borrowed from `reader`, which is
+                // borrowed for `'a` & not used again (this is only needed due
+                // to a limitation of the borrow checker)
+                Poll::Ready(result.map(|buf| unsafe { &*(buf as *const [u8]) }))
+            }
+            Poll::Pending => {
+                self.reader = Some(reader);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Adapts a reader or writer from `embedded-io-async` to the async traits of
+/// this crate.
+///
+/// `FromEmbeddedAsync<T>` implements [`AsyncRead`], [`AsyncBufRead`],
+/// [`AsyncWrite`] & [`AsyncSeek`] of this crate if `T` implements `Read`,
+/// `Write` or `Seek` from `embedded-io-async`. Errors from `T` are converted
+/// in the same way as by [`FromEmbedded`](crate::FromEmbedded).
+///
+/// The methods of `embedded-io-async` return futures that borrow `T` & cannot
+/// be named, so that they cannot be kept between calls of the `poll_*`
+/// methods. Rather, `T` is moved into a boxed future for each operation,
+/// which lives for `'a`, & is given back once the operation is completed.
+/// Until then, [`get_ref`](FromEmbeddedAsync::get_ref) & the like return `None`.
+///
+/// Reads go through a buffer, which provides [`AsyncBufRead`]. Writes are
+/// copied to another buffer & only written by the next call of a `poll_*`
+/// method, like for the `File` of `tokio`: an error from writing them is
+/// returned by that call, & this adapter must be flushed for the bytes to
+/// reach `T`. In the same way, an operation that was started but not
+/// completed (for a call that returned [`Poll::Pending`]) is completed by the
+/// next call, which returns its error if any.
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(all(feature = "embedded-io-async", feature = "alloc"))] {
+/// use portable_io::async_io::embedded_compat::FromEmbeddedAsync;
+/// use portable_io::async_io::{AsyncBufReadExt, AsyncWriteExt, block_on};
+///
+/// // (`&[u8]` implements `embedded_io_async::Read`, like a driver from a HAL would)
+/// let uart: &[u8] = b"first line\nsecond line\n";
+/// let mut reader = FromEmbeddedAsync::new(uart);
+///
+/// let mut line = String::new();
+/// block_on(reader.read_line(&mut line)).unwrap();
+/// assert_eq!(line, "first line\n");
+///
+/// // (`&mut [u8]` implements `embedded_io_async::Write`)
+/// let mut storage = [0; 8];
+/// let mut writer = FromEmbeddedAsync::new(&mut storage[..]);
+/// block_on(writer.write_all(b"hello")).unwrap();
+/// block_on(writer.flush()).unwrap();
+/// drop(writer);
+/// assert_eq!(&storage[..5], b"hello");
+/// # }
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES features to be enabled: <code>embedded-io-async</code> & <code>alloc</code></div>
+#[cfg(feature = "alloc")]
+pub struct FromEmbeddedAsync<'a, T> {
+    state: State<'a, T>,
+}
+
+#[cfg(feature = "alloc")]
+struct Core<T> {
+    inner: T,
+    capacity: usize,
+    // (the bytes that were read ahead are `read_buf[pos..filled]`)
+    read_buf: Box<[u8]>,
+    pos: usize,
+    filled: usize,
+    write_buf: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+type Operation<'a, T> = Pin<Box<dyn Future<Output = (Core<T>, Result<u64>)> + 'a>>;
+
+#[cfg(feature = "alloc")]
+enum State<'a, T> {
+    Idle(Core<T>),
+    Busy(Op, Operation<'a, T>),
+    // (only while an operation is started)
+    Starting,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Read,
+    Write,
+    Flush,
+    Seek(SeekFrom),
+}
+
+// (`T` is never pinned: it is moved into the futures of the operations)
+#[cfg(feature = "alloc")]
+impl<T> Unpin for FromEmbeddedAsync<'_, T> {}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> FromEmbeddedAsync<'a, T> {
+    /// Creates a new `FromEmbeddedAsync` that wraps the given reader or writer
+    /// from `embedded-io-async`, with buffers of the default capacity
+    /// (currently 8 KiB), which are allocated once they are used.
+    pub fn new(inner: T) -> FromEmbeddedAsync<'a, T> {
+        FromEmbeddedAsync::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a new `FromEmbeddedAsync` with buffers of the given capacity,
+    /// which are allocated once they are used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn with_capacity(capacity: usize, inner: T) -> FromEmbeddedAsync<'a, T> {
+        assert!(capacity > 0, "buffer capacity must not be 0");
+        let core = Core {
+            inner,
+            capacity,
+            read_buf: Box::new([]),
+            pos: 0,
+            filled: 0,
+            write_buf: Vec::new(),
+        };
+        FromEmbeddedAsync { state: State::Idle(core) }
+    }
+
+    /// Gets a reference to the underlying reader or writer, or `None` while
+    /// an operation is in progress.
+    pub fn get_ref(&self) -> Option<&T> {
+        match &self.state {
+            State::Idle(core) => Some(&core.inner),
+            _ => None,
+        }
+    }
+
+    /// Gets a mutable reference to the underlying reader or writer, or `None`
+    /// while an operation is in progress.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        match &mut self.state {
+            State::Idle(core) => Some(&mut core.inner),
+            _ => None,
+        }
+    }
+
+    /// Unwraps this `FromEmbeddedAsync`, returning the underlying reader or
+    /// writer, or `None` while an operation is in progress (such as a write
+    /// that was not flushed).
+    ///
+    /// Any bytes that were read ahead are lost.
+    pub fn into_inner(self) -> Option<T> {
+        match self.state {
+            State::Idle(core) => Some(core.inner),
+            _ => None,
+        }
+    }
+
+    fn core(&mut self) -> &mut Core<T> {
+        match &mut self.state {
+            State::Idle(core) => core,
+            _ => unreachable!("no operation in progress"),
+        }
+    }
+
+    fn start<F>(&mut self, op: Op, f: impl FnOnce(Core<T>) -> F)
+    where
+        F: Future<Output = (Core<T>, Result<u64>)> + 'a,
+    {
+        match mem::replace(&mut self.state, State::Starting) {
+            State::Idle(core) => self.state = State::Busy(op, Box::pin(f(core))),
+            _ => unreachable!("no operation in progress"),
+        }
+    }
+
+    // Completes the operation in progress, if any, returning it with its result
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<Option<(Op, Result<u64>)>> {
+        let (op, (core, result)) = match &mut self.state {
+            State::Busy(op, operation) => (*op, ready!(operation.as_mut().poll(cx))),
+            _ => return Poll::Ready(None),
+        };
+        self.state = State::Idle(core);
+        Poll::Ready(Some((op, result)))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> FromEmbeddedAsync<'a, T>
+where
+    T: embedded_io_async::Read + 'a,
+    T::Error: Send + Sync + 'static,
+{
+    // Fills the read buffer if it is empty, after the operation in progress
+    fn poll_fill(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        loop {
+            match ready!(self.poll_idle(cx)) {
+                Some((_, Err(e))) => return Poll::Ready(Err(e)),
+                Some((Op::Read, Ok(_))) => return Poll::Ready(Ok(())),
+                _ => {}
+            }
+            let core = self.core();
+            if core.pos < core.filled {
+                return Poll::Ready(Ok(()));
+            }
+            self.start(Op::Read, |mut core| {
+                if core.read_buf.is_empty() {
+                    core.read_buf = vec![0; core.capacity].into_boxed_slice();
+                }
+                async move {
+                    let result = core.inner.read(&mut core.read_buf).await;
+                    let result = result.map_err(from_embedded_error).map(|n| {
+                        (core.pos, core.filled) = (0, n);
+                        n as u64
+                    });
+                    (core, result)
+                }
+            });
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> AsyncRead for FromEmbeddedAsync<'a, T>
+where
+    T: embedded_io_async::Read + 'a,
+    T::Error: Send + Sync + 'static,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        ready!(this.poll_fill(cx))?;
+        let core = this.core();
+        let amt = cmp::min(buf.remaining(), core.filled - core.pos);
+        buf.append(&core.read_buf[core.pos..][..amt]);
+        core.pos += amt;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> AsyncBufRead for FromEmbeddedAsync<'a, T>
+where
+    T: embedded_io_async::Read + 'a,
+    T::Error: Send + Sync + 'static,
+{
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        let this = self.get_mut();
+        ready!(this.poll_fill(cx))?;
+        let core = this.core();
+        Poll::Ready(Ok(&core.read_buf[core.pos..core.filled]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        if let State::Idle(core) = &mut self.get_mut().state {
+            core.pos = cmp::min(core.pos + amt, core.filled);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> AsyncWrite for FromEmbeddedAsync<'a, T>
+where
+    T: embedded_io_async::Write + 'a,
+    T::Error: Send + Sync + 'static,
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+        if let Some((_, result)) = ready!(this.poll_idle(cx)) {
+            result?;
+        }
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        let amt = cmp::min(buf.len(), this.core().capacity);
+        this.start(Op::Write, |mut core| {
+            core.write_buf.clear();
+            core.write_buf.extend_from_slice(&buf[..amt]);
+            async move {
+                let result = write_all(&mut core.inner, &core.write_buf).await;
+                (core, result.map(|()| 0))
+            }
+        });
+        Poll::Ready(Ok(amt))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let this = self.get_mut();
+        loop {
+            match ready!(this.poll_idle(cx)) {
+                Some((Op::Flush, result)) => return Poll::Ready(result.map(drop)),
+                Some((_, Err(e))) => return Poll::Ready(Err(e)),
+                _ => {}
+            }
+            this.start(Op::Flush, |mut core| async move {
+                let result = core.inner.flush().await.map_err(from_embedded_error);
+                (core, result.map(|()| 0))
+            });
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> AsyncSeek for FromEmbeddedAsync<'a, T>
+where
+    T: embedded_io_async::Seek + 'a,
+    T::Error: Send + Sync + 'static,
+{
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        let this = self.get_mut();
+        loop {
+            match ready!(this.poll_idle(cx)) {
+                Some((Op::Seek(started), result)) if started == pos => return Poll::Ready(result),
+                Some((_, Err(e))) => return Poll::Ready(Err(e)),
+                _ => {}
+            }
+            this.start(Op::Seek(pos), |mut core| async move {
+                let result = seek(&mut core, pos).await;
+                (core, result)
+            });
+        }
+    }
+}
+
+// Writes all of the bytes, with an error rather than a panic if no bytes are
+// written (unlike `write_all` of `embedded-io-async`)
+#[cfg(feature = "alloc")]
+async fn write_all<W>(writer: &mut W, mut buf: &[u8]) -> Result<()>
+where
+    W: embedded_io_async::Write,
+    W::Error: Send + Sync + 'static,
+{
+    while !buf.is_empty() {
+        match writer.write(buf).await.map_err(from_embedded_error) {
+            Ok(0) => {
+                return Err(Error::new_const(
+                    ErrorKind::WriteZero,
+                    &"failed to write whole buffer",
+                ));
+            }
+            Ok(n) => buf = &buf[n..],
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+// Seeks like `BufReader` does, discarding the bytes that were read ahead
+#[cfg(feature = "alloc")]
+async fn seek<T>(core: &mut Core<T>, pos: SeekFrom) -> Result<u64>
+where
+    T: embedded_io_async::Seek,
+    T::Error: Send + Sync + 'static,
+{
+    let pos = match pos {
+        SeekFrom::Current(n) => {
+            let remainder = (core.filled - core.pos) as i64;
+            match n.checked_sub(remainder) {
+                Some(offset) => SeekFrom::Current(offset),
+                None => {
+                    // (seeking back by the remainder first, then by the offset)
+                    let back = SeekFrom::Current(-remainder);
+                    core.inner
+                        .seek(to_embedded_seek_from(back))
+                        .await
+                        .map_err(from_embedded_error)?;
+                    (core.pos, core.filled) = (0, 0);
+                    SeekFrom::Current(n)
+                }
+            }
+        }
+        pos => pos,
+    };
+    let result = core.inner.seek(to_embedded_seek_from(pos)).await.map_err(from_embedded_error)?;
+    (core.pos, core.filled) = (0, 0);
+    Ok(result)
+}
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug> fmt::Debug for FromEmbeddedAsync<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromEmbeddedAsync").field("inner", &self.get_ref()).finish()
+    }
+}
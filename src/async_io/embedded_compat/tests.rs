@@ -0,0 +1,220 @@
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use core::future::Future;
+#[cfg(feature = "alloc")]
+use core::pin::Pin;
+#[cfg(feature = "alloc")]
+use core::task::{Context, Poll};
+
+use embedded_io_async::{BufRead as _, Read as _, Seek as _, Write as _};
+
+#[cfg(feature = "alloc")]
+use super::FromEmbeddedAsync;
+use super::ToEmbeddedAsync;
+use crate::async_io::block_on;
+#[cfg(feature = "alloc")]
+use crate::async_io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use crate::{Cursor, ErrorKind};
+
+// Returns `Poll::Pending` once, like a driver waiting for an interrupt
+#[cfg(feature = "alloc")]
+struct YieldOnce(bool);
+
+#[cfg(feature = "alloc")]
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+// An in-memory device with the traits of `embedded-io-async`, which waits
+// once in each operation
+#[cfg(feature = "alloc")]
+struct Device {
+    data: Vec<u8>,
+    pos: usize,
+    // (the most bytes that are read or written at a time)
+    chunk: usize,
+    // (returned by the next write, if any)
+    write_error: Option<embedded_io_async::ErrorKind>,
+    flushes: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl Device {
+    fn new(data: &[u8], chunk: usize) -> Self {
+        Device { data: data.to_vec(), pos: 0, chunk, write_error: None, flushes: 0 }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl embedded_io_async::ErrorType for Device {
+    type Error = embedded_io_async::ErrorKind;
+}
+
+#[cfg(feature = "alloc")]
+impl embedded_io_async::Read for Device {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        YieldOnce(false).await;
+        let n = buf.len().min(self.chunk).min(self.data.len().saturating_sub(self.pos));
+        buf[..n].copy_from_slice(&self.data[self.pos..][..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl embedded_io_async::Write for Device {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        YieldOnce(false).await;
+        if let Some(kind) = self.write_error.take() {
+            return Err(kind);
+        }
+        let n = buf.len().min(self.chunk);
+        let end = self.pos + n;
+        if self.data.len() < end {
+            self.data.resize(end, 0);
+        }
+        self.data[self.pos..end].copy_from_slice(&buf[..n]);
+        self.pos = end;
+        Ok(n)
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        YieldOnce(false).await;
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl embedded_io_async::Seek for Device {
+    async fn seek(&mut self, pos: embedded_io_async::SeekFrom) -> Result<u64, Self::Error> {
+        YieldOnce(false).await;
+        let pos = match pos {
+            embedded_io_async::SeekFrom::Start(n) => n as i64,
+            embedded_io_async::SeekFrom::End(n) => self.data.len() as i64 + n,
+            embedded_io_async::SeekFrom::Current(n) => self.pos as i64 + n,
+        };
+        if pos < 0 {
+            return Err(embedded_io_async::ErrorKind::InvalidInput);
+        }
+        self.pos = pos as usize;
+        Ok(self.pos as u64)
+    }
+}
+
+#[test]
+fn to_embedded_async() {
+    let mut reader = ToEmbeddedAsync::new(&b"hello\nworld"[..]);
+    let mut buf = [0; 3];
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 3);
+    assert_eq!(&buf, b"hel");
+    assert_eq!(block_on(reader.fill_buf()).unwrap(), b"lo\nworld");
+    reader.consume(3);
+    block_on(reader.read_exact(&mut [0; 5])).unwrap();
+    assert_eq!(block_on(reader.fill_buf()).unwrap(), b"");
+
+    let mut storage = [0; 8];
+    let mut writer = ToEmbeddedAsync::new(Cursor::new(&mut storage[..]));
+    block_on(writer.write_all(b"hello")).unwrap();
+    block_on(writer.flush()).unwrap();
+    assert_eq!(block_on(writer.seek(embedded_io_async::SeekFrom::Current(-2))).unwrap(), 3);
+    assert_eq!(block_on(writer.stream_position()).unwrap(), 3);
+
+    // (an error rather than a panic once the storage is full)
+    let error = block_on(writer.write_all(b"LO, world")).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::WriteZero);
+    assert_eq!(embedded_io_async::Error::kind(&error), embedded_io_async::ErrorKind::WriteZero);
+    assert_eq!(&storage, b"helLO, w");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn from_embedded_async_read() {
+    let mut reader = FromEmbeddedAsync::with_capacity(4, Device::new(b"first line\nsecond", 3));
+    let mut line = String::new();
+    assert_eq!(block_on(reader.read_line(&mut line)).unwrap(), 11);
+    assert_eq!(line, "first line\n");
+
+    let mut rest = Vec::new();
+    assert_eq!(block_on(reader.read_to_end(&mut rest)).unwrap(), 6);
+    assert_eq!(rest, b"second");
+    assert_eq!(reader.into_inner().unwrap().pos, 17);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn from_embedded_async_write_and_flush() {
+    let mut writer = FromEmbeddedAsync::with_capacity(4, Device::new(b"", 3));
+    // (at most the capacity at a time, written by the next call)
+    assert_eq!(block_on(writer.write(b"hello")).unwrap(), 4);
+    assert!(writer.get_ref().is_none());
+    block_on(writer.write_all(b"o world")).unwrap();
+    block_on(writer.flush()).unwrap();
+    let device = writer.get_ref().unwrap();
+    assert_eq!((&device.data[..], device.flushes), (&b"hello world"[..], 1));
+
+    // (the error from writing is returned by the next call)
+    writer.get_mut().unwrap().write_error = Some(embedded_io_async::ErrorKind::BrokenPipe);
+    assert_eq!(block_on(writer.write(b"!")).unwrap(), 1);
+    assert_eq!(block_on(writer.flush()).unwrap_err().kind(), ErrorKind::BrokenPipe);
+    block_on(writer.close()).unwrap();
+    assert_eq!(writer.get_ref().unwrap().flushes, 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn from_embedded_async_seek() {
+    let mut stream = FromEmbeddedAsync::with_capacity(8, Device::new(b"0123456789", 8));
+    let mut buf = [0; 2];
+    block_on(stream.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"01");
+
+    // (relative to the bytes that were read, not to the bytes read ahead)
+    assert_eq!(block_on(stream.seek(crate::SeekFrom::Current(1))).unwrap(), 3);
+    block_on(stream.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"34");
+    assert_eq!(block_on(stream.seek(crate::SeekFrom::Current(-5))).unwrap(), 0);
+    assert_eq!(block_on(stream.seek(crate::SeekFrom::End(-1))).unwrap(), 9);
+    block_on(stream.write_all(b"9!")).unwrap();
+    assert_eq!(block_on(stream.seek(crate::SeekFrom::Start(8))).unwrap(), 8);
+    let mut rest = Vec::new();
+    block_on(stream.read_to_end(&mut rest)).unwrap();
+    assert_eq!(rest, b"89!");
+
+    let error = block_on(stream.seek(crate::SeekFrom::Current(-20))).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn round_trip_through_embedded_io_async() {
+    // (the inner adapter implements the traits of `embedded-io-async` & the
+    // outer one implements the traits of this crate again)
+    let mut cursor = FromEmbeddedAsync::new(ToEmbeddedAsync::new(Cursor::new(Vec::new())));
+    block_on(cursor.write_all(b"hello world")).unwrap();
+    assert_eq!(block_on(cursor.seek(crate::SeekFrom::Start(6))).unwrap(), 6);
+    let mut buf = [0; 5];
+    block_on(cursor.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"world");
+
+    // (errors of this crate are passed on as is)
+    let error = block_on(cursor.seek(crate::SeekFrom::Current(-20))).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert!(error.get_ref().is_none());
+    assert_eq!(cursor.into_inner().unwrap().into_inner().into_inner(), b"hello world");
+}
@@ -0,0 +1,458 @@
+use core::future::Future;
+use core::mem;
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use super::AsyncBufRead;
+use super::{AsyncRead, AsyncSeek, AsyncWrite};
+use crate::{Error, ErrorKind, ReadBuf, Result, SeekFrom};
+
+/// Extension methods for [`AsyncRead`], returning futures.
+///
+/// All instances of [`ErrorKind::Interrupted`] are handled by the futures
+/// which read more than once, and the underlying read is retried.
+pub trait AsyncReadExt: AsyncRead {
+    /// Reads some bytes into `buf`, returning how many bytes were read.
+    ///
+    /// This is the asynchronous counterpart of [`Read::read`](crate::Read::read).
+    fn read<'a>(&'a mut self, buf: &'a mut [u8]) -> ReadFuture<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadFuture { reader: self, buf }
+    }
+
+    /// Reads the exact number of bytes required to fill `buf`.
+    ///
+    /// This is the asynchronous counterpart of
+    /// [`Read::read_exact`](crate::Read::read_exact). If EOF is reached before
+    /// `buf` is filled, the future resolves to an error of the kind
+    /// [`ErrorKind::UnexpectedEof`].
+    fn read_exact<'a>(&'a mut self, buf: &'a mut [u8]) -> ReadExact<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadExact { reader: self, buf }
+    }
+
+    /// Reads all bytes until EOF, appending them to `buf`.
+    ///
+    /// This is the asynchronous counterpart of
+    /// [`Read::read_to_end`](crate::Read::read_to_end). On success, the
+    /// future resolves to the number of bytes that were read.
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
+    fn read_to_end<'a>(&'a mut self, buf: &'a mut Vec<u8>) -> ReadToEnd<'a, Self>
+    where
+        Self: Unpin,
+    {
+        let start_len = buf.len();
+        ReadToEnd { reader: self, buf, start_len }
+    }
+}
+
+impl<R: AsyncRead + ?Sized> AsyncReadExt for R {}
+
+/// Extension methods for [`AsyncWrite`], returning futures.
+pub trait AsyncWriteExt: AsyncWrite {
+    /// Writes some bytes from `buf`, returning how many bytes were written.
+    ///
+    /// This is the asynchronous counterpart of [`Write::write`](crate::Write::write).
+    fn write<'a>(&'a mut self, buf: &'a [u8]) -> WriteFuture<'a, Self>
+    where
+        Self: Unpin,
+    {
+        WriteFuture { writer: self, buf }
+    }
+
+    /// Writes all bytes from `buf`.
+    ///
+    /// This is the asynchronous counterpart of
+    /// [`Write::write_all`](crate::Write::write_all). All instances of
+    /// [`ErrorKind::Interrupted`] are handled by this future and the underlying
+    /// write is retried.
+    fn write_all<'a>(&'a mut self, buf: &'a [u8]) -> WriteAll<'a, Self>
+    where
+        Self: Unpin,
+    {
+        WriteAll { writer: self, buf }
+    }
+
+    /// Flushes this writer.
+    ///
+    /// This is the asynchronous counterpart of [`Write::flush`](crate::Write::flush).
+    fn flush(&mut self) -> Flush<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Flush { writer: self }
+    }
+
+    /// Closes this writer, flushing any buffered data first.
+    fn close(&mut self) -> Close<'_, Self>
+    where
+        Self: Unpin,
+    {
+        Close { writer: self }
+    }
+}
+
+impl<W: AsyncWrite + ?Sized> AsyncWriteExt for W {}
+
+/// Extension methods for [`AsyncSeek`], returning futures.
+pub trait AsyncSeekExt: AsyncSeek {
+    /// Seeks to an offset, in bytes, in a stream.
+    ///
+    /// This is the asynchronous counterpart of [`Seek::seek`](crate::Seek::seek).
+    fn seek(&mut self, pos: SeekFrom) -> SeekFuture<'_, Self>
+    where
+        Self: Unpin,
+    {
+        SeekFuture { seeker: self, pos }
+    }
+}
+
+impl<S: AsyncSeek + ?Sized> AsyncSeekExt for S {}
+
+/// Extension methods for [`AsyncBufRead`](super::AsyncBufRead), returning futures.
+///
+/// All instances of [`ErrorKind::Interrupted`] are handled by these futures
+/// and the underlying read is retried.
+pub trait AsyncBufReadExt: super::AsyncBufRead {
+    /// Reads all bytes until the delimiter `byte` or EOF is reached,
+    /// appending them to `buf`.
+    ///
+    /// This is the asynchronous counterpart of
+    /// [`BufRead::read_until`](crate::BufRead::read_until). The delimiter, if
+    /// found, is appended to `buf` as well.
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
+    fn read_until<'a>(&'a mut self, byte: u8, buf: &'a mut Vec<u8>) -> ReadUntil<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadUntil { reader: self, byte, buf, read: 0 }
+    }
+
+    /// Reads all bytes until a newline (the `0xA` byte) or EOF is reached,
+    /// appending them to `buf`.
+    ///
+    /// This is the asynchronous counterpart of
+    /// [`BufRead::read_line`](crate::BufRead::read_line). If the data is not
+    /// valid UTF-8, the future resolves to an error of the kind
+    /// [`ErrorKind::InvalidData`] and `buf` is left unchanged.
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
+    fn read_line<'a>(&'a mut self, buf: &'a mut String) -> ReadLine<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadLine { reader: self, buf, bytes: Vec::new(), read: 0 }
+    }
+}
+
+impl<R: super::AsyncBufRead + ?Sized> AsyncBufReadExt for R {}
+
+/// Future for the [`read`](AsyncReadExt::read) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadFuture<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut [u8],
+}
+
+impl<R: AsyncRead + ?Sized + Unpin> Future for ReadFuture<'_, R> {
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut buf = ReadBuf::new(this.buf);
+        ready!(Pin::new(&mut *this.reader).poll_read(cx, &mut buf))?;
+        Poll::Ready(Ok(buf.filled_len()))
+    }
+}
+
+/// Future for the [`read_exact`](AsyncReadExt::read_exact) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadExact<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut [u8],
+}
+
+impl<R: AsyncRead + ?Sized + Unpin> Future for ReadExact<'_, R> {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        while !this.buf.is_empty() {
+            let mut buf = ReadBuf::new(this.buf);
+            match ready!(Pin::new(&mut *this.reader).poll_read(cx, &mut buf)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+            let n = buf.filled_len();
+            if n == 0 {
+                return Poll::Ready(Err(Error::new_const(
+                    ErrorKind::UnexpectedEof,
+                    &"failed to fill whole buffer",
+                )));
+            }
+            this.buf = &mut mem::take(&mut this.buf)[n..];
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Future for the [`read_to_end`](AsyncReadExt::read_to_end) method.
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadToEnd<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut Vec<u8>,
+    start_len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<R: AsyncRead + ?Sized + Unpin> Future for ReadToEnd<'_, R> {
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            if this.buf.len() == this.buf.capacity() {
+                this.buf.reserve(32); // buf is full, need more space
+            }
+
+            let mut read_buf = ReadBuf::uninit(this.buf.spare_capacity_mut());
+            match ready!(Pin::new(&mut *this.reader).poll_read(cx, &mut read_buf)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+
+            let n = read_buf.filled_len();
+            if n == 0 {
+                return Poll::Ready(Ok(this.buf.len() - this.start_len));
+            }
+
+            // SAFETY: the first `n` bytes of the spare capacity were filled by the reader
+            unsafe { this.buf.set_len(this.buf.len() + n) };
+        }
+    }
+}
+
+/// Future for the [`write`](AsyncWriteExt::write) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WriteFuture<'a, W: ?Sized> {
+    writer: &'a mut W,
+    buf: &'a [u8],
+}
+
+impl<W: AsyncWrite + ?Sized + Unpin> Future for WriteFuture<'_, W> {
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        Pin::new(&mut *this.writer).poll_write(cx, this.buf)
+    }
+}
+
+/// Future for the [`write_all`](AsyncWriteExt::write_all) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WriteAll<'a, W: ?Sized> {
+    writer: &'a mut W,
+    buf: &'a [u8],
+}
+
+impl<W: AsyncWrite + ?Sized + Unpin> Future for WriteAll<'_, W> {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        while !this.buf.is_empty() {
+            match ready!(Pin::new(&mut *this.writer).poll_write(cx, this.buf)) {
+                Ok(0) => {
+                    return Poll::Ready(Err(Error::new_const(
+                        ErrorKind::WriteZero,
+                        &"failed to write whole buffer",
+                    )));
+                }
+                Ok(n) => this.buf = &this.buf[n..],
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Future for the [`flush`](AsyncWriteExt::flush) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Flush<'a, W: ?Sized> {
+    writer: &'a mut W,
+}
+
+impl<W: AsyncWrite + ?Sized + Unpin> Future for Flush<'_, W> {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut *self.get_mut().writer).poll_flush(cx)
+    }
+}
+
+/// Future for the [`close`](AsyncWriteExt::close) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Close<'a, W: ?Sized> {
+    writer: &'a mut W,
+}
+
+impl<W: AsyncWrite + ?Sized + Unpin> Future for Close<'_, W> {
+    type Output = Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut *self.get_mut().writer).poll_close(cx)
+    }
+}
+
+/// Future for the [`seek`](AsyncSeekExt::seek) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SeekFuture<'a, S: ?Sized> {
+    seeker: &'a mut S,
+    pos: SeekFrom,
+}
+
+impl<S: AsyncSeek + ?Sized + Unpin> Future for SeekFuture<'_, S> {
+    type Output = Result<u64>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        Pin::new(&mut *this.seeker).poll_seek(cx, this.pos)
+    }
+}
+
+/// Future for the [`read_until`](AsyncBufReadExt::read_until) method.
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadUntil<'a, R: ?Sized> {
+    reader: &'a mut R,
+    byte: u8,
+    buf: &'a mut Vec<u8>,
+    read: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<R: AsyncBufRead + ?Sized + Unpin> Future for ReadUntil<'_, R> {
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        read_until(Pin::new(&mut *this.reader), cx, this.byte, this.buf, &mut this.read)
+    }
+}
+
+/// Future for the [`read_line`](AsyncBufReadExt::read_line) method.
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadLine<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut String,
+    // (the bytes of the line, which are only appended to `buf` once they are known to be valid UTF-8)
+    bytes: Vec<u8>,
+    read: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<R: AsyncBufRead + ?Sized + Unpin> Future for ReadLine<'_, R> {
+    type Output = Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let read = ready!(read_until(
+            Pin::new(&mut *this.reader),
+            cx,
+            b'\n',
+            &mut this.bytes,
+            &mut this.read
+        ))?;
+        match String::from_utf8(mem::take(&mut this.bytes)) {
+            Ok(line) if this.buf.is_empty() => *this.buf = line,
+            Ok(line) => this.buf.push_str(&line),
+            Err(_) => {
+                return Poll::Ready(Err(Error::new_const(
+                    ErrorKind::InvalidData,
+                    &"stream did not contain valid UTF-8",
+                )));
+            }
+        }
+        Poll::Ready(Ok(read))
+    }
+}
+
+// Reads into `buf` until `delim` or EOF, keeping track of the number of bytes
+// read so far in `read`, so that it can be resumed after `Poll::Pending`.
+#[cfg(feature = "alloc")]
+fn read_until<R: AsyncBufRead + ?Sized>(
+    mut reader: Pin<&mut R>,
+    cx: &mut Context<'_>,
+    delim: u8,
+    buf: &mut Vec<u8>,
+    read: &mut usize,
+) -> Poll<Result<usize>> {
+    loop {
+        let (done, used) = {
+            let available = match ready!(reader.as_mut().poll_fill_buf(cx)) {
+                Ok(n) => n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Poll::Ready(Err(e)),
+            };
+            match memchr::memchr(delim, available) {
+                Some(i) => {
+                    buf.extend_from_slice(&available[..=i]);
+                    (true, i + 1)
+                }
+                None => {
+                    buf.extend_from_slice(available);
+                    (false, available.len())
+                }
+            }
+        };
+        reader.as_mut().consume(used);
+        *read += used;
+        if done || used == 0 {
+            return Poll::Ready(Ok(mem::replace(read, 0)));
+        }
+    }
+}
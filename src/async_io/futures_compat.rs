@@ -0,0 +1,232 @@
+//! Compatibility with the asynchronous traits of `futures-io`.
+//!
+//! <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+//! <div class="warning">REQUIRES feature to be enabled: <code>futures-io</code></div>
+
+// (ADDED in this fork) adapters between the async traits of this crate & the traits of `futures-io`
+
+#[cfg(test)]
+mod tests;
+
+use core::array;
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
+
+extern crate std;
+
+use super::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite};
+use crate::std_io::{MAX_BUFS, from_std_seek_from, to_std_seek_from};
+use crate::{IoSlice, IoSliceMut, ReadBuf, Result, SeekFrom};
+
+/// Adapts a type between the async traits of this crate & the traits of
+/// `futures-io`, in both directions.
+///
+/// `Compat<T>` implements [`AsyncRead`], [`AsyncWrite`], [`AsyncSeek`] &
+/// [`AsyncBufRead`] of this crate if `T` implements the same trait from
+/// `futures-io`, and it implements the traits of `futures-io` if `T` implements
+/// the same trait of this crate. Errors are converted between
+/// [`Error`](crate::Error) & [`std::io::Error`] (which is the error type of
+/// `futures-io`).
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(feature = "futures-io")] {
+/// use portable_io::async_io::futures_compat::Compat;
+/// use portable_io::async_io::{AsyncReadExt, block_on};
+///
+/// // (`&[u8]` implements `futures_io::AsyncRead`)
+/// let mut reader = Compat::new(&b"hello"[..]);
+/// let mut buffer = [0; 5];
+/// block_on(reader.read_exact(&mut buffer)).unwrap();
+/// assert_eq!(&buffer, b"hello");
+/// # }
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>futures-io</code></div>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Compat<T> {
+    inner: T,
+}
+
+impl<T> Compat<T> {
+    /// Creates a new `Compat<T>`, wrapping the given value.
+    pub const fn new(inner: T) -> Compat<T> {
+        Compat { inner }
+    }
+
+    /// Gets a reference to the wrapped value.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the wrapped value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this `Compat<T>`, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn inner_pin(self: Pin<&mut Self>) -> Pin<&mut T> {
+        // SAFETY: `inner` is pinned structurally - it is never moved out of a pinned `Compat<T>`
+        unsafe { self.map_unchecked_mut(|compat| &mut compat.inner) }
+    }
+}
+
+// From `futures-io` to this crate
+
+impl<T: futures_io::AsyncRead> AsyncRead for Compat<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        let n = ready!(self.inner_pin().poll_read(cx, buf.initialize_unfilled()))?;
+        buf.add_filled(n);
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [IoSliceMut<'_>],
+    ) -> Poll<Result<usize>> {
+        let mut std_bufs: [std::io::IoSliceMut<'_>; MAX_BUFS] =
+            array::from_fn(|_| std::io::IoSliceMut::new(&mut []));
+        let mut n = 0;
+        for (std_buf, buf) in std_bufs.iter_mut().zip(bufs.iter_mut()) {
+            *std_buf = std::io::IoSliceMut::new(buf);
+            n += 1;
+        }
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_read_vectored(cx, &mut std_bufs[..n]))?))
+    }
+}
+
+impl<T: futures_io::AsyncBufRead> AsyncBufRead for Compat<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_fill_buf(cx))?))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.inner_pin().consume(amt)
+    }
+}
+
+impl<T: futures_io::AsyncWrite> AsyncWrite for Compat<T> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_write(cx, buf))?))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<Result<usize>> {
+        let mut std_bufs: [std::io::IoSlice<'_>; MAX_BUFS] =
+            array::from_fn(|_| std::io::IoSlice::new(&[]));
+        let mut n = 0;
+        for (std_buf, buf) in std_bufs.iter_mut().zip(bufs.iter()) {
+            *std_buf = std::io::IoSlice::new(buf);
+            n += 1;
+        }
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_write_vectored(cx, &std_bufs[..n]))?))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_flush(cx))?))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_close(cx))?))
+    }
+}
+
+impl<T: futures_io::AsyncSeek> AsyncSeek for Compat<T> {
+    fn poll_seek(self: Pin<&mut Self>, cx: &mut Context<'_>, pos: SeekFrom) -> Poll<Result<u64>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_seek(cx, to_std_seek_from(pos)))?))
+    }
+}
+
+// From this crate to `futures-io`
+
+impl<T: AsyncRead> futures_io::AsyncRead for Compat<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let mut buf = ReadBuf::new(buf);
+        ready!(self.inner_pin().poll_read(cx, &mut buf))?;
+        Poll::Ready(Ok(buf.filled_len()))
+    }
+
+    fn poll_read_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &mut [std::io::IoSliceMut<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        let mut our_bufs: [IoSliceMut<'_>; MAX_BUFS] = array::from_fn(|_| IoSliceMut::new(&mut []));
+        let mut n = 0;
+        for (our_buf, buf) in our_bufs.iter_mut().zip(bufs.iter_mut()) {
+            *our_buf = IoSliceMut::new(buf);
+            n += 1;
+        }
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_read_vectored(cx, &mut our_bufs[..n]))?))
+    }
+}
+
+impl<T: AsyncBufRead> futures_io::AsyncBufRead for Compat<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_fill_buf(cx))?))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.inner_pin().consume(amt)
+    }
+}
+
+impl<T: AsyncWrite> futures_io::AsyncWrite for Compat<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_write(cx, buf))?))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        let mut our_bufs: [IoSlice<'_>; MAX_BUFS] = array::from_fn(|_| IoSlice::new(&[]));
+        let mut n = 0;
+        for (our_buf, buf) in our_bufs.iter_mut().zip(bufs.iter()) {
+            *our_buf = IoSlice::new(buf);
+            n += 1;
+        }
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_write_vectored(cx, &our_bufs[..n]))?))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_flush(cx))?))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_close(cx))?))
+    }
+}
+
+impl<T: AsyncSeek> futures_io::AsyncSeek for Compat<T> {
+    fn poll_seek(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        pos: std::io::SeekFrom,
+    ) -> Poll<std::io::Result<u64>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_seek(cx, from_std_seek_from(pos)))?))
+    }
+}
@@ -0,0 +1,117 @@
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+extern crate std;
+
+use core::pin::Pin;
+
+use super::Compat;
+use crate::async_io::{
+    AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt, block_on, copy,
+};
+use crate::{Cursor, Error, ErrorKind, IoSlice, SeekFrom};
+
+#[test]
+fn read_from_futures_io() {
+    // (`&[u8]` implements `futures_io::AsyncRead` & `futures_io::AsyncBufRead`)
+    let mut reader = Compat::new(&b"hello\nworld"[..]);
+
+    let mut buf = [0; 3];
+    block_on(reader.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"hel");
+
+    let mut line = String::new();
+    assert_eq!(block_on(reader.read_line(&mut line)).unwrap(), 3);
+    assert_eq!(line, "lo\n");
+
+    let mut rest = Vec::new();
+    assert_eq!(block_on(reader.read_to_end(&mut rest)).unwrap(), 5);
+    assert_eq!(rest, b"world");
+}
+
+#[test]
+fn write_to_futures_io() {
+    // (`Vec<u8>` implements `futures_io::AsyncWrite`)
+    let mut writer = Compat::new(Vec::new());
+    block_on(writer.write_all(b"hello")).unwrap();
+
+    let bufs = [IoSlice::new(b" "), IoSlice::new(b"world")];
+    let n =
+        block_on(core::future::poll_fn(|cx| Pin::new(&mut writer).poll_write_vectored(cx, &bufs)))
+            .unwrap();
+    assert_eq!(n, 6);
+
+    block_on(writer.flush()).unwrap();
+    block_on(writer.close()).unwrap();
+    assert_eq!(writer.into_inner(), b"hello world");
+}
+
+#[test]
+fn round_trip_through_futures_io() {
+    // (the inner `Compat` implements the traits of `futures-io` & the outer one
+    // implements the traits of this crate again)
+    let mut cursor = Compat::new(Compat::new(Cursor::new(Vec::new())));
+
+    block_on(cursor.write_all(b"hello world")).unwrap();
+    assert_eq!(block_on(cursor.seek(SeekFrom::Start(6))).unwrap(), 6);
+    let mut buf = [0; 5];
+    block_on(cursor.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"world");
+
+    assert_eq!(block_on(cursor.seek(SeekFrom::End(-5))).unwrap(), 6);
+    let mut writer = Compat::new(Compat::new(Vec::new()));
+    assert_eq!(block_on(copy(&mut cursor, &mut writer)).unwrap(), 5);
+    assert_eq!(writer.get_ref().get_ref(), b"world");
+}
+
+#[test]
+fn errors_through_futures_io() {
+    let mut reader = Compat::new(Compat::new(&b"abc"[..]));
+    let error = block_on(reader.read_exact(&mut [0; 4])).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+    struct FailingWriter;
+
+    impl futures_io::AsyncWrite for FailingWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
+            _buf: &[u8],
+        ) -> core::task::Poll<std::io::Result<usize>> {
+            core::task::Poll::Ready(Err(Error::new_const_with_code(
+                ErrorKind::StorageFull,
+                3,
+                &"full",
+            )
+            .into()))
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _cx: &mut core::task::Context<'_>,
+        ) -> core::task::Poll<std::io::Result<()>> {
+            #[allow(clippy::io_other_error)] // (`Error::other` is not in the nightly MSRV)
+            core::task::Poll::Ready(Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "flush failed",
+            )))
+        }
+
+        fn poll_close(
+            self: Pin<&mut Self>,
+            cx: &mut core::task::Context<'_>,
+        ) -> core::task::Poll<std::io::Result<()>> {
+            self.poll_flush(cx)
+        }
+    }
+
+    let mut writer = Compat::new(FailingWriter);
+    let error = block_on(writer.write_all(b"data")).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::StorageFull);
+    assert_eq!(error.code(), Some(3));
+
+    let error = block_on(writer.flush()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Other);
+    assert_eq!(alloc::format!("{}", error), "flush failed");
+}
@@ -0,0 +1,282 @@
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+#[cfg(feature = "alloc")]
+use super::AsyncBufReadExt;
+use super::{
+    AsyncBufRead, AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt, block_on, copy,
+    copy_buf,
+};
+use crate::{Cursor, Error, ErrorKind, IoSlice, IoSliceMut, ReadBuf, Result, SeekFrom};
+
+// A reader that is only ready on every other poll, returns at most 2 bytes at
+// a time & fails once with `Interrupted`, like a slow device would
+struct SlowReader<'a> {
+    data: &'a [u8],
+    ready: bool,
+    interrupted: bool,
+}
+
+impl<'a> SlowReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        SlowReader { data, ready: false, interrupted: false }
+    }
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if !self.ready {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.ready = false;
+        if !self.interrupted {
+            self.interrupted = true;
+            return Poll::Ready(Err(Error::from(ErrorKind::Interrupted)));
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncRead for SlowReader<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        core::task::ready!(self.poll_ready(cx))?;
+        let n = buf.remaining().min(self.data.len()).min(2);
+        buf.append(&self.data[..n]);
+        self.data = &self.data[n..];
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncBufRead for SlowReader<'_> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        let this = self.get_mut();
+        core::task::ready!(this.poll_ready(cx))?;
+        Poll::Ready(Ok(&this.data[..this.data.len().min(2)]))
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        self.data = &self.data[amt..];
+    }
+}
+
+// A writer that is only ready on every other poll & accepts at most 3 bytes at a time
+struct SlowWriter {
+    data: [u8; 64],
+    len: usize,
+    ready: bool,
+    flushed: bool,
+}
+
+impl SlowWriter {
+    fn new() -> Self {
+        SlowWriter { data: [0; 64], len: 0, ready: false, flushed: false }
+    }
+
+    fn written(&self) -> &[u8] {
+        &self.data[..self.len]
+    }
+}
+
+impl AsyncWrite for SlowWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize>> {
+        if !self.ready {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        self.ready = false;
+        let len = self.len;
+        let n = buf.len().min(self.data.len() - len).min(3);
+        self.data[len..len + n].copy_from_slice(&buf[..n]);
+        self.len += n;
+        self.flushed = false;
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.flushed = true;
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+#[test]
+fn read_and_read_exact() {
+    let mut reader = SlowReader::new(b"hello world");
+
+    let mut buf = [0; 8];
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap_err().kind(), ErrorKind::Interrupted);
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 2);
+    assert_eq!(&buf[..2], b"he");
+
+    block_on(reader.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"llo worl");
+
+    let error = block_on(reader.read_exact(&mut buf)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(buf[0], b'd');
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn read_to_end() {
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    let mut reader = SlowReader::new(&data);
+    let mut buf = alloc::vec![1, 2, 3];
+    assert_eq!(block_on(reader.read_to_end(&mut buf)).unwrap(), 1000);
+    assert_eq!(&buf[..3], [1, 2, 3]);
+    assert_eq!(&buf[3..], &data[..]);
+}
+
+#[test]
+fn write_all_flush_close() {
+    let mut writer = SlowWriter::new();
+
+    assert_eq!(block_on(writer.write(b"hello")).unwrap(), 3);
+    block_on(writer.write_all(b"lo world")).unwrap();
+    assert!(!writer.flushed);
+    block_on(writer.flush()).unwrap();
+    assert!(writer.flushed);
+    assert_eq!(writer.written(), b"hello world");
+
+    let mut storage = [0; 4];
+    let mut writer = Cursor::new(&mut storage[..]);
+    block_on(writer.write_all(b"abc")).unwrap();
+    let error = block_on(writer.write_all(b"de")).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::WriteZero);
+    block_on(writer.close()).unwrap();
+    assert_eq!(&storage, b"abcd");
+}
+
+#[test]
+fn seek_cursor() {
+    let mut cursor = Cursor::new(&b"hello"[..]);
+    assert_eq!(block_on(cursor.seek(SeekFrom::End(-2))).unwrap(), 3);
+    let mut buf = [0; 2];
+    block_on(cursor.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"lo");
+    assert_eq!(block_on(cursor.seek(SeekFrom::Current(-4))).unwrap(), 1);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn read_until_and_read_line() {
+    let mut reader = SlowReader::new(b"one,two\nthree");
+
+    let mut buf = Vec::new();
+    assert_eq!(block_on(reader.read_until(b',', &mut buf)).unwrap(), 4);
+    assert_eq!(buf, b"one,");
+
+    let mut line = String::from("> ");
+    assert_eq!(block_on(reader.read_line(&mut line)).unwrap(), 4);
+    assert_eq!(line, "> two\n");
+
+    line.clear();
+    assert_eq!(block_on(reader.read_line(&mut line)).unwrap(), 5);
+    assert_eq!(line, "three");
+    assert_eq!(block_on(reader.read_line(&mut line)).unwrap(), 0);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn read_line_invalid_utf8() {
+    let mut reader: &[u8] = b"ok\n\xff\n";
+    let mut line = String::new();
+    block_on(reader.read_line(&mut line)).unwrap();
+    let error = block_on(reader.read_line(&mut line)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert_eq!(line, "ok\n");
+}
+
+#[test]
+fn copy_between_slow_devices() {
+    let mut reader = SlowReader::new(b"from a slow reader");
+    let mut writer = SlowWriter::new();
+    assert_eq!(block_on(copy(&mut reader, &mut writer)).unwrap(), 18);
+    assert_eq!(writer.written(), b"from a slow reader");
+    assert!(writer.flushed);
+
+    let mut reader = SlowReader::new(b"buffered");
+    let mut writer = SlowWriter::new();
+    assert_eq!(block_on(copy_buf(&mut reader, &mut writer)).unwrap(), 8);
+    assert_eq!(writer.written(), b"buffered");
+    assert!(writer.flushed);
+}
+
+#[test]
+fn copy_write_zero() {
+    let mut reader: &[u8] = b"too long";
+    let mut storage = [0; 4];
+    let mut writer = Cursor::new(&mut storage[..]);
+    let error = block_on(copy(&mut reader, &mut writer)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::WriteZero);
+}
+
+#[test]
+fn vectored() {
+    let mut reader: &[u8] = b"hello";
+    let mut buf1 = [0; 2];
+    let mut buf2 = [0; 4];
+    let mut bufs = [IoSliceMut::new(&mut buf1), IoSliceMut::new(&mut buf2)];
+    let n = block_on(core::future::poll_fn(|cx| {
+        Pin::new(&mut reader).poll_read_vectored(cx, &mut bufs)
+    }))
+    .unwrap();
+    assert_eq!(n, 5);
+    assert_eq!(&buf1, b"he");
+    assert_eq!(&buf2[..3], b"llo");
+
+    // (the default implementations use the first non-empty buffer)
+    let mut reader = SlowReader::new(b"abc");
+    reader.interrupted = true;
+    let mut buf1 = [0; 0];
+    let mut buf2 = [0; 4];
+    let mut bufs = [IoSliceMut::new(&mut buf1), IoSliceMut::new(&mut buf2)];
+    let n = block_on(core::future::poll_fn(|cx| {
+        Pin::new(&mut reader).poll_read_vectored(cx, &mut bufs)
+    }))
+    .unwrap();
+    assert_eq!(n, 2);
+    assert_eq!(&buf2[..2], b"ab");
+
+    let mut writer = SlowWriter::new();
+    let bufs = [IoSlice::new(b""), IoSlice::new(b"xy")];
+    let n =
+        block_on(core::future::poll_fn(|cx| Pin::new(&mut writer).poll_write_vectored(cx, &bufs)))
+            .unwrap();
+    assert_eq!(n, 2);
+    assert_eq!(writer.written(), b"xy");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn boxed_and_pinned() {
+    use alloc::boxed::Box;
+
+    let mut reader: Pin<Box<dyn AsyncBufRead>> = Box::pin(&b"boxed"[..]);
+    let mut writer: Box<dyn AsyncWrite + Unpin> = Box::new(Vec::new());
+    assert_eq!(block_on(copy_buf(&mut reader, &mut writer)).unwrap(), 5);
+
+    let mut buf = [0; 4];
+    let mut reader = Box::new(crate::empty());
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 0);
+    assert_eq!(block_on(crate::sink().write(b"abc")).unwrap(), 3);
+}
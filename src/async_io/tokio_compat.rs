@@ -0,0 +1,220 @@
+//! Compatibility with the asynchronous traits of `tokio`.
+//!
+//! <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+//! <div class="warning">REQUIRES feature to be enabled: <code>tokio</code></div>
+
+// (ADDED in this fork) adapters between the async traits of this crate & the traits of `tokio`
+
+#[cfg(test)]
+mod tests;
+
+use core::array;
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
+
+extern crate std;
+
+use super::{AsyncBufRead, AsyncRead, AsyncWrite};
+use crate::std_io::MAX_BUFS;
+use crate::{IoSlice, ReadBuf, Result};
+
+/// Adapts a type between the async traits of this crate & the traits of
+/// `tokio`, in both directions.
+///
+/// `Compat<T>` implements [`AsyncRead`], [`AsyncWrite`] & [`AsyncBufRead`] of
+/// this crate if `T` implements the same trait from `tokio::io`, and it
+/// implements the traits of `tokio::io` if `T` implements the same trait of
+/// this crate. The [`ReadBuf`] of `tokio` is mapped onto the [`ReadBuf`] of
+/// this crate, so that no part of the buffer needs to be initialized again,
+/// and errors are converted between [`Error`](crate::Error) &
+/// [`std::io::Error`] (which is the error type of `tokio`).
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(feature = "tokio")] {
+/// use portable_io::async_io::tokio_compat::Compat;
+/// use portable_io::async_io::{AsyncWriteExt, block_on};
+///
+/// // (`Vec<u8>` implements `tokio::io::AsyncWrite`)
+/// let mut writer = Compat::new(Vec::new());
+/// block_on(writer.write_all(b"hello")).unwrap();
+/// assert_eq!(writer.get_ref(), b"hello");
+/// # }
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>tokio</code></div>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Compat<T> {
+    inner: T,
+}
+
+impl<T> Compat<T> {
+    /// Creates a new `Compat<T>`, wrapping the given value.
+    pub const fn new(inner: T) -> Compat<T> {
+        Compat { inner }
+    }
+
+    /// Gets a reference to the wrapped value.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the wrapped value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this `Compat<T>`, returning the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn inner_pin(self: Pin<&mut Self>) -> Pin<&mut T> {
+        // SAFETY: `inner` is pinned structurally - it is never moved out of a pinned `Compat<T>`
+        unsafe { self.map_unchecked_mut(|compat| &mut compat.inner) }
+    }
+}
+
+// From `tokio` to this crate
+
+impl<T: tokio::io::AsyncRead> AsyncRead for Compat<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        let already_init = buf.initialized_len() - buf.filled_len();
+        // SAFETY: the tokio `ReadBuf` never de-initializes any part of the buffer
+        let mut tokio_buf = tokio::io::ReadBuf::uninit(unsafe { buf.unfilled_mut() });
+        // SAFETY: these bytes were initialized, as tracked by our `ReadBuf`
+        unsafe { tokio_buf.assume_init(already_init) };
+        let ptr = tokio_buf.filled().as_ptr();
+
+        ready!(self.inner_pin().poll_read(cx, &mut tokio_buf))?;
+
+        // (a reader might have replaced the tokio `ReadBuf` with one for another buffer)
+        assert_eq!(ptr, tokio_buf.filled().as_ptr(), "tokio ReadBuf was swapped");
+        let filled = tokio_buf.filled().len();
+        let initialized = tokio_buf.initialized().len();
+        // SAFETY: the tokio `ReadBuf` tracked that these bytes were initialized
+        unsafe { buf.assume_init(initialized) };
+        buf.add_filled(filled);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: tokio::io::AsyncBufRead> AsyncBufRead for Compat<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_fill_buf(cx))?))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.inner_pin().consume(amt)
+    }
+}
+
+impl<T: tokio::io::AsyncWrite> AsyncWrite for Compat<T> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_write(cx, buf))?))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<Result<usize>> {
+        let mut std_bufs: [std::io::IoSlice<'_>; MAX_BUFS] =
+            array::from_fn(|_| std::io::IoSlice::new(&[]));
+        let mut n = 0;
+        for (std_buf, buf) in std_bufs.iter_mut().zip(bufs.iter()) {
+            *std_buf = std::io::IoSlice::new(buf);
+            n += 1;
+        }
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_write_vectored(cx, &std_bufs[..n]))?))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_flush(cx))?))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_shutdown(cx))?))
+    }
+}
+
+// From this crate to `tokio`
+
+impl<T: AsyncRead> tokio::io::AsyncRead for Compat<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        tokio_buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let already_init = tokio_buf.initialized().len() - tokio_buf.filled().len();
+        // SAFETY: our `ReadBuf` never de-initializes any part of the buffer
+        let mut buf = ReadBuf::uninit(unsafe { tokio_buf.unfilled_mut() });
+        // SAFETY: these bytes were initialized, as tracked by the tokio `ReadBuf`
+        unsafe { buf.assume_init(already_init) };
+
+        ready!(self.inner_pin().poll_read(cx, &mut buf))?;
+
+        let filled = buf.filled_len();
+        let initialized = buf.initialized_len();
+        // SAFETY: our `ReadBuf` tracked that these bytes were initialized
+        unsafe { tokio_buf.assume_init(initialized) };
+        tokio_buf.advance(filled);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: AsyncBufRead> tokio::io::AsyncBufRead for Compat<T> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_fill_buf(cx))?))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.inner_pin().consume(amt)
+    }
+}
+
+impl<T: AsyncWrite> tokio::io::AsyncWrite for Compat<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_write(cx, buf))?))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        let mut our_bufs: [IoSlice<'_>; MAX_BUFS] = array::from_fn(|_| IoSlice::new(&[]));
+        let mut n = 0;
+        for (our_buf, buf) in our_bufs.iter_mut().zip(bufs.iter()) {
+            *our_buf = IoSlice::new(buf);
+            n += 1;
+        }
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_write_vectored(cx, &our_bufs[..n]))?))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_flush(cx))?))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(ready!(self.inner_pin().poll_close(cx))?))
+    }
+}
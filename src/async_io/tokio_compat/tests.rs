@@ -0,0 +1,110 @@
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+extern crate std;
+
+use core::mem::MaybeUninit;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use super::Compat;
+use crate::async_io::{
+    AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, block_on, copy_buf,
+};
+use crate::{Error, ErrorKind, IoSlice, ReadBuf};
+
+#[test]
+fn read_from_tokio() {
+    // (`&[u8]` implements `tokio::io::AsyncRead` & `tokio::io::AsyncBufRead`)
+    let mut reader = Compat::new(&b"hello\nworld"[..]);
+
+    let mut buf = [0; 3];
+    block_on(reader.read_exact(&mut buf)).unwrap();
+    assert_eq!(&buf, b"hel");
+
+    let mut line = String::new();
+    assert_eq!(block_on(reader.read_line(&mut line)).unwrap(), 3);
+    assert_eq!(line, "lo\n");
+
+    let mut rest = Vec::new();
+    assert_eq!(block_on(reader.read_to_end(&mut rest)).unwrap(), 5);
+    assert_eq!(rest, b"world");
+}
+
+#[test]
+fn write_to_tokio() {
+    // (`Vec<u8>` implements `tokio::io::AsyncWrite`)
+    let mut writer = Compat::new(Vec::new());
+    block_on(writer.write_all(b"hello")).unwrap();
+    assert!(writer.is_write_vectored());
+
+    let bufs = [IoSlice::new(b" "), IoSlice::new(b"world")];
+    let n =
+        block_on(core::future::poll_fn(|cx| Pin::new(&mut writer).poll_write_vectored(cx, &bufs)))
+            .unwrap();
+    assert_eq!(n, 6);
+
+    block_on(writer.flush()).unwrap();
+    block_on(writer.close()).unwrap();
+    assert_eq!(writer.into_inner(), b"hello world");
+}
+
+#[test]
+fn read_buf_through_tokio() {
+    // (the inner `Compat` implements the traits of `tokio` & the outer one
+    // implements the traits of this crate again)
+    let mut reader = Compat::new(Compat::new(&b"hello world"[..]));
+
+    let mut storage = [MaybeUninit::uninit(); 8];
+    let mut buf = ReadBuf::uninit(&mut storage);
+    block_on(core::future::poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf))).unwrap();
+    assert_eq!(buf.filled(), b"hello wo");
+    assert_eq!(buf.initialized_len(), 8);
+
+    // (the part of the buffer that is already initialized is kept track of)
+    buf.clear();
+    block_on(core::future::poll_fn(|cx| Pin::new(&mut reader).poll_read(cx, &mut buf))).unwrap();
+    assert_eq!(buf.filled(), b"rld");
+    assert_eq!(buf.initialized_len(), 8);
+}
+
+#[test]
+fn copy_through_tokio() {
+    let mut reader = Compat::new(Compat::new(&b"from tokio"[..]));
+    let mut writer = Compat::new(Compat::new(Vec::new()));
+    assert_eq!(block_on(copy_buf(&mut reader, &mut writer)).unwrap(), 10);
+    assert_eq!(writer.get_ref().get_ref(), b"from tokio");
+}
+
+#[test]
+fn errors_through_tokio() {
+    struct FailingReader;
+
+    impl AsyncRead for FailingReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut ReadBuf<'_>,
+        ) -> Poll<crate::Result<()>> {
+            Poll::Ready(Err(Error::new_const_with_code(ErrorKind::TimedOut, 9, &"no answer")))
+        }
+    }
+
+    let mut reader = Compat::new(Compat::new(FailingReader));
+    let error = block_on(reader.read(&mut [0; 4])).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::TimedOut);
+    assert_eq!(error.code(), Some(9));
+
+    let error = block_on(core::future::poll_fn(|cx| {
+        let mut buf = [0; 4];
+        tokio::io::AsyncRead::poll_read(
+            Pin::new(reader.get_mut()),
+            cx,
+            &mut tokio::io::ReadBuf::new(&mut buf),
+        )
+    }))
+    .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::TimedOut);
+    assert_eq!(std::string::ToString::to_string(&error), "no answer (code 9)");
+}
@@ -0,0 +1,462 @@
+// (ADDED in this fork) support for vectors & strings of a fixed capacity, such
+// as those of the `heapless` & `arrayvec` crates, which do not use the heap
+
+#[cfg(test)]
+mod tests;
+
+use core::cmp;
+use core::mem::MaybeUninit;
+use core::slice;
+use core::str;
+
+use crate::{BufRead, Error, ErrorKind, Read, ReadBuf, Result};
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+use crate::{IoSlice, Write};
+
+/// A vector of bytes with a fixed capacity.
+///
+/// With the `heapless` & `arrayvec` features, this is implemented for
+/// `heapless::Vec<u8, N>` & `arrayvec::ArrayVec<u8, N>`, which then implement
+/// [`Write`](crate::Write), & for mutable references to any of them. A
+/// [`Cursor`](crate::Cursor) over a `BoundedVec` also implements `Write`.
+///
+/// Like for `&mut [u8]`, writing past the capacity returns short writes:
+/// ultimately, `Ok(0)`; in this situation, [`write_all`](crate::Write::write_all)
+/// returns an error of the kind [`ErrorKind::WriteZero`].
+///
+/// # Safety
+///
+/// [`spare_capacity_mut`](BoundedVec::spare_capacity_mut) must return the
+/// storage right after the bytes of the vector, up to the capacity, & after
+/// a call to [`set_len`](BoundedVec::set_len), the bytes of the vector must be
+/// the first `len` bytes of the storage.
+pub unsafe trait BoundedVec {
+    /// Returns the bytes in this vector.
+    fn as_slice(&self) -> &[u8];
+
+    /// Returns the bytes in this vector as a mutable slice.
+    fn as_mut_slice(&mut self) -> &mut [u8];
+
+    /// Returns the number of bytes this vector can hold.
+    fn capacity(&self) -> usize;
+
+    /// Returns the storage after the bytes in this vector, up to the
+    /// capacity, which may be uninitialized.
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>];
+
+    /// Sets the number of bytes in this vector.
+    ///
+    /// # Safety
+    ///
+    /// `len` must not be more than the capacity, & the first `len` bytes of
+    /// the storage must be initialized.
+    unsafe fn set_len(&mut self, len: usize);
+}
+
+unsafe impl<V: BoundedVec + ?Sized> BoundedVec for &mut V {
+    fn as_slice(&self) -> &[u8] {
+        (**self).as_slice()
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        (**self).as_mut_slice()
+    }
+
+    fn capacity(&self) -> usize {
+        (**self).capacity()
+    }
+
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        (**self).spare_capacity_mut()
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        (**self).set_len(len)
+    }
+}
+
+/// A string with a fixed capacity.
+///
+/// With the `heapless` & `arrayvec` features, this is implemented for
+/// `heapless::String<N>` & `arrayvec::ArrayString<N>`, & for mutable
+/// references to any of them.
+///
+/// # Safety
+///
+/// [`spare_capacity_mut`](BoundedString::spare_capacity_mut) must return the
+/// storage right after the bytes of the string, up to the capacity, & after a
+/// call to [`set_len`](BoundedString::set_len), the bytes of the string must
+/// be the first `len` bytes of the storage.
+pub unsafe trait BoundedString {
+    /// Returns the contents of this string.
+    fn as_str(&self) -> &str;
+
+    /// Returns the number of bytes this string can hold.
+    fn capacity(&self) -> usize;
+
+    /// Returns the storage after the bytes in this string, up to the
+    /// capacity, which may be uninitialized.
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>];
+
+    /// Sets the number of bytes in this string.
+    ///
+    /// # Safety
+    ///
+    /// `len` must not be more than the capacity, & the first `len` bytes of
+    /// the storage must be initialized & valid UTF-8.
+    unsafe fn set_len(&mut self, len: usize);
+}
+
+unsafe impl<S: BoundedString + ?Sized> BoundedString for &mut S {
+    fn as_str(&self) -> &str {
+        (**self).as_str()
+    }
+
+    fn capacity(&self) -> usize {
+        (**self).capacity()
+    }
+
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        (**self).spare_capacity_mut()
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        (**self).set_len(len)
+    }
+}
+
+fn storage_full() -> Error {
+    Error::new_const(ErrorKind::StorageFull, &"fixed-capacity buffer is full")
+}
+
+// Appends as many of the bytes as there is room for, returning how many
+pub(crate) fn append<V: BoundedVec + ?Sized>(vec: &mut V, buf: &[u8]) -> usize {
+    let len = vec.as_slice().len();
+    let mut spare = ReadBuf::uninit(vec.spare_capacity_mut());
+    let amt = cmp::min(buf.len(), spare.remaining());
+    spare.append(&buf[..amt]);
+    // SAFETY: the bytes were just appended to the spare capacity
+    unsafe { vec.set_len(len + amt) };
+    amt
+}
+
+// Writes the bytes at the given position, overwriting the bytes of the vector
+// & then appending, after zeros if the position is past the end
+pub(crate) fn write_at<V: BoundedVec + ?Sized>(vec: &mut V, pos: usize, buf: &[u8]) -> usize {
+    let len = vec.as_slice().len();
+    if pos > vec.capacity() {
+        return 0;
+    }
+    if pos > len {
+        let mut spare = ReadBuf::uninit(vec.spare_capacity_mut());
+        spare.initialize_unfilled_to(pos - len);
+        // SAFETY: the bytes up to `pos` were just zeroed
+        unsafe { vec.set_len(pos) };
+    }
+    let slice = &mut vec.as_mut_slice()[pos..];
+    let overwritten = cmp::min(slice.len(), buf.len());
+    slice[..overwritten].copy_from_slice(&buf[..overwritten]);
+    overwritten + append(vec, &buf[overwritten..])
+}
+
+// Reads into the spare capacity until the delimiter (included) or EOF, returning
+// the number of bytes that were read, with the error that stopped it if any
+fn read_into_spare<R: BufRead + ?Sized>(
+    r: &mut R,
+    spare: &mut [MaybeUninit<u8>],
+    delim: Option<u8>,
+) -> (usize, Result<()>) {
+    let mut buf = ReadBuf::uninit(spare);
+    loop {
+        let available = match r.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return (buf.filled_len(), Err(e)),
+        };
+        let (done, used) = match delim.and_then(|delim| memchr::memchr(delim, available)) {
+            Some(i) => (true, i + 1),
+            None => (available.is_empty(), available.len()),
+        };
+        let amt = cmp::min(used, buf.remaining());
+        buf.append(&available[..amt]);
+        r.consume(amt);
+        if amt < used {
+            return (buf.filled_len(), Err(storage_full()));
+        }
+        if done {
+            return (buf.filled_len(), Ok(()));
+        }
+    }
+}
+
+/// Extends [`Read`] with a method to read all of the bytes until EOF into a
+/// vector of a fixed capacity, like [`read_to_end`](Read::read_to_end) does
+/// with a `Vec<u8>`.
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(feature = "heapless")] {
+/// use portable_io::{ErrorKind, ReadBoundedExt};
+///
+/// let mut buf: heapless::Vec<u8, 8> = heapless::Vec::new();
+/// let mut reader = &b"some"[..];
+/// assert_eq!(reader.read_to_end_bounded(&mut buf).unwrap(), 4);
+/// assert_eq!(&buf[..], b"some");
+///
+/// let mut reader = &b" more data"[..];
+/// let error = reader.read_to_end_bounded(&mut buf).unwrap_err();
+/// assert_eq!(error.kind(), ErrorKind::StorageFull);
+/// assert_eq!(&buf[..], b"some mor");
+/// # }
+/// ```
+pub trait ReadBoundedExt: Read {
+    /// Reads all of the bytes until EOF, appending them to `buf`, & returns
+    /// the number of bytes that were read.
+    ///
+    /// If the capacity of `buf` is reached before EOF, an error of the kind
+    /// [`ErrorKind::StorageFull`] is returned, with the bytes that fit
+    /// appended to `buf`; the rest of the bytes are left in the reader. As
+    /// nothing is read beyond the capacity of `buf`, this error is returned
+    /// too if the bytes fit exactly, & the next read then gets EOF.
+    fn read_to_end_bounded<V: BoundedVec + ?Sized>(&mut self, buf: &mut V) -> Result<usize> {
+        let start_len = buf.as_slice().len();
+        loop {
+            let len = buf.as_slice().len();
+            let mut read_buf = ReadBuf::uninit(buf.spare_capacity_mut());
+            if read_buf.remaining() == 0 {
+                return Err(storage_full());
+            }
+            let result = self.read_buf(&mut read_buf);
+            let filled = read_buf.filled_len();
+            // SAFETY: `ReadBuf`'s invariants mean that the bytes that were filled are initialized
+            unsafe { buf.set_len(len + filled) };
+            match result {
+                Ok(()) if filled == 0 => return Ok(len - start_len),
+                Ok(()) => {}
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<R: Read + ?Sized> ReadBoundedExt for R {}
+
+/// Extends [`BufRead`] with methods to read into a vector or a string of a
+/// fixed capacity, like [`read_until`](BufRead::read_until) &
+/// [`read_line`](BufRead::read_line) do with a `Vec<u8>` & a `String`.
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(feature = "arrayvec")] {
+/// use portable_io::BufReadBoundedExt;
+///
+/// let mut line: arrayvec::ArrayString<16> = arrayvec::ArrayString::new();
+/// let mut reader = &b"first line\nsecond line\n"[..];
+/// assert_eq!(reader.read_line_bounded(&mut line).unwrap(), 11);
+/// assert_eq!(line.as_str(), "first line\n");
+/// # }
+/// ```
+pub trait BufReadBoundedExt: BufRead {
+    /// Reads all of the bytes until the delimiter `byte` or EOF, appending
+    /// them to `buf`, & returns the number of bytes that were read.
+    ///
+    /// If the capacity of `buf` is reached before the delimiter, an error of
+    /// the kind [`ErrorKind::StorageFull`] is returned, with the bytes that
+    /// fit appended to `buf`; the rest of the bytes up to the delimiter are
+    /// left in the reader.
+    fn read_until_bounded<V: BoundedVec + ?Sized>(
+        &mut self,
+        byte: u8,
+        buf: &mut V,
+    ) -> Result<usize> {
+        let len = buf.as_slice().len();
+        let (n, result) = read_into_spare(self, buf.spare_capacity_mut(), Some(byte));
+        // SAFETY: the bytes that were read were appended to the spare capacity
+        unsafe { buf.set_len(len + n) };
+        result.map(|()| n)
+    }
+
+    /// Reads all of the bytes until a newline (the `0xA` byte) or EOF,
+    /// appending them to `buf`, & returns the number of bytes that were read.
+    ///
+    /// If the bytes are not valid UTF-8, an error of the kind
+    /// [`ErrorKind::InvalidData`] is returned & `buf` is left unchanged. If
+    /// the capacity of `buf` is reached before the newline, an error of the
+    /// kind [`ErrorKind::StorageFull`] is returned; as with any other error
+    /// while reading, the bytes that were read up to the last whole character
+    /// are appended to `buf`, & the bytes of a character that was cut are
+    /// lost.
+    fn read_line_bounded<S: BoundedString + ?Sized>(&mut self, buf: &mut S) -> Result<usize> {
+        let len = buf.as_str().len();
+        let spare = buf.spare_capacity_mut();
+        let (n, result) = read_into_spare(self, spare, Some(b'\n'));
+        // SAFETY: the first `n` bytes of the spare capacity were just filled
+        let appended = unsafe { slice::from_raw_parts(spare.as_ptr() as *const u8, n) };
+        let valid = match (str::from_utf8(appended), result) {
+            (Ok(_), Ok(())) => n,
+            (Err(_), Ok(())) => {
+                return Err(Error::new_const(
+                    ErrorKind::InvalidData,
+                    &"stream did not contain valid UTF-8",
+                ));
+            }
+            // (the bytes that were read are kept, up to the last whole
+            // character, as `read_line` does)
+            (utf8, Err(e)) => {
+                let valid = utf8.map_or_else(|e| e.valid_up_to(), |_| n);
+                // SAFETY: the first `valid` bytes that were appended are valid UTF-8
+                unsafe { buf.set_len(len + valid) };
+                return Err(e);
+            }
+        };
+        // SAFETY: the bytes that were appended are valid UTF-8
+        unsafe { buf.set_len(len + valid) };
+        Ok(n)
+    }
+}
+
+impl<R: BufRead + ?Sized> BufReadBoundedExt for R {}
+
+#[cfg(feature = "heapless")]
+unsafe impl<const N: usize> BoundedVec for heapless::Vec<u8, N> {
+    fn as_slice(&self) -> &[u8] {
+        heapless::Vec::as_slice(self)
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        heapless::Vec::as_mut_slice(self)
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        let len = self.len();
+        // SAFETY: the storage has room for `N` bytes
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr().add(len).cast(), N - len) }
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        heapless::Vec::set_len(self, len)
+    }
+}
+
+#[cfg(feature = "heapless")]
+unsafe impl<const N: usize> BoundedString for heapless::String<N> {
+    fn as_str(&self) -> &str {
+        heapless::String::as_str(self)
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        // SAFETY: the bytes that are not valid UTF-8 are not part of the string
+        unsafe { BoundedVec::spare_capacity_mut(self.as_mut_vec()) }
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        heapless::Vec::set_len(self.as_mut_vec(), len)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+unsafe impl<const N: usize> BoundedVec for arrayvec::ArrayVec<u8, N> {
+    fn as_slice(&self) -> &[u8] {
+        arrayvec::ArrayVec::as_slice(self)
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        arrayvec::ArrayVec::as_mut_slice(self)
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        let len = self.len();
+        // SAFETY: the storage has room for `N` bytes
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr().add(len).cast(), N - len) }
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        arrayvec::ArrayVec::set_len(self, len)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+unsafe impl<const N: usize> BoundedString for arrayvec::ArrayString<N> {
+    fn as_str(&self) -> &str {
+        arrayvec::ArrayString::as_str(self)
+    }
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        let len = self.len();
+        // SAFETY: the storage has room for `N` bytes
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr().add(len).cast(), N - len) }
+    }
+
+    unsafe fn set_len(&mut self, len: usize) {
+        arrayvec::ArrayString::set_len(self, len)
+    }
+}
+
+// `Write` for a vector of a fixed capacity, with short writes like `&mut [u8]`
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+macro_rules! bounded_vec_write {
+    ($ty:ty) => {
+        impl<const N: usize> Write for $ty {
+            #[inline]
+            fn write(&mut self, buf: &[u8]) -> Result<usize> {
+                Ok(append(self, buf))
+            }
+
+            #[inline]
+            fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+                let mut nwritten = 0;
+                for buf in bufs {
+                    let n = append(self, buf);
+                    nwritten += n;
+                    if n < buf.len() {
+                        break;
+                    }
+                }
+                Ok(nwritten)
+            }
+
+            #[inline]
+            fn is_write_vectored(&self) -> bool {
+                true
+            }
+
+            #[inline]
+            fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+                if append(self, buf) == buf.len() {
+                    Ok(())
+                } else {
+                    Err(Error::new_const(ErrorKind::WriteZero, &"failed to write whole buffer"))
+                }
+            }
+
+            #[inline]
+            fn flush(&mut self) -> Result<()> {
+                Ok(())
+            }
+        }
+    };
+}
+
+#[cfg(feature = "heapless")]
+bounded_vec_write!(heapless::Vec<u8, N>);
+#[cfg(feature = "arrayvec")]
+bounded_vec_write!(arrayvec::ArrayVec<u8, N>);
@@ -0,0 +1,160 @@
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+use super::{BufReadBoundedExt, ReadBoundedExt};
+#[cfg(feature = "arrayvec")]
+use crate::test_util::{Flaky, retry};
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+use crate::{BufReader, Cursor, ErrorKind, IoSlice, Read, Seek, SeekFrom, Write};
+
+// (a reader that returns at most 3 bytes at a time, to check the loops)
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+struct Chunked<'a>(&'a [u8]);
+
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+impl Read for Chunked<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> crate::Result<usize> {
+        let len = buf.len().min(3);
+        self.0.read(&mut buf[..len])
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn heapless_write() {
+    let mut vec: heapless::Vec<u8, 8> = heapless::Vec::new();
+    assert_eq!(vec.write(b"some").unwrap(), 4);
+    let bufs = [IoSlice::new(b" "), IoSlice::new(b""), IoSlice::new(b"data")];
+    assert_eq!(vec.write_vectored(&bufs).unwrap(), 4);
+    assert_eq!(vec.write(b"a").unwrap(), 0);
+    assert_eq!(&vec[..], b"some dat");
+
+    vec.clear();
+    vec.write_all(b"1234").unwrap();
+    assert_eq!(vec.write_all(b"56789").unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(&vec[..], b"12345678");
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn arrayvec_write() {
+    let mut vec: arrayvec::ArrayVec<u8, 8> = arrayvec::ArrayVec::new();
+    assert_eq!(vec.write(b"some").unwrap(), 4);
+    let bufs = [IoSlice::new(b" "), IoSlice::new(b""), IoSlice::new(b"data")];
+    assert_eq!(vec.write_vectored(&bufs).unwrap(), 4);
+    assert_eq!(vec.write(b"a").unwrap(), 0);
+    assert_eq!(&vec[..], b"some dat");
+    assert_eq!(vec.write_all(b"!").unwrap_err().kind(), ErrorKind::WriteZero);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn heapless_cursor() {
+    let mut cursor = Cursor::new(heapless::Vec::<u8, 8>::new());
+    cursor.write_all(b"abcd").unwrap();
+    cursor.seek(SeekFrom::Start(2)).unwrap();
+    assert_eq!(cursor.write(b"CDEF").unwrap(), 4);
+    assert_eq!(&cursor.get_ref()[..], b"abCDEF");
+
+    // (zeros up to the position, & short writes at the capacity)
+    cursor.set_position(7);
+    assert_eq!(cursor.write(b"xy").unwrap(), 1);
+    assert_eq!(&cursor.get_ref()[..], b"abCDEF\0x");
+    assert_eq!(cursor.write(b"z").unwrap(), 0);
+    cursor.set_position(20);
+    assert_eq!(cursor.write(b"z").unwrap(), 0);
+    assert_eq!(cursor.position(), 20);
+
+    let mut vec: heapless::Vec<u8, 4> = heapless::Vec::new();
+    let mut cursor = Cursor::new(&mut vec);
+    let bufs = [IoSlice::new(b"ab"), IoSlice::new(b"cde")];
+    assert_eq!(cursor.write_vectored(&bufs).unwrap(), 4);
+    assert_eq!(&vec[..], b"abcd");
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn arrayvec_cursor() {
+    let mut vec: arrayvec::ArrayVec<u8, 6> = arrayvec::ArrayVec::new();
+    let mut cursor = Cursor::new(&mut vec);
+    cursor.write_all(b"abc").unwrap();
+    cursor.seek(SeekFrom::Current(-1)).unwrap();
+    assert_eq!(cursor.write_all(b"CDEFG").unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(cursor.position(), 6);
+    assert_eq!(&vec[..], b"abCDEF");
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn heapless_read_to_end_and_read_line() {
+    let mut vec: heapless::Vec<u8, 8> = heapless::Vec::new();
+    vec.push(b'>').unwrap();
+    assert_eq!(Chunked(b"123456").read_to_end_bounded(&mut vec).unwrap(), 6);
+    assert_eq!(&vec[..], b">123456");
+
+    // (more bytes than fit, which are left in the reader)
+    vec.truncate(1);
+    let mut reader = Chunked(b"123456789");
+    assert_eq!(reader.read_to_end_bounded(&mut vec).unwrap_err().kind(), ErrorKind::StorageFull);
+    assert_eq!(&vec[..], b">1234567");
+    vec.clear();
+    assert_eq!(reader.read_to_end_bounded(&mut vec).unwrap(), 2);
+    assert_eq!(&vec[..], b"89");
+
+    let mut line: heapless::String<8> = heapless::String::new();
+    let mut storage = [0; 2];
+    let mut reader = BufReader::with_slice(&mut storage, &b"one\ntwo"[..]);
+    assert_eq!(reader.read_line_bounded(&mut line).unwrap(), 4);
+    assert_eq!(reader.read_line_bounded(&mut line).unwrap(), 3);
+    assert_eq!(reader.read_line_bounded(&mut line).unwrap(), 0);
+    assert_eq!(line.as_str(), "one\ntwo");
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn arrayvec_read_until_and_read_line() {
+    let mut storage = [0; 3];
+    let mut reader = BufReader::with_slice(&mut storage, &b"key=value;more"[..]);
+    let mut vec: arrayvec::ArrayVec<u8, 8> = arrayvec::ArrayVec::new();
+    assert_eq!(reader.read_until_bounded(b'=', &mut vec).unwrap(), 4);
+    assert_eq!(&vec[..], b"key=");
+
+    // (the rest of the bytes up to the delimiter are left in the reader)
+    vec.clear();
+    assert_eq!(
+        reader.read_until_bounded(b'=', &mut vec).unwrap_err().kind(),
+        ErrorKind::StorageFull
+    );
+    assert_eq!(&vec[..], b"value;mo");
+    vec.clear();
+    assert_eq!(reader.read_until_bounded(b'=', &mut vec).unwrap(), 2);
+    assert_eq!(&vec[..], b"re");
+    // (an exact fit, which is only told from a longer stream by reading again)
+    let mut reader = Chunked(b"123456");
+    assert_eq!(reader.read_to_end_bounded(&mut vec).unwrap_err().kind(), ErrorKind::StorageFull);
+    assert_eq!(&vec[..], b"re123456");
+    assert_eq!(reader.read(&mut [0; 1]).unwrap(), 0);
+
+    let mut line: arrayvec::ArrayString<4> = arrayvec::ArrayString::new();
+    let mut reader = &b"long line\n"[..];
+    assert_eq!(reader.read_line_bounded(&mut line).unwrap_err().kind(), ErrorKind::StorageFull);
+    assert_eq!(line.as_str(), "long");
+    // (the bytes of a character that was cut are lost)
+    let mut line: arrayvec::ArrayString<3> = arrayvec::ArrayString::new();
+    let mut reader = "caña\n".as_bytes();
+    assert_eq!(reader.read_line_bounded(&mut line).unwrap_err().kind(), ErrorKind::StorageFull);
+    assert_eq!(line.as_str(), "ca");
+
+    // (the bytes that were read before an I/O error are kept)
+    let mut line: arrayvec::ArrayString<8> = arrayvec::ArrayString::new();
+    let mut reader = Flaky::new(&b"ok\n"[..]);
+    assert_eq!(reader.read_line_bounded(&mut line).unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(reader.read_line_bounded(&mut line).unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(line.as_str(), "o");
+    assert_eq!(retry(|| reader.read_line_bounded(&mut line)).unwrap(), 1);
+    assert_eq!(line.as_str(), "ok\n");
+
+    let mut line: arrayvec::ArrayString<8> = arrayvec::ArrayString::new();
+    let mut reader = &b"ok\n\xff\n"[..];
+    assert_eq!(reader.read_line_bounded(&mut line).unwrap(), 3);
+    assert_eq!(reader.read_line_bounded(&mut line).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(line.as_str(), "ok\n");
+}
@@ -0,0 +1,208 @@
+//! Buffering wrappers for I/O traits
+
+#[cfg(test)]
+mod tests;
+
+mod bufreader;
+mod bufwriter;
+mod linewriter;
+mod linewritershim;
+
+use core::error;
+use core::fmt;
+use core::mem::MaybeUninit;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::Error;
+
+pub use self::bufreader::BufReader;
+pub use self::bufwriter::BufWriter;
+#[cfg(feature = "alloc")]
+pub use self::bufwriter::WriterPanicked;
+pub use self::linewriter::LineWriter;
+use self::linewritershim::LineWriterShim;
+
+/// Storage for the internal buffer of the buffering wrappers in this crate.
+///
+/// This is implemented for any type that can be viewed as a slice of
+/// (possibly uninitialized) bytes, for example:
+///
+/// - `Box<[MaybeUninit<u8>]>` - heap storage (used by default)
+/// - `&mut [MaybeUninit<u8>]` - storage owned by the caller
+/// - `[MaybeUninit<u8>; N]` - inline storage, with no use of `alloc`
+///
+/// The buffering wrappers never de-initialize bytes in this storage.
+pub trait BufferStorage: AsRef<[MaybeUninit<u8>]> + AsMut<[MaybeUninit<u8>]> {}
+
+impl<T> BufferStorage for T where T: AsRef<[MaybeUninit<u8>]> + AsMut<[MaybeUninit<u8>]> + ?Sized {}
+
+/// Allocates the default heap storage with the given capacity.
+// (ADAPTED to avoid using `Box::new_uninit_slice`, which is not available with MSRV)
+#[cfg(feature = "alloc")]
+fn new_uninit_slice(capacity: usize) -> Box<[MaybeUninit<u8>]> {
+    let mut buf = Vec::with_capacity(capacity);
+    // SAFETY: `MaybeUninit<u8>` does not require initialization
+    unsafe {
+        buf.set_len(capacity);
+    }
+    buf.into_boxed_slice()
+}
+
+/// An error returned by [`BufWriter::into_inner`] which combines an error that
+/// happened while writing out the buffer, and the buffered writer object
+/// which may be used to recover from the condition.
+///
+/// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+/// # Example code
+///
+/// ```
+/// use portable_io::{BufWriter, Write};
+///
+/// let mut storage = [0u8; 4];
+/// let mut stream = BufWriter::new(&mut storage[..]);
+///
+/// // do stuff with the stream
+/// stream.write_all(b"too much data").unwrap();
+///
+/// // we want to get our `&mut [u8]` back, but the buffered data does not fit
+/// let stream = match stream.into_inner() {
+///     Ok(s) => s,
+///     Err(e) => {
+///         // Here, e is an IntoInnerError
+///         assert_eq!(e.error().kind(), portable_io::ErrorKind::WriteZero);
+///         return;
+///     }
+/// };
+/// # unreachable!("{:?}", stream);
+/// ```
+#[derive(Debug)]
+pub struct IntoInnerError<W>(W, Error);
+
+impl<W> IntoInnerError<W> {
+    /// Construct a new IntoInnerError
+    fn new(writer: W, error: Error) -> Self {
+        Self(writer, error)
+    }
+
+    /// Helper to construct a new IntoInnerError; intended to help with
+    /// adapters that wrap other adapters
+    fn new_wrapped<W2>(self, f: impl FnOnce(W) -> W2) -> IntoInnerError<W2> {
+        let Self(writer, error) = self;
+        IntoInnerError::new(f(writer), error)
+    }
+
+    /// Returns the error which caused the call to [`BufWriter::into_inner()`]
+    /// to fail.
+    ///
+    /// This error was returned when attempting to write the internal buffer.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufWriter, ErrorKind, Write};
+    ///
+    /// let mut storage = [0u8; 4];
+    /// let mut stream = BufWriter::new(&mut storage[..]);
+    /// stream.write_all(b"too much data").unwrap();
+    ///
+    /// let e = stream.into_inner().unwrap_err();
+    /// assert_eq!(e.error().kind(), ErrorKind::WriteZero);
+    /// ```
+    pub fn error(&self) -> &Error {
+        &self.1
+    }
+
+    /// Returns the buffered writer instance which generated the error.
+    ///
+    /// The returned object can be used for error recovery, such as
+    /// re-inspecting the buffer.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufWriter, Write};
+    ///
+    /// let mut storage = [0u8; 4];
+    /// let mut stream = BufWriter::new(&mut storage[..]);
+    /// stream.write_all(b"too much data").unwrap();
+    ///
+    /// let e = stream.into_inner().unwrap_err();
+    /// let stream = e.into_inner();
+    /// assert_eq!(stream.buffer(), b"much data");
+    /// ```
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+
+    /// Consumes the [`IntoInnerError`] and returns the error which caused the call to
+    /// [`BufWriter::into_inner()`] to fail.  Unlike `error`, this can be used to
+    /// obtain ownership of the underlying error.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufWriter, ErrorKind, Write};
+    ///
+    /// let mut not_enough_space = [0u8; 10];
+    /// let mut stream = BufWriter::new(&mut not_enough_space[..]);
+    /// write!(stream, "this cannot be actually written").unwrap();
+    /// let into_inner_err = stream.into_inner().expect_err("now we discover it's too small");
+    /// let err = into_inner_err.into_error();
+    /// assert_eq!(err.kind(), ErrorKind::WriteZero);
+    /// ```
+    pub fn into_error(self) -> Error {
+        self.1
+    }
+
+    /// Consumes the [`IntoInnerError`] and returns the error which caused the call to
+    /// [`BufWriter::into_inner()`] to fail, and the underlying writer.
+    ///
+    /// This can be used to simply obtain ownership of the underlying error; it can also be used for
+    /// advanced error recovery.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufWriter, ErrorKind, Write};
+    ///
+    /// let mut not_enough_space = [0u8; 10];
+    /// let mut stream = BufWriter::new(&mut not_enough_space[..]);
+    /// write!(stream, "this cannot be actually written").unwrap();
+    /// let into_inner_err = stream.into_inner().expect_err("now we discover it's too small");
+    /// let (err, recovered_writer) = into_inner_err.into_parts();
+    /// assert_eq!(err.kind(), ErrorKind::WriteZero);
+    /// assert_eq!(recovered_writer.buffer(), b"t be actually written");
+    /// ```
+    pub fn into_parts(self) -> (Error, W) {
+        (self.1, self.0)
+    }
+}
+
+impl<W> From<IntoInnerError<W>> for Error {
+    fn from(iie: IntoInnerError<W>) -> Error {
+        iie.1
+    }
+}
+
+impl<W: Send + fmt::Debug> error::Error for IntoInnerError<W> {
+    #[allow(deprecated, deprecated_in_future)]
+    fn description(&self) -> &str {
+        error::Error::description(self.error())
+    }
+}
+
+impl<W> fmt::Display for IntoInnerError<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error().fmt(f)
+    }
+}
@@ -0,0 +1,572 @@
+use core::cmp;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::slice;
+#[cfg(feature = "alloc")]
+use core::str;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(portable_io_unstable_all)] // for unstable feature: size hint optimization
+use crate::SizeHint;
+use crate::{self as io, BufRead, IoSliceMut, Read, ReadBuf, Seek, SeekFrom, default_read_exact};
+#[cfg(feature = "alloc")]
+use crate::{DEFAULT_BUF_SIZE, append_to_string};
+
+use super::BufferStorage;
+#[cfg(feature = "alloc")]
+use super::new_uninit_slice;
+
+/// The `BufReader<R>` struct adds buffering to any reader.
+///
+/// It can be excessively inefficient to work directly with a [`Read`] instance.
+/// For example, every call to [`read`][`Read::read`] on a device driver may
+/// result in a separate request to the hardware. A `BufReader<R>` performs
+/// large, infrequent reads on the underlying [`Read`] and maintains an
+/// in-memory buffer of the results.
+///
+/// `BufReader<R>` can improve the speed of programs that make *small* and
+/// *repeated* read calls to the same device. It does not help when reading
+/// very large amounts at once, or reading just one or a few times. It also
+/// provides no advantage when reading from a source that is already in memory,
+/// like a <code>[Vec]\<u8></code>.
+///
+/// When the `BufReader<R>` is dropped, the contents of its buffer will be
+/// discarded. Creating multiple instances of a `BufReader<R>` on the same
+/// stream can cause data loss. Reading from the underlying reader after
+/// unwrapping the `BufReader<R>` with [`BufReader::into_inner`] can also cause
+/// data loss.
+///
+/// The internal buffer is stored in `B`, which may be any [`BufferStorage`]:
+/// a heap allocation (the default, with the `alloc` feature), a slice supplied by the caller (see
+/// [`BufReader::with_slice`]), or an inline array (see
+/// [`BufReader::with_array`]). Bytes of the buffer are never zeroed before
+/// use: the buffer is filled by way of [`Read::read_buf`].
+///
+/// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+/// # Example code
+///
+/// ```
+/// use portable_io::{self as io, BufRead, BufReader};
+///
+/// fn main() -> io::Result<()> {
+///     let mut reader = BufReader::new(&b"first line\nsecond line\n"[..]);
+///
+///     let mut line = String::new();
+///     let len = reader.read_line(&mut line)?;
+///     assert_eq!(len, 11);
+///     assert_eq!(line, "first line\n");
+///     Ok(())
+/// }
+/// ```
+// (ADAPTED to only default to heap storage when the `alloc` feature is enabled)
+pub struct BufReader<
+    R,
+    #[cfg(feature = "alloc")] B = Box<[MaybeUninit<u8>]>,
+    #[cfg(not(feature = "alloc"))] B,
+> {
+    inner: R,
+    buf: B,
+    pos: usize,
+    cap: usize,
+    init: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<R: Read> BufReader<R> {
+    /// Creates a new `BufReader<R>` with a default buffer capacity. The default is currently 8 KB,
+    /// but may change in the future.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::BufReader;
+    ///
+    /// let reader = BufReader::new(&b"some data"[..]);
+    /// assert_eq!(reader.capacity(), 8 * 1024);
+    /// ```
+    pub fn new(inner: R) -> BufReader<R> {
+        BufReader::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a new `BufReader<R>` with the specified buffer capacity.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// Creating a buffer with ten bytes of capacity:
+    ///
+    /// ```
+    /// use portable_io::BufReader;
+    ///
+    /// let reader = BufReader::with_capacity(10, &b"some data"[..]);
+    /// assert_eq!(reader.capacity(), 10);
+    /// ```
+    pub fn with_capacity(capacity: usize, inner: R) -> BufReader<R> {
+        BufReader::with_buffer(new_uninit_slice(capacity), inner)
+    }
+}
+
+impl<'a, R: Read> BufReader<R, &'a mut [MaybeUninit<u8>]> {
+    /// Creates a new `BufReader<R>` that uses a slice supplied by the caller
+    /// as its buffer.
+    ///
+    /// The capacity of the buffer is the length of the slice. The contents of
+    /// the slice after the `BufReader<R>` is dropped are unspecified.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufRead, BufReader};
+    ///
+    /// let mut storage = [0; 4];
+    /// let mut reader = BufReader::with_slice(&mut storage, &b"some data"[..]);
+    /// assert_eq!(reader.fill_buf().unwrap(), b"some");
+    /// ```
+    pub fn with_slice(buf: &'a mut [u8], inner: R) -> Self {
+        let len = buf.len();
+        let buf_ptr = buf as *mut [u8];
+
+        BufReader {
+            inner,
+            // SAFETY: initialized bytes are never de-initialized by `BufReader`, and the
+            // buffer is never exposed in its `MaybeUninit` form
+            buf: unsafe { slice::from_raw_parts_mut(buf_ptr as *mut MaybeUninit<u8>, len) },
+            pos: 0,
+            cap: 0,
+            init: len,
+        }
+    }
+}
+
+impl<R: Read, const N: usize> BufReader<R, [MaybeUninit<u8>; N]> {
+    /// Creates a new `BufReader<R>` with an inline buffer of `N` bytes.
+    ///
+    /// This does not use the `alloc` crate. The buffer is stored within the
+    /// `BufReader<R>` itself, so large values of `N` should be used with care
+    /// on targets with a small stack.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use portable_io::{BufRead, BufReader};
+    ///
+    /// let mut reader: BufReader<_, [MaybeUninit<u8>; 4]> = BufReader::with_array(&b"some data"[..]);
+    /// assert_eq!(reader.capacity(), 4);
+    /// assert_eq!(reader.fill_buf().unwrap(), b"some");
+    /// ```
+    pub fn with_array(inner: R) -> Self {
+        BufReader::with_buffer([MaybeUninit::uninit(); N], inner)
+    }
+}
+
+impl<R: Read, B: BufferStorage> BufReader<R, B> {
+    /// Creates a new `BufReader<R>` that uses the given storage as its buffer.
+    ///
+    /// The capacity of the buffer is the length of the storage.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use portable_io::{BufRead, BufReader};
+    ///
+    /// let mut storage = [MaybeUninit::uninit(); 4];
+    /// let mut reader = BufReader::with_buffer(&mut storage[..], &b"some data"[..]);
+    /// assert_eq!(reader.fill_buf().unwrap(), b"some");
+    /// ```
+    pub fn with_buffer(buf: B, inner: R) -> Self {
+        BufReader { inner, buf, pos: 0, cap: 0, init: 0 }
+    }
+}
+
+impl<R, B: BufferStorage> BufReader<R, B> {
+    /// Gets a reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::BufReader;
+    ///
+    /// let reader = BufReader::new(&b"some data"[..]);
+    /// assert_eq!(*reader.get_ref(), b"some data");
+    /// ```
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    ///
+    /// <!-- TODO ADD EXAMPLE CODE THAT DOES NOT USE FS -->
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns a reference to the internally buffered data.
+    ///
+    /// Unlike [`fill_buf`], this will not attempt to fill the buffer if it is empty.
+    ///
+    /// [`fill_buf`]: BufRead::fill_buf
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufRead, BufReader};
+    ///
+    /// let mut reader = BufReader::new(&b"some data"[..]);
+    /// assert!(reader.buffer().is_empty());
+    ///
+    /// if reader.fill_buf().unwrap().len() > 0 {
+    ///     assert!(!reader.buffer().is_empty());
+    /// }
+    /// ```
+    pub fn buffer(&self) -> &[u8] {
+        // SAFETY: self.cap is always <= self.init, so self.buf[self.pos..self.cap] is always init
+        // (ADAPTED to avoid using unstable fn)
+        let buffer_ptr = &self.buf.as_ref()[self.pos..self.cap] as *const [MaybeUninit<u8>];
+        unsafe { &*(buffer_ptr as *const [u8]) }
+    }
+
+    /// Returns the number of bytes the internal buffer can hold at once.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufRead, BufReader};
+    ///
+    /// let mut reader = BufReader::with_capacity(4, &b"some data"[..]);
+    /// assert_eq!(reader.capacity(), 4);
+    ///
+    /// let buf = reader.fill_buf().unwrap();
+    /// assert_eq!(buf.len(), 4);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.buf.as_ref().len()
+    }
+
+    /// Unwraps this `BufReader<R>`, returning the underlying reader.
+    ///
+    /// Note that any leftover data in the internal buffer is lost. Therefore,
+    /// a following read from the underlying reader may lead to data loss.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::BufReader;
+    ///
+    /// let reader = BufReader::new(&b"some data"[..]);
+    /// let inner = reader.into_inner();
+    /// assert_eq!(inner, b"some data");
+    /// ```
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Invalidates all data in the internal buffer.
+    #[inline]
+    pub(crate) fn discard_buffer(&mut self) {
+        self.pos = 0;
+        self.cap = 0;
+    }
+}
+
+impl<R: Seek, B: BufferStorage> BufReader<R, B> {
+    /// Seeks relative to the current position. If the new position lies within the buffer,
+    /// the buffer will not be flushed, allowing for more efficient seeks.
+    /// This method does not return the location of the underlying reader, so the caller
+    /// must track this information themselves if it is required.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufRead, BufReader, Cursor};
+    ///
+    /// let mut reader = BufReader::new(Cursor::new(b"some data"));
+    /// reader.fill_buf().unwrap();
+    ///
+    /// reader.seek_relative(5).unwrap();
+    /// assert_eq!(reader.buffer(), b"data");
+    ///
+    /// reader.seek_relative(-2).unwrap();
+    /// assert_eq!(reader.buffer(), b"e data");
+    /// ```
+    pub fn seek_relative(&mut self, offset: i64) -> io::Result<()> {
+        let pos = self.pos as u64;
+        if offset < 0 {
+            if let Some(new_pos) = pos.checked_sub(offset.unsigned_abs()) {
+                self.pos = new_pos as usize;
+                return Ok(());
+            }
+        } else if let Some(new_pos) = pos.checked_add(offset as u64) {
+            if new_pos <= self.cap as u64 {
+                self.pos = new_pos as usize;
+                return Ok(());
+            }
+        }
+        self.seek(SeekFrom::Current(offset)).map(drop)
+    }
+}
+
+impl<R: Read, B: BufferStorage> Read for BufReader<R, B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // If we don't have any buffered data and we're doing a massive read
+        // (larger than our internal buffer), bypass our internal buffer
+        // entirely.
+        if self.pos == self.cap && buf.len() >= self.capacity() {
+            self.discard_buffer();
+            return self.inner.read(buf);
+        }
+        let nread = {
+            let mut rem = self.fill_buf()?;
+            rem.read(buf)?
+        };
+        self.consume(nread);
+        Ok(nread)
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        // If we don't have any buffered data and we're doing a massive read
+        // (larger than our internal buffer), bypass our internal buffer
+        // entirely.
+        if self.pos == self.cap && buf.remaining() >= self.capacity() {
+            self.discard_buffer();
+            return self.inner.read_buf(buf);
+        }
+
+        let prev = buf.filled_len();
+
+        let mut rem = self.fill_buf()?;
+        rem.read_buf(buf)?;
+
+        self.consume(buf.filled_len() - prev); //slice impl of read_buf known to never unfill buf
+
+        Ok(())
+    }
+
+    // Small read_exacts from a BufReader are extremely common when used with a deserializer.
+    // The default implementation calls read in a loop, which results in surprisingly poor code
+    // generation for the common path where the buffer has enough bytes to fill the passed-in
+    // buffer.
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if self.buffer().len() >= buf.len() {
+            buf.copy_from_slice(&self.buffer()[..buf.len()]);
+            self.consume(buf.len());
+            return Ok(());
+        }
+
+        default_read_exact(self, buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let total_len = bufs.iter().map(|b| b.len()).sum::<usize>();
+        if self.pos == self.cap && total_len >= self.capacity() {
+            self.discard_buffer();
+            return self.inner.read_vectored(bufs);
+        }
+        let nread = {
+            let mut rem = self.fill_buf()?;
+            rem.read_vectored(bufs)?
+        };
+        self.consume(nread);
+        Ok(nread)
+    }
+
+    fn is_read_vectored(&self) -> bool {
+        self.inner.is_read_vectored()
+    }
+
+    // The inner reader might have an optimized `read_to_end`. Drain our buffer and then
+    // delegate to the inner implementation.
+    #[cfg(feature = "alloc")]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let nread = self.cap - self.pos;
+        buf.extend_from_slice(self.buffer());
+        self.discard_buffer();
+        Ok(nread + self.inner.read_to_end(buf)?)
+    }
+
+    // The inner reader might have an optimized `read_to_end`. Drain our buffer and then
+    // delegate to the inner implementation.
+    #[cfg(feature = "alloc")]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        // In the general `else` case below we must read bytes into a side buffer, check
+        // that they are valid UTF-8, and then append them to `buf`. This requires a
+        // potentially large memcpy.
+        //
+        // If `buf` is empty--the most common case--we can leverage `append_to_string`
+        // to read directly into `buf`'s internal byte buffer, saving an allocation
+        // and a memcpy.
+        if buf.is_empty() {
+            // `append_to_string`'s safety relies on the buffer only being appended to since
+            // it only checks the UTF-8 validity of new data. If there were existing content in
+            // `buf` then an untrustworthy reader (i.e. `self.inner`) could not only append
+            // bytes but also modify existing bytes and render them invalid. On the other hand,
+            // if `buf` is empty then by definition any writes must be appends and
+            // `append_to_string` will validate all of the new bytes.
+            unsafe { append_to_string(buf, |b| self.read_to_end(b)) }
+        } else {
+            // We cannot append our byte buffer directly onto the `buf` String as there could
+            // be an incomplete UTF-8 sequence that has only been partially read. We must read
+            // everything into a side buffer first and then call `from_utf8` on the complete
+            // buffer.
+            let mut bytes = Vec::new();
+            self.read_to_end(&mut bytes)?;
+            let string = str::from_utf8(&bytes).map_err(|_| {
+                io::Error::new_const(
+                    io::ErrorKind::InvalidData,
+                    &"stream did not contain valid UTF-8",
+                )
+            })?;
+            *buf += string;
+            Ok(string.len())
+        }
+    }
+}
+
+impl<R: Read, B: BufferStorage> BufRead for BufReader<R, B> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        // If we've reached the end of our internal buffer then we need to fetch
+        // some more data from the underlying reader.
+        // Branch using `>=` instead of the more correct `==`
+        // to tell the compiler that the pos..cap slice is always valid.
+        if self.pos >= self.cap {
+            debug_assert!(self.pos == self.cap);
+
+            let mut readbuf = ReadBuf::uninit(self.buf.as_mut());
+
+            // SAFETY: `self.init` is either 0, the length of a fully initialized
+            // buffer supplied by the caller, or set to `readbuf.initialized_len()`
+            // from the last time this function was called
+            unsafe {
+                readbuf.assume_init(self.init);
+            }
+
+            self.inner.read_buf(&mut readbuf)?;
+
+            self.cap = readbuf.filled_len();
+            self.init = readbuf.initialized_len();
+
+            self.pos = 0;
+        }
+        Ok(self.buffer())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = cmp::min(self.pos + amt, self.cap);
+    }
+}
+
+impl<R, B: BufferStorage> fmt::Debug for BufReader<R, B>
+where
+    R: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BufReader")
+            .field("reader", &self.inner)
+            .field("buffer", &format_args!("{}/{}", self.cap - self.pos, self.capacity()))
+            .finish()
+    }
+}
+
+impl<R: Seek, B: BufferStorage> Seek for BufReader<R, B> {
+    /// Seek to an offset, in bytes, in the underlying reader.
+    ///
+    /// The position used for seeking with <code>[SeekFrom::Current]\(_)</code> is the
+    /// position the underlying reader would be at if the `BufReader<R>` had no
+    /// internal buffer.
+    ///
+    /// Seeking always discards the internal buffer, even if the seek position
+    /// would otherwise fall within it. This guarantees that calling
+    /// [`BufReader::into_inner()`] immediately after a seek yields the underlying reader
+    /// at the same position.
+    ///
+    /// To seek without discarding the internal buffer, use [`BufReader::seek_relative`].
+    ///
+    /// See [`Seek`] for more details.
+    ///
+    /// Note: In the edge case where you're seeking with <code>[SeekFrom::Current]\(n)</code>
+    /// where `n` minus the internal buffer length overflows an `i64`, two
+    /// seeks will be performed instead of one. If the second seek returns
+    /// [`Err`], the underlying reader will be left at the same position it would
+    /// have if you called `seek` with <code>[SeekFrom::Current]\(0)</code>.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let result: u64;
+        if let SeekFrom::Current(n) = pos {
+            let remainder = (self.cap - self.pos) as i64;
+            // it should be safe to assume that remainder fits within an i64 as the alternative
+            // means we managed to allocate 8 exbibytes and that's absurd.
+            // But it's not out of the realm of possibility for some weird underlying reader to
+            // support seeking by i64::MIN so we need to handle underflow when subtracting
+            // remainder.
+            if let Some(offset) = n.checked_sub(remainder) {
+                result = self.inner.seek(SeekFrom::Current(offset))?;
+            } else {
+                // seek backwards by our remainder, and then by the offset
+                self.inner.seek(SeekFrom::Current(-remainder))?;
+                self.discard_buffer();
+                result = self.inner.seek(SeekFrom::Current(n))?;
+            }
+        } else {
+            // Seeking with Start/End doesn't care about our buffer length.
+            result = self.inner.seek(pos)?;
+        }
+        self.discard_buffer();
+        Ok(result)
+    }
+
+    /// Returns the current seek position from the start of the stream.
+    ///
+    /// The value returned is equivalent to `self.seek(SeekFrom::Current(0))`
+    /// but does not flush the internal buffer. Due to this optimization the
+    /// function does not guarantee that calling `.into_inner()` immediately
+    /// afterwards will yield the underlying reader at the same position. Use
+    /// [`BufReader::seek`] instead if you require that guarantee.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the position of the inner reader is smaller
+    /// than the amount of buffered data. That can happen if the inner reader
+    /// has an incorrect implementation of [`Seek::stream_position`], or if the
+    /// position has gone out of sync due to calling [`Seek::seek`] directly on
+    /// the underlying reader.
+    fn stream_position(&mut self) -> io::Result<u64> {
+        let remainder = (self.cap - self.pos) as u64;
+        self.inner.stream_position().map(|pos| {
+            pos.checked_sub(remainder).expect(
+                "overflow when subtracting remaining buffer size from inner stream position",
+            )
+        })
+    }
+}
+
+#[cfg(portable_io_unstable_all)] // unstable feature: size hint optimization (requires Rust nightly for min_specialization)
+// (ADAPTED to use the buffered length directly, since specializing on `BufferStorage` is not allowed)
+impl<T, B> SizeHint for BufReader<T, B> {
+    #[inline]
+    fn lower_bound(&self) -> usize {
+        SizeHint::lower_bound(&self.inner) + (self.cap - self.pos)
+    }
+
+    #[inline]
+    fn upper_bound(&self) -> Option<usize> {
+        SizeHint::upper_bound(&self.inner).and_then(|up| (self.cap - self.pos).checked_add(up))
+    }
+}
@@ -0,0 +1,727 @@
+use core::fmt;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ptr;
+use core::slice;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::DEFAULT_BUF_SIZE;
+use crate::{self as io, Error, ErrorKind, IoSlice, Seek, SeekFrom, Write};
+
+#[cfg(feature = "alloc")]
+use super::new_uninit_slice;
+use super::{BufferStorage, IntoInnerError};
+
+/// Wraps a writer and buffers its output.
+///
+/// It can be excessively inefficient to work directly with something that
+/// implements [`Write`]. For example, every call to
+/// [`write`][`Write::write`] on a device driver may result in a separate
+/// request to the hardware. A `BufWriter<W>` keeps an in-memory buffer of
+/// data and writes it to an underlying writer in large, infrequent batches.
+///
+/// `BufWriter<W>` can improve the speed of programs that make *small* and
+/// *repeated* write calls to the same device. It does not help when writing
+/// very large amounts at once, or writing just one or a few times. It also
+/// provides no advantage when writing to a destination that is in memory,
+/// like a <code>[Vec]\<u8></code>.
+///
+/// It is critical to call [`flush`] before `BufWriter<W>` is dropped. Though
+/// dropping will attempt to flush the contents of the buffer, any errors
+/// that happen in the process of dropping will be ignored. Calling [`flush`]
+/// ensures that the buffer is empty and thus dropping will not even attempt
+/// file operations.
+///
+/// The internal buffer is stored in `B`, which may be any [`BufferStorage`]:
+/// a heap allocation (the default, with the `alloc` feature), storage supplied by the caller (see
+/// [`BufWriter::with_buffer`]), or an inline array (see
+/// [`BufWriter::with_array`]).
+///
+/// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+/// # Example code
+///
+/// ```
+/// use portable_io::{self as io, BufWriter, Write};
+///
+/// fn main() -> io::Result<()> {
+///     let mut stream = BufWriter::new(Vec::new());
+///
+///     for i in 0..10 {
+///         stream.write(&[i + 1])?;
+///     }
+///     stream.flush()?;
+///
+///     assert_eq!(stream.get_ref(), &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+///     Ok(())
+/// }
+/// ```
+///
+/// By wrapping the stream with a `BufWriter<W>`, these ten writes are all
+/// grouped together by the buffer and will all be written out in one write
+/// call to the inner writer when the stream is flushed.
+///
+/// [`flush`]: BufWriter::flush
+// (ADAPTED to only default to heap storage when the `alloc` feature is enabled)
+pub struct BufWriter<
+    W: Write,
+    #[cfg(feature = "alloc")] B: BufferStorage = Box<[MaybeUninit<u8>]>,
+    #[cfg(not(feature = "alloc"))] B: BufferStorage,
+> {
+    inner: W,
+    // The buffer. Bytes `..len` are initialized and hold the buffered data;
+    // the rest is spare capacity.
+    buf: B,
+    len: usize,
+    // #30888: If the inner writer panics in a call to write, we don't want to
+    // write the buffered data a second time in BufWriter's destructor. This
+    // flag tells the Drop impl if it should skip the flush.
+    panicked: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<W: Write> BufWriter<W> {
+    /// Creates a new `BufWriter<W>` with a default buffer capacity. The default is currently 8 KB,
+    /// but may change in the future.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::BufWriter;
+    ///
+    /// let buffer = BufWriter::new(Vec::new());
+    /// assert_eq!(buffer.capacity(), 8 * 1024);
+    /// ```
+    pub fn new(inner: W) -> BufWriter<W> {
+        BufWriter::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a new `BufWriter<W>` with the specified buffer capacity.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// Creating a buffer with a buffer of a hundred bytes.
+    ///
+    /// ```
+    /// use portable_io::BufWriter;
+    ///
+    /// let buffer = BufWriter::with_capacity(100, Vec::new());
+    /// assert_eq!(buffer.capacity(), 100);
+    /// ```
+    pub fn with_capacity(capacity: usize, inner: W) -> BufWriter<W> {
+        BufWriter::with_buffer(new_uninit_slice(capacity), inner)
+    }
+}
+
+impl<W: Write, const N: usize> BufWriter<W, [MaybeUninit<u8>; N]> {
+    /// Creates a new `BufWriter<W>` with an inline buffer of `N` bytes.
+    ///
+    /// This does not use the `alloc` crate. The buffer is stored within the
+    /// `BufWriter<W>` itself, so large values of `N` should be used with care
+    /// on targets with a small stack.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use portable_io::{BufWriter, Write};
+    ///
+    /// let mut storage = [0u8; 16];
+    /// let mut writer: BufWriter<_, [MaybeUninit<u8>; 4]> = BufWriter::with_array(&mut storage[..]);
+    /// assert_eq!(writer.capacity(), 4);
+    ///
+    /// writer.write_all(b"abc").unwrap();
+    /// assert_eq!(writer.buffer(), b"abc");
+    /// ```
+    pub fn with_array(inner: W) -> Self {
+        BufWriter::with_buffer([MaybeUninit::uninit(); N], inner)
+    }
+}
+
+impl<W: Write, B: BufferStorage> BufWriter<W, B> {
+    /// Creates a new `BufWriter<W>` that uses the given storage as its buffer.
+    ///
+    /// The capacity of the buffer is the length of the storage.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use portable_io::{BufWriter, Write};
+    ///
+    /// let mut storage = [MaybeUninit::uninit(); 4];
+    /// let mut writer = BufWriter::with_buffer(&mut storage[..], Vec::new());
+    ///
+    /// writer.write_all(b"abc").unwrap();
+    /// assert!(writer.get_ref().is_empty());
+    /// ```
+    pub fn with_buffer(buf: B, inner: W) -> Self {
+        BufWriter { inner, buf, len: 0, panicked: false }
+    }
+
+    /// Send data in our local buffer into the inner writer, looping as
+    /// necessary until either it's all been sent or an error occurs.
+    ///
+    /// Because all the data in the buffer has been reported to our owner as
+    /// "successfully written" (by returning nonzero success values from
+    /// `write`), any 0-length writes from `inner` must be reported as i/o
+    /// errors from this method.
+    pub(super) fn flush_buf(&mut self) -> io::Result<()> {
+        /// Helper struct to ensure the buffer is updated after all the writes
+        /// are complete. It tracks the number of written bytes and moves the
+        /// unwritten bytes to the front of the buffer when dropped.
+        // (ADAPTED to move the bytes with `copy_within`, since the buffer is not a `Vec`)
+        struct BufGuard<'a> {
+            buffer: &'a mut [MaybeUninit<u8>],
+            len: &'a mut usize,
+            written: usize,
+        }
+
+        impl<'a> BufGuard<'a> {
+            fn new(buffer: &'a mut [MaybeUninit<u8>], len: &'a mut usize) -> Self {
+                Self { buffer, len, written: 0 }
+            }
+
+            /// The unwritten part of the buffer
+            fn remaining(&self) -> &[u8] {
+                let remaining = &self.buffer[self.written..*self.len];
+                // SAFETY: bytes `..len` of the buffer are always initialized
+                unsafe { slice::from_raw_parts(remaining.as_ptr() as *const u8, remaining.len()) }
+            }
+
+            /// Flag some bytes as removed from the front of the buffer
+            fn consume(&mut self, amt: usize) {
+                self.written += amt;
+            }
+
+            /// true if all of the bytes have been written
+            fn done(&self) -> bool {
+                self.written >= *self.len
+            }
+        }
+
+        impl Drop for BufGuard<'_> {
+            fn drop(&mut self) {
+                if self.written > 0 {
+                    self.buffer.copy_within(self.written..*self.len, 0);
+                    *self.len -= self.written;
+                }
+            }
+        }
+
+        let mut guard = BufGuard::new(self.buf.as_mut(), &mut self.len);
+        while !guard.done() {
+            self.panicked = true;
+            let r = self.inner.write(guard.remaining());
+            self.panicked = false;
+
+            match r {
+                Ok(0) => {
+                    return Err(Error::new_const(
+                        ErrorKind::WriteZero,
+                        &"failed to write the buffered data",
+                    ));
+                }
+                Ok(n) => guard.consume(n),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Buffer some data without flushing it, regardless of the size of the
+    /// data. Writes as much as possible without exceeding capacity. Returns
+    /// the number of bytes written.
+    pub(super) fn write_to_buf(&mut self, buf: &[u8]) -> usize {
+        let available = self.spare_capacity();
+        let amt_to_buffer = available.min(buf.len());
+
+        // SAFETY: `amt_to_buffer` is <= buffer's spare capacity by construction.
+        unsafe {
+            self.write_to_buffer_unchecked(&buf[..amt_to_buffer]);
+        }
+
+        amt_to_buffer
+    }
+
+    /// Gets a reference to the underlying writer.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::BufWriter;
+    ///
+    /// let mut buffer = BufWriter::new(Vec::new());
+    ///
+    /// // we can use reference just like buffer
+    /// let reference = buffer.get_ref();
+    /// assert!(reference.is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::BufWriter;
+    ///
+    /// let mut buffer = BufWriter::new(Vec::new());
+    ///
+    /// // we can use reference just like buffer
+    /// let reference = buffer.get_mut();
+    /// reference.push(1);
+    /// ```
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns a reference to the internally buffered data.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::BufWriter;
+    ///
+    /// let buf_writer = BufWriter::new(Vec::new());
+    ///
+    /// // See how many bytes are currently buffered
+    /// let bytes_buffered = buf_writer.buffer().len();
+    /// assert_eq!(bytes_buffered, 0);
+    /// ```
+    pub fn buffer(&self) -> &[u8] {
+        // SAFETY: bytes `..len` of the buffer are always initialized
+        // (ADAPTED to avoid using unstable fn)
+        let buffer_ptr = &self.buf.as_ref()[..self.len] as *const [MaybeUninit<u8>];
+        unsafe { &*(buffer_ptr as *const [u8]) }
+    }
+
+    /// Returns the number of bytes the internal buffer can hold without flushing.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::BufWriter;
+    ///
+    /// let buf_writer = BufWriter::new(Vec::new());
+    ///
+    /// // Check the capacity of the inner buffer
+    /// let capacity = buf_writer.capacity();
+    /// // Calculate how many bytes can be written without flushing
+    /// let without_flush = capacity - buf_writer.buffer().len();
+    /// assert_eq!(without_flush, 8 * 1024);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.buf.as_ref().len()
+    }
+
+    /// Unwraps this `BufWriter<W>`, returning the underlying writer.
+    ///
+    /// The buffer is written out before returning the writer.
+    ///
+    /// # Errors
+    ///
+    /// An [`Err`] will be returned if an error occurs while flushing the buffer.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufWriter, Write};
+    ///
+    /// let mut buffer = BufWriter::new(Vec::new());
+    /// buffer.write_all(b"some data").unwrap();
+    ///
+    /// // unwrap the Vec from the BufWriter
+    /// let inner = buffer.into_inner().unwrap();
+    /// assert_eq!(inner, b"some data");
+    /// ```
+    pub fn into_inner(mut self) -> Result<W, IntoInnerError<BufWriter<W, B>>> {
+        match self.flush_buf() {
+            Err(e) => Err(IntoInnerError::new(self, e)),
+            Ok(()) => Ok(self.into_raw_parts().0),
+        }
+    }
+
+    /// Disassembles this `BufWriter<W>`, returning the underlying writer, and any buffered but
+    /// unwritten data.
+    ///
+    /// If the underlying writer panicked, it is not known what portion of the data was written.
+    /// In this case, we return `WriterPanicked` for the buffered data (from which the buffer
+    /// contents can still be recovered).
+    ///
+    /// `into_parts` makes no attempt to flush data and cannot fail.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufWriter, Write};
+    ///
+    /// let mut buffer = [0u8; 10];
+    /// let mut stream = BufWriter::new(buffer.as_mut());
+    /// write!(stream, "too much data").unwrap();
+    /// stream.flush().expect_err("it doesn't fit");
+    /// let (recovered_writer, buffered_data) = stream.into_parts();
+    /// assert_eq!(recovered_writer.len(), 0);
+    /// assert_eq!(&buffered_data.unwrap(), b"ata");
+    /// ```
+    // (ADAPTED to copy the buffered data into a new `Vec`, since the storage is not a `Vec`)
+    #[cfg(feature = "alloc")]
+    pub fn into_parts(self) -> (W, Result<Vec<u8>, WriterPanicked>) {
+        let buf = self.buffer().to_vec();
+        let buf = if !self.panicked { Ok(buf) } else { Err(WriterPanicked { buf }) };
+
+        let (inner, _storage) = self.into_raw_parts();
+
+        (inner, buf)
+    }
+
+    /// Disassembles this `BufWriter<W>` without running its destructor,
+    /// returning the underlying writer and the buffer storage.
+    fn into_raw_parts(self) -> (W, B) {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: double-drops are prevented by putting `self` in a ManuallyDrop
+        // that is never dropped.
+        unsafe { (ptr::read(&this.inner), ptr::read(&this.buf)) }
+    }
+
+    // Ensure this function does not get inlined into `write`, so that it
+    // remains inlineable and its common path remains as short as possible.
+    // If this function ends up being called frequently relative to `write`,
+    // it's likely a sign that the client is using an improperly sized buffer
+    // or their write patterns are somewhat pathological.
+    #[cold]
+    #[inline(never)]
+    fn write_cold(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.len() > self.spare_capacity() {
+            self.flush_buf()?;
+        }
+
+        // Why not len > capacity? To avoid a needless trip through the buffer when the input
+        // exactly fills it. We'd just need to flush it to the underlying writer anyway.
+        if buf.len() >= self.capacity() {
+            self.panicked = true;
+            let r = self.get_mut().write(buf);
+            self.panicked = false;
+            r
+        } else {
+            // Write to the buffer. In this case, we write to the buffer even if it fills it
+            // exactly. Doing otherwise would mean flushing the buffer, then writing this
+            // input to the inner writer, which in many cases would be a worse strategy.
+
+            // SAFETY: There was either enough spare capacity already, or there wasn't and we
+            // flushed the buffer to ensure that there is. In the latter case, we know that there
+            // is because flushing ensured that our entire buffer is spare capacity, and we entered
+            // this block because the input buffer length is less than that capacity. In either
+            // case, it's safe to write the input buffer to our buffer.
+            unsafe {
+                self.write_to_buffer_unchecked(buf);
+            }
+
+            Ok(buf.len())
+        }
+    }
+
+    // Ensure this function does not get inlined into `write_all`, so that it
+    // remains inlineable and its common path remains as short as possible.
+    // If this function ends up being called frequently relative to `write_all`,
+    // it's likely a sign that the client is using an improperly sized buffer
+    // or their write patterns are somewhat pathological.
+    #[cold]
+    #[inline(never)]
+    fn write_all_cold(&mut self, buf: &[u8]) -> io::Result<()> {
+        // Normally, `write_all` just calls `write` in a loop. We can do better
+        // by calling `self.get_mut().write_all()` directly, which avoids
+        // round trips through the buffer in the event of a series of partial
+        // writes in some circumstances.
+
+        if buf.len() > self.spare_capacity() {
+            self.flush_buf()?;
+        }
+
+        // Why not len > capacity? To avoid a needless trip through the buffer when the input
+        // exactly fills it. We'd just need to flush it to the underlying writer anyway.
+        if buf.len() >= self.capacity() {
+            self.panicked = true;
+            let r = self.get_mut().write_all(buf);
+            self.panicked = false;
+            r
+        } else {
+            // Write to the buffer. In this case, we write to the buffer even if it fills it
+            // exactly. Doing otherwise would mean flushing the buffer, then writing this
+            // input to the inner writer, which in many cases would be a worse strategy.
+
+            // SAFETY: There was either enough spare capacity already, or there wasn't and we
+            // flushed the buffer to ensure that there is. In the latter case, we know that there
+            // is because flushing ensured that our entire buffer is spare capacity, and we entered
+            // this block because the input buffer length is less than that capacity. In either
+            // case, it's safe to write the input buffer to our buffer.
+            unsafe {
+                self.write_to_buffer_unchecked(buf);
+            }
+
+            Ok(())
+        }
+    }
+
+    // SAFETY: Requires `buf.len() <= self.capacity() - self.len`,
+    // i.e., that input buffer length is less than or equal to spare capacity.
+    #[inline]
+    unsafe fn write_to_buffer_unchecked(&mut self, buf: &[u8]) {
+        debug_assert!(buf.len() <= self.spare_capacity());
+        let old_len = self.len;
+        let buf_len = buf.len();
+        let src = buf.as_ptr();
+        let dst = self.buf.as_mut().as_mut_ptr().add(old_len) as *mut u8;
+        ptr::copy_nonoverlapping(src, dst, buf_len);
+        self.len = old_len + buf_len;
+    }
+
+    #[inline]
+    fn spare_capacity(&self) -> usize {
+        self.capacity() - self.len
+    }
+}
+
+/// Error returned for the buffered data from `BufWriter::into_parts`, when the underlying
+/// writer has previously panicked.  Contains the (possibly partly written) buffered data.
+///
+/// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+/// # Example code
+///
+/// ```
+/// use portable_io::{self as io, BufWriter, Write};
+///
+/// struct PanickingWriter;
+/// impl Write for PanickingWriter {
+///   fn write(&mut self, buf: &[u8]) -> io::Result<usize> { panic!() }
+///   fn flush(&mut self) -> io::Result<()> { panic!() }
+/// }
+///
+/// let mut stream = BufWriter::new(PanickingWriter);
+/// write!(stream, "some data").unwrap();
+/// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+///     stream.flush().unwrap()
+/// }));
+/// assert!(result.is_err());
+/// let (recovered_writer, buffered_data) = stream.into_parts();
+/// assert!(matches!(recovered_writer, PanickingWriter));
+/// assert_eq!(buffered_data.unwrap_err().into_inner(), b"some data");
+/// ```
+#[cfg(feature = "alloc")]
+pub struct WriterPanicked {
+    buf: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl WriterPanicked {
+    /// Returns the perhaps-unwritten data.  Some of this data may have been written by the
+    /// panicking call(s) to the underlying writer, so simply writing it again is not a good idea.
+    #[must_use = "`self` will be dropped if the result is not used"]
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+
+    const DESCRIPTION: &'static str =
+        "BufWriter inner writer panicked, what data remains unwritten is not known";
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for WriterPanicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Self::DESCRIPTION)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl core::error::Error for WriterPanicked {
+    #[allow(deprecated, deprecated_in_future)]
+    fn description(&self) -> &str {
+        Self::DESCRIPTION
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for WriterPanicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriterPanicked")
+            .field("buffer", &format_args!("{}/{}", self.buf.len(), self.buf.capacity()))
+            .finish()
+    }
+}
+
+impl<W: Write, B: BufferStorage> Write for BufWriter<W, B> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Use < instead of <= to avoid a needless trip through the buffer in some cases.
+        // See `write_cold` for details.
+        if buf.len() < self.spare_capacity() {
+            // SAFETY: safe by above conditional.
+            unsafe {
+                self.write_to_buffer_unchecked(buf);
+            }
+
+            Ok(buf.len())
+        } else {
+            self.write_cold(buf)
+        }
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        // Use < instead of <= to avoid a needless trip through the buffer in some cases.
+        // See `write_all_cold` for details.
+        if buf.len() < self.spare_capacity() {
+            // SAFETY: safe by above conditional.
+            unsafe {
+                self.write_to_buffer_unchecked(buf);
+            }
+
+            Ok(())
+        } else {
+            self.write_all_cold(buf)
+        }
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        // FIXME: Consider applying `#[inline]` / `#[inline(never)]` optimizations already applied
+        // to `write` and `write_all`. The performance benefits can be significant. See #79930.
+        if self.get_ref().is_write_vectored() {
+            // We have to handle the possibility that the total length of the buffers overflows
+            // `usize` (even though this can only happen if multiple `IoSlice`s reference the
+            // same underlying buffer, as otherwise the buffers wouldn't fit in memory). If the
+            // computation overflows, then surely the input cannot fit in our buffer, so we forward
+            // to the inner writer's `write_vectored` method to let it handle it appropriately.
+            let saturated_total_len =
+                bufs.iter().fold(0usize, |acc, b| acc.saturating_add(b.len()));
+
+            if saturated_total_len > self.spare_capacity() {
+                // Flush if the total length of the input exceeds our buffer's spare capacity.
+                // If we would have overflowed, this condition also holds, and we need to flush.
+                self.flush_buf()?;
+            }
+
+            if saturated_total_len >= self.capacity() {
+                // Forward to our inner writer if the total length of the input is greater than or
+                // equal to our buffer capacity. If we would have overflowed, this condition also
+                // holds, and we punt to the inner writer.
+                self.panicked = true;
+                let r = self.get_mut().write_vectored(bufs);
+                self.panicked = false;
+                r
+            } else {
+                // `saturated_total_len < self.capacity()` implies that we did not saturate.
+
+                // SAFETY: We checked whether or not the spare capacity was large enough above. If
+                // it was, then we're safe already. If it wasn't, we flushed, making sufficient
+                // room for any input <= the buffer size, which includes this input.
+                unsafe {
+                    bufs.iter().for_each(|b| self.write_to_buffer_unchecked(b));
+                };
+
+                Ok(saturated_total_len)
+            }
+        } else {
+            let mut iter = bufs.iter();
+            let mut total_written = if let Some(buf) = iter.by_ref().find(|&buf| !buf.is_empty()) {
+                // This is the first non-empty slice to write, so if it does
+                // not fit in the buffer, we still get to flush and proceed.
+                if buf.len() > self.spare_capacity() {
+                    self.flush_buf()?;
+                }
+                if buf.len() >= self.capacity() {
+                    // The slice is at least as large as the buffering capacity,
+                    // so it's better to write it directly, bypassing the buffer.
+                    self.panicked = true;
+                    let r = self.get_mut().write(buf);
+                    self.panicked = false;
+                    return r;
+                } else {
+                    // SAFETY: We checked whether or not the spare capacity was large enough above.
+                    // If it was, then we're safe already. If it wasn't, we flushed, making
+                    // sufficient room for any input <= the buffer size, which includes this input.
+                    unsafe {
+                        self.write_to_buffer_unchecked(buf);
+                    }
+
+                    buf.len()
+                }
+            } else {
+                return Ok(0);
+            };
+            debug_assert!(total_written != 0);
+            for buf in iter {
+                if buf.len() <= self.spare_capacity() {
+                    // SAFETY: safe by above conditional.
+                    unsafe {
+                        self.write_to_buffer_unchecked(buf);
+                    }
+
+                    // This cannot overflow `usize`. If we are here, we've written all of the bytes
+                    // so far to our buffer, and we've ensured that we never exceed the buffer's
+                    // capacity. Therefore, `total_written` <= `self.capacity()` <= `usize::MAX`.
+                    total_written += buf.len();
+                } else {
+                    break;
+                }
+            }
+            Ok(total_written)
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_buf().and_then(|()| self.get_mut().flush())
+    }
+}
+
+impl<W: Write, B: BufferStorage> fmt::Debug for BufWriter<W, B>
+where
+    W: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BufWriter")
+            .field("writer", &self.inner)
+            .field("buffer", &format_args!("{}/{}", self.len, self.capacity()))
+            .finish()
+    }
+}
+
+impl<W: Write + Seek, B: BufferStorage> Seek for BufWriter<W, B> {
+    /// Seek to the offset, in bytes, in the underlying writer.
+    ///
+    /// Seeking always writes out the internal buffer before seeking.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.flush_buf()?;
+        self.get_mut().seek(pos)
+    }
+}
+
+impl<W: Write, B: BufferStorage> Drop for BufWriter<W, B> {
+    fn drop(&mut self) {
+        if !self.panicked {
+            // dtors should not panic, so we ignore a failed flush
+            let _r = self.flush_buf();
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use core::fmt;
+use core::mem::MaybeUninit;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+use crate::{self as io, IoSlice, Write};
+
+#[cfg(feature = "alloc")]
+use super::new_uninit_slice;
+use super::{BufWriter, BufferStorage, IntoInnerError, LineWriterShim};
+
+/// Wraps a writer and buffers output to it, flushing whenever a newline
+/// (`0x0a`, `'\n'`) is detected.
+///
+/// The [`BufWriter`] struct wraps a writer and buffers its output.
+/// But it only does this batched write when it goes out of scope, or when the
+/// internal buffer is full. Sometimes, you'd prefer to write each line as it's
+/// completed, rather than the entire buffer at once. Enter `LineWriter`. It
+/// does exactly that.
+///
+/// Like [`BufWriter`], a `LineWriter`’s buffer will also be flushed when the
+/// `LineWriter` goes out of scope or when its internal buffer is full.
+///
+/// If there's still a partial line in the buffer when the `LineWriter` is
+/// dropped, it will flush those contents.
+///
+/// The line terminator byte may be changed with [`LineWriter::set_terminator`],
+/// for example to `b'\r'` for devices that expect carriage returns.
+///
+/// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+/// # Example code
+///
+/// We can use `LineWriter` to write one line at a time, significantly
+/// reducing the number of actual writes to the underlying writer.
+///
+/// ```
+/// use portable_io::{self as io, LineWriter, Write};
+///
+/// fn main() -> io::Result<()> {
+///     let road_not_taken = b"I shall be telling this with a sigh
+/// Somewhere ages and ages hence:
+/// Two roads diverged in a wood, and I -
+/// I took the one less traveled by,
+/// And that has made all the difference.";
+///
+///     let mut writer = LineWriter::new(Vec::new());
+///
+///     writer.write_all(&road_not_taken[..35])?;
+///     // No bytes are written until a newline is encountered (or
+///     // the internal buffer is filled).
+///     assert_eq!(writer.get_ref(), b"");
+///
+///     writer.write_all(&road_not_taken[35..])?;
+///     // The first four lines have been written, the last line is still buffered.
+///     assert_eq!(writer.get_ref(), &road_not_taken[..138]);
+///
+///     // Unwrapping the `LineWriter` flushes the last line.
+///     let inner = writer.into_inner()?;
+///     assert_eq!(inner, &road_not_taken[..]);
+///     Ok(())
+/// }
+/// ```
+// (ADAPTED to only default to heap storage when the `alloc` feature is enabled)
+pub struct LineWriter<
+    W: Write,
+    #[cfg(feature = "alloc")] B: BufferStorage = Box<[MaybeUninit<u8>]>,
+    #[cfg(not(feature = "alloc"))] B: BufferStorage,
+> {
+    inner: BufWriter<W, B>,
+    terminator: u8,
+}
+
+#[cfg(feature = "alloc")]
+impl<W: Write> LineWriter<W> {
+    /// Creates a new `LineWriter`.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::LineWriter;
+    ///
+    /// let writer = LineWriter::new(Vec::new());
+    /// assert_eq!(writer.capacity(), 1024);
+    /// ```
+    pub fn new(inner: W) -> LineWriter<W> {
+        // Lines typically aren't that long, don't use a giant buffer
+        LineWriter::with_capacity(1024, inner)
+    }
+
+    /// Creates a new `LineWriter` with at least the specified capacity for the
+    /// internal buffer.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::LineWriter;
+    ///
+    /// let writer = LineWriter::with_capacity(100, Vec::new());
+    /// assert_eq!(writer.capacity(), 100);
+    /// ```
+    pub fn with_capacity(capacity: usize, inner: W) -> LineWriter<W> {
+        LineWriter::with_buffer(new_uninit_slice(capacity), inner)
+    }
+}
+
+impl<W: Write, const N: usize> LineWriter<W, [MaybeUninit<u8>; N]> {
+    /// Creates a new `LineWriter` with an inline buffer of `N` bytes.
+    ///
+    /// This does not use the `alloc` crate. The buffer is stored within the
+    /// `LineWriter` itself, so large values of `N` should be used with care
+    /// on targets with a small stack.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use portable_io::{LineWriter, Write};
+    ///
+    /// let mut storage = [0u8; 16];
+    /// let mut writer: LineWriter<_, [MaybeUninit<u8>; 8]> = LineWriter::with_array(&mut storage[..]);
+    /// writer.write_all(b"one\ntwo").unwrap();
+    /// drop(writer);
+    /// assert_eq!(&storage[..7], b"one\ntwo");
+    /// ```
+    pub fn with_array(inner: W) -> Self {
+        LineWriter::with_buffer([MaybeUninit::uninit(); N], inner)
+    }
+}
+
+impl<W: Write, B: BufferStorage> LineWriter<W, B> {
+    /// Creates a new `LineWriter` that uses the given storage as its buffer.
+    ///
+    /// The capacity of the buffer is the length of the storage.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use core::mem::MaybeUninit;
+    /// use portable_io::{LineWriter, Write};
+    ///
+    /// let mut storage = [MaybeUninit::uninit(); 8];
+    /// let mut writer = LineWriter::with_buffer(&mut storage[..], Vec::new());
+    /// writer.write_all(b"one\ntwo").unwrap();
+    /// assert_eq!(writer.get_ref(), b"one\n");
+    /// ```
+    pub fn with_buffer(buf: B, inner: W) -> Self {
+        LineWriter { inner: BufWriter::with_buffer(buf, inner), terminator: b'\n' }
+    }
+
+    /// Returns the byte that ends a line, which is `b'\n'` by default.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::LineWriter;
+    ///
+    /// let writer = LineWriter::new(Vec::new());
+    /// assert_eq!(writer.terminator(), b'\n');
+    /// ```
+    pub fn terminator(&self) -> u8 {
+        self.terminator
+    }
+
+    /// Sets the byte that ends a line.
+    ///
+    /// Data that is already buffered is not flushed by this call.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{LineWriter, Write};
+    ///
+    /// let mut writer = LineWriter::new(Vec::new());
+    /// writer.set_terminator(b'\r');
+    ///
+    /// writer.write_all(b"one\rtwo\nthree").unwrap();
+    /// assert_eq!(writer.get_ref(), b"one\r");
+    /// ```
+    pub fn set_terminator(&mut self, terminator: u8) {
+        self.terminator = terminator;
+    }
+
+    /// Gets a reference to the underlying writer.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::LineWriter;
+    ///
+    /// let writer = LineWriter::new(Vec::new());
+    ///
+    /// let reference = writer.get_ref();
+    /// assert!(reference.is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &W {
+        self.inner.get_ref()
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// Caution must be taken when calling methods on the mutable reference
+    /// returned as extra writes could corrupt the output stream.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::LineWriter;
+    ///
+    /// let mut writer = LineWriter::new(Vec::new());
+    ///
+    /// // we can use reference just like writer
+    /// let reference = writer.get_mut();
+    /// reference.push(1);
+    /// ```
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.get_mut()
+    }
+
+    /// Returns the number of bytes the internal buffer can hold without flushing.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::LineWriter;
+    ///
+    /// let writer = LineWriter::with_capacity(100, Vec::new());
+    /// assert_eq!(writer.capacity(), 100);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Unwraps this `LineWriter`, returning the underlying writer.
+    ///
+    /// The internal buffer is written out before returning the writer.
+    ///
+    /// # Errors
+    ///
+    /// An [`Err`] will be returned if an error occurs while flushing the buffer.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{LineWriter, Write};
+    ///
+    /// let mut writer = LineWriter::new(Vec::new());
+    /// writer.write_all(b"no newline").unwrap();
+    ///
+    /// let inner = writer.into_inner().unwrap();
+    /// assert_eq!(inner, b"no newline");
+    /// ```
+    pub fn into_inner(self) -> Result<W, IntoInnerError<LineWriter<W, B>>> {
+        let terminator = self.terminator;
+        self.inner
+            .into_inner()
+            .map_err(|err| err.new_wrapped(|inner| LineWriter { inner, terminator }))
+    }
+}
+
+impl<W: Write, B: BufferStorage> Write for LineWriter<W, B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        LineWriterShim::new(&mut self.inner, self.terminator).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        LineWriterShim::new(&mut self.inner, self.terminator).write_vectored(bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        LineWriterShim::new(&mut self.inner, self.terminator).write_all(buf)
+    }
+
+    fn write_all_vectored(&mut self, bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+        LineWriterShim::new(&mut self.inner, self.terminator).write_all_vectored(bufs)
+    }
+
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> io::Result<()> {
+        LineWriterShim::new(&mut self.inner, self.terminator).write_fmt(fmt)
+    }
+}
+
+impl<W: Write, B: BufferStorage> fmt::Debug for LineWriter<W, B>
+where
+    W: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("LineWriter")
+            .field("writer", &self.get_ref())
+            .field(
+                "buffer",
+                &format_args!("{}/{}", self.inner.buffer().len(), self.inner.capacity()),
+            )
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,406 @@
+// (ADDED in this fork) extension traits to read & write numbers in a given byte order,
+// similar to the `byteorder` crate but with the byte order in the method names
+
+#[cfg(test)]
+mod tests;
+
+use core::mem::size_of;
+
+use crate::{Read, Result, Write};
+
+// The size of the buffer on the stack used by the bulk `read_*_into` methods
+const CHUNK_SIZE: usize = 256;
+
+macro_rules! read_method {
+    ($ty:ty, $name:ident, $from_bytes:ident, $order:literal) => {
+        #[doc = concat!("Reads a ", $order, " `", stringify!($ty), "` from this reader.")]
+        #[inline]
+        fn $name(&mut self) -> Result<$ty> {
+            let mut buf = [0; size_of::<$ty>()];
+            self.read_exact(&mut buf)?;
+            Ok(<$ty>::$from_bytes(buf))
+        }
+    };
+}
+
+macro_rules! read_into_method {
+    ($ty:ty, $name:ident, $from_bytes:ident, $order:literal) => {
+        #[doc = concat!("Reads ", $order, " `", stringify!($ty), "` values from this reader,")]
+        /// enough to fill `dst`.
+        ///
+        /// If this returns an error, the contents of `dst` are unspecified,
+        /// like with [`read_exact`](Read::read_exact).
+        fn $name(&mut self, dst: &mut [$ty]) -> Result<()> {
+            const SIZE: usize = size_of::<$ty>();
+            let mut buf = [0; CHUNK_SIZE];
+            for chunk in dst.chunks_mut(CHUNK_SIZE / SIZE) {
+                let bytes = &mut buf[..chunk.len() * SIZE];
+                self.read_exact(bytes)?;
+                for (n, b) in chunk.iter_mut().zip(bytes.chunks_exact(SIZE)) {
+                    *n = <$ty>::$from_bytes(b.try_into().unwrap());
+                }
+            }
+            Ok(())
+        }
+    };
+}
+
+macro_rules! write_method {
+    ($ty:ty, $name:ident, $to_bytes:ident, $order:literal) => {
+        #[doc = concat!("Writes a ", $order, " `", stringify!($ty), "` to this writer.")]
+        #[inline]
+        fn $name(&mut self, n: $ty) -> Result<()> {
+            self.write_all(&n.$to_bytes())
+        }
+    };
+}
+
+/// Extends [`Read`] with methods to read numbers in a given byte order.
+///
+/// Each method has three variants: `_le` for little-endian, `_be` for big-endian
+/// and `_ne` for the native byte order of the target platform.
+///
+/// # Errors
+///
+/// All methods are built on [`read_exact`](Read::read_exact), so any error is
+/// returned the same way, including an error of the kind
+/// [`ErrorKind::UnexpectedEof`](crate::ErrorKind::UnexpectedEof) if the reader
+/// reaches EOF before enough bytes were read.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{ErrorKind, ReadBytesExt};
+///
+/// let mut reader: &[u8] = &[0x12, 0x34, 0x12, 0x34, 0xff];
+/// assert_eq!(reader.read_u16_be().unwrap(), 0x1234);
+/// assert_eq!(reader.read_u16_le().unwrap(), 0x3412);
+/// assert_eq!(reader.read_u16_le().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+/// ```
+pub trait ReadBytesExt: Read {
+    /// Reads a `u8` from this reader.
+    #[inline]
+    fn read_u8(&mut self) -> Result<u8> {
+        let mut buf = [0; 1];
+        self.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Reads an `i8` from this reader.
+    #[inline]
+    fn read_i8(&mut self) -> Result<i8> {
+        Ok(self.read_u8()? as i8)
+    }
+
+    read_method!(u16, read_u16_le, from_le_bytes, "little-endian");
+    read_method!(u16, read_u16_be, from_be_bytes, "big-endian");
+    read_method!(u16, read_u16_ne, from_ne_bytes, "native-endian");
+    read_method!(i16, read_i16_le, from_le_bytes, "little-endian");
+    read_method!(i16, read_i16_be, from_be_bytes, "big-endian");
+    read_method!(i16, read_i16_ne, from_ne_bytes, "native-endian");
+    read_method!(u32, read_u32_le, from_le_bytes, "little-endian");
+    read_method!(u32, read_u32_be, from_be_bytes, "big-endian");
+    read_method!(u32, read_u32_ne, from_ne_bytes, "native-endian");
+    read_method!(i32, read_i32_le, from_le_bytes, "little-endian");
+    read_method!(i32, read_i32_be, from_be_bytes, "big-endian");
+    read_method!(i32, read_i32_ne, from_ne_bytes, "native-endian");
+    read_method!(u64, read_u64_le, from_le_bytes, "little-endian");
+    read_method!(u64, read_u64_be, from_be_bytes, "big-endian");
+    read_method!(u64, read_u64_ne, from_ne_bytes, "native-endian");
+    read_method!(i64, read_i64_le, from_le_bytes, "little-endian");
+    read_method!(i64, read_i64_be, from_be_bytes, "big-endian");
+    read_method!(i64, read_i64_ne, from_ne_bytes, "native-endian");
+    read_method!(u128, read_u128_le, from_le_bytes, "little-endian");
+    read_method!(u128, read_u128_be, from_be_bytes, "big-endian");
+    read_method!(u128, read_u128_ne, from_ne_bytes, "native-endian");
+    read_method!(i128, read_i128_le, from_le_bytes, "little-endian");
+    read_method!(i128, read_i128_be, from_be_bytes, "big-endian");
+    read_method!(i128, read_i128_ne, from_ne_bytes, "native-endian");
+    read_method!(f32, read_f32_le, from_le_bytes, "little-endian");
+    read_method!(f32, read_f32_be, from_be_bytes, "big-endian");
+    read_method!(f32, read_f32_ne, from_ne_bytes, "native-endian");
+    read_method!(f64, read_f64_le, from_le_bytes, "little-endian");
+    read_method!(f64, read_f64_be, from_be_bytes, "big-endian");
+    read_method!(f64, read_f64_ne, from_ne_bytes, "native-endian");
+
+    /// Reads `i8` values from this reader, enough to fill `dst`.
+    ///
+    /// If this returns an error, the contents of `dst` are unspecified,
+    /// like with [`read_exact`](Read::read_exact).
+    fn read_i8_into(&mut self, dst: &mut [i8]) -> Result<()> {
+        let mut buf = [0; CHUNK_SIZE];
+        for chunk in dst.chunks_mut(CHUNK_SIZE) {
+            let bytes = &mut buf[..chunk.len()];
+            self.read_exact(bytes)?;
+            for (n, b) in chunk.iter_mut().zip(bytes.iter()) {
+                *n = *b as i8;
+            }
+        }
+        Ok(())
+    }
+
+    read_into_method!(u16, read_u16_le_into, from_le_bytes, "little-endian");
+    read_into_method!(u16, read_u16_be_into, from_be_bytes, "big-endian");
+    read_into_method!(u16, read_u16_ne_into, from_ne_bytes, "native-endian");
+    read_into_method!(i16, read_i16_le_into, from_le_bytes, "little-endian");
+    read_into_method!(i16, read_i16_be_into, from_be_bytes, "big-endian");
+    read_into_method!(i16, read_i16_ne_into, from_ne_bytes, "native-endian");
+    read_into_method!(u32, read_u32_le_into, from_le_bytes, "little-endian");
+    read_into_method!(u32, read_u32_be_into, from_be_bytes, "big-endian");
+    read_into_method!(u32, read_u32_ne_into, from_ne_bytes, "native-endian");
+    read_into_method!(i32, read_i32_le_into, from_le_bytes, "little-endian");
+    read_into_method!(i32, read_i32_be_into, from_be_bytes, "big-endian");
+    read_into_method!(i32, read_i32_ne_into, from_ne_bytes, "native-endian");
+    read_into_method!(u64, read_u64_le_into, from_le_bytes, "little-endian");
+    read_into_method!(u64, read_u64_be_into, from_be_bytes, "big-endian");
+    read_into_method!(u64, read_u64_ne_into, from_ne_bytes, "native-endian");
+    read_into_method!(i64, read_i64_le_into, from_le_bytes, "little-endian");
+    read_into_method!(i64, read_i64_be_into, from_be_bytes, "big-endian");
+    read_into_method!(i64, read_i64_ne_into, from_ne_bytes, "native-endian");
+    read_into_method!(u128, read_u128_le_into, from_le_bytes, "little-endian");
+    read_into_method!(u128, read_u128_be_into, from_be_bytes, "big-endian");
+    read_into_method!(u128, read_u128_ne_into, from_ne_bytes, "native-endian");
+    read_into_method!(i128, read_i128_le_into, from_le_bytes, "little-endian");
+    read_into_method!(i128, read_i128_be_into, from_be_bytes, "big-endian");
+    read_into_method!(i128, read_i128_ne_into, from_ne_bytes, "native-endian");
+    read_into_method!(f32, read_f32_le_into, from_le_bytes, "little-endian");
+    read_into_method!(f32, read_f32_be_into, from_be_bytes, "big-endian");
+    read_into_method!(f32, read_f32_ne_into, from_ne_bytes, "native-endian");
+    read_into_method!(f64, read_f64_le_into, from_le_bytes, "little-endian");
+    read_into_method!(f64, read_f64_be_into, from_be_bytes, "big-endian");
+    read_into_method!(f64, read_f64_ne_into, from_ne_bytes, "native-endian");
+
+    /// Reads a little-endian unsigned integer of `nbytes` bytes from this
+    /// reader.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8.
+    fn read_uint_le(&mut self, nbytes: usize) -> Result<u64> {
+        assert!((1..=8).contains(&nbytes), "invalid number of bytes: {}", nbytes);
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf[..nbytes])?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    /// Reads a big-endian unsigned integer of `nbytes` bytes from this reader.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8.
+    fn read_uint_be(&mut self, nbytes: usize) -> Result<u64> {
+        assert!((1..=8).contains(&nbytes), "invalid number of bytes: {}", nbytes);
+        let mut buf = [0; 8];
+        self.read_exact(&mut buf[8 - nbytes..])?;
+        Ok(u64::from_be_bytes(buf))
+    }
+
+    /// Reads a native-endian unsigned integer of `nbytes` bytes from this
+    /// reader.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8.
+    #[inline]
+    fn read_uint_ne(&mut self, nbytes: usize) -> Result<u64> {
+        if cfg!(target_endian = "little") {
+            self.read_uint_le(nbytes)
+        } else {
+            self.read_uint_be(nbytes)
+        }
+    }
+
+    /// Reads a little-endian signed integer of `nbytes` bytes from this
+    /// reader, extending its sign to 64 bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8.
+    #[inline]
+    fn read_int_le(&mut self, nbytes: usize) -> Result<i64> {
+        Ok(extend_sign(self.read_uint_le(nbytes)?, nbytes))
+    }
+
+    /// Reads a big-endian signed integer of `nbytes` bytes from this reader,
+    /// extending its sign to 64 bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8.
+    #[inline]
+    fn read_int_be(&mut self, nbytes: usize) -> Result<i64> {
+        Ok(extend_sign(self.read_uint_be(nbytes)?, nbytes))
+    }
+
+    /// Reads a native-endian signed integer of `nbytes` bytes from this
+    /// reader, extending its sign to 64 bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8.
+    #[inline]
+    fn read_int_ne(&mut self, nbytes: usize) -> Result<i64> {
+        Ok(extend_sign(self.read_uint_ne(nbytes)?, nbytes))
+    }
+}
+
+impl<R: Read + ?Sized> ReadBytesExt for R {}
+
+/// Extends [`Write`] with methods to write numbers in a given byte order.
+///
+/// Each method has three variants: `_le` for little-endian, `_be` for big-endian
+/// and `_ne` for the native byte order of the target platform.
+///
+/// # Errors
+///
+/// All methods are built on [`write_all`](Write::write_all), so any error is
+/// returned the same way.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{Cursor, WriteBytesExt};
+///
+/// let mut storage = [0; 6];
+/// let mut writer = Cursor::new(&mut storage[..]);
+/// writer.write_u16_be(0x1234).unwrap();
+/// writer.write_u32_le(0x12345678).unwrap();
+/// assert_eq!(storage, [0x12, 0x34, 0x78, 0x56, 0x34, 0x12]);
+/// ```
+pub trait WriteBytesExt: Write {
+    /// Writes a `u8` to this writer.
+    #[inline]
+    fn write_u8(&mut self, n: u8) -> Result<()> {
+        self.write_all(&[n])
+    }
+
+    /// Writes an `i8` to this writer.
+    #[inline]
+    fn write_i8(&mut self, n: i8) -> Result<()> {
+        self.write_all(&[n as u8])
+    }
+
+    write_method!(u16, write_u16_le, to_le_bytes, "little-endian");
+    write_method!(u16, write_u16_be, to_be_bytes, "big-endian");
+    write_method!(u16, write_u16_ne, to_ne_bytes, "native-endian");
+    write_method!(i16, write_i16_le, to_le_bytes, "little-endian");
+    write_method!(i16, write_i16_be, to_be_bytes, "big-endian");
+    write_method!(i16, write_i16_ne, to_ne_bytes, "native-endian");
+    write_method!(u32, write_u32_le, to_le_bytes, "little-endian");
+    write_method!(u32, write_u32_be, to_be_bytes, "big-endian");
+    write_method!(u32, write_u32_ne, to_ne_bytes, "native-endian");
+    write_method!(i32, write_i32_le, to_le_bytes, "little-endian");
+    write_method!(i32, write_i32_be, to_be_bytes, "big-endian");
+    write_method!(i32, write_i32_ne, to_ne_bytes, "native-endian");
+    write_method!(u64, write_u64_le, to_le_bytes, "little-endian");
+    write_method!(u64, write_u64_be, to_be_bytes, "big-endian");
+    write_method!(u64, write_u64_ne, to_ne_bytes, "native-endian");
+    write_method!(i64, write_i64_le, to_le_bytes, "little-endian");
+    write_method!(i64, write_i64_be, to_be_bytes, "big-endian");
+    write_method!(i64, write_i64_ne, to_ne_bytes, "native-endian");
+    write_method!(u128, write_u128_le, to_le_bytes, "little-endian");
+    write_method!(u128, write_u128_be, to_be_bytes, "big-endian");
+    write_method!(u128, write_u128_ne, to_ne_bytes, "native-endian");
+    write_method!(i128, write_i128_le, to_le_bytes, "little-endian");
+    write_method!(i128, write_i128_be, to_be_bytes, "big-endian");
+    write_method!(i128, write_i128_ne, to_ne_bytes, "native-endian");
+    write_method!(f32, write_f32_le, to_le_bytes, "little-endian");
+    write_method!(f32, write_f32_be, to_be_bytes, "big-endian");
+    write_method!(f32, write_f32_ne, to_ne_bytes, "native-endian");
+    write_method!(f64, write_f64_le, to_le_bytes, "little-endian");
+    write_method!(f64, write_f64_be, to_be_bytes, "big-endian");
+    write_method!(f64, write_f64_ne, to_ne_bytes, "native-endian");
+
+    /// Writes a little-endian unsigned integer of `nbytes` bytes to this
+    /// writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8, or if `n` does not fit in
+    /// `nbytes` bytes.
+    fn write_uint_le(&mut self, n: u64, nbytes: usize) -> Result<()> {
+        check_uint_size(n, nbytes);
+        self.write_all(&n.to_le_bytes()[..nbytes])
+    }
+
+    /// Writes a big-endian unsigned integer of `nbytes` bytes to this writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8, or if `n` does not fit in
+    /// `nbytes` bytes.
+    fn write_uint_be(&mut self, n: u64, nbytes: usize) -> Result<()> {
+        check_uint_size(n, nbytes);
+        self.write_all(&n.to_be_bytes()[8 - nbytes..])
+    }
+
+    /// Writes a native-endian unsigned integer of `nbytes` bytes to this
+    /// writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8, or if `n` does not fit in
+    /// `nbytes` bytes.
+    #[inline]
+    fn write_uint_ne(&mut self, n: u64, nbytes: usize) -> Result<()> {
+        if cfg!(target_endian = "little") {
+            self.write_uint_le(n, nbytes)
+        } else {
+            self.write_uint_be(n, nbytes)
+        }
+    }
+
+    /// Writes a little-endian signed integer of `nbytes` bytes to this writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8, or if `n` does not fit in
+    /// `nbytes` bytes.
+    fn write_int_le(&mut self, n: i64, nbytes: usize) -> Result<()> {
+        check_int_size(n, nbytes);
+        self.write_all(&n.to_le_bytes()[..nbytes])
+    }
+
+    /// Writes a big-endian signed integer of `nbytes` bytes to this writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8, or if `n` does not fit in
+    /// `nbytes` bytes.
+    fn write_int_be(&mut self, n: i64, nbytes: usize) -> Result<()> {
+        check_int_size(n, nbytes);
+        self.write_all(&n.to_be_bytes()[8 - nbytes..])
+    }
+
+    /// Writes a native-endian signed integer of `nbytes` bytes to this writer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not between 1 and 8, or if `n` does not fit in
+    /// `nbytes` bytes.
+    #[inline]
+    fn write_int_ne(&mut self, n: i64, nbytes: usize) -> Result<()> {
+        if cfg!(target_endian = "little") {
+            self.write_int_le(n, nbytes)
+        } else {
+            self.write_int_be(n, nbytes)
+        }
+    }
+}
+
+impl<W: Write + ?Sized> WriteBytesExt for W {}
+
+fn extend_sign(n: u64, nbytes: usize) -> i64 {
+    let shift = (8 - nbytes) * 8;
+    ((n << shift) as i64) >> shift
+}
+
+fn check_uint_size(n: u64, nbytes: usize) {
+    assert!((1..=8).contains(&nbytes), "invalid number of bytes: {}", nbytes);
+    assert!(nbytes == 8 || n >> (nbytes * 8) == 0, "{} does not fit in {} bytes", n, nbytes);
+}
+
+fn check_int_size(n: i64, nbytes: usize) {
+    assert!((1..=8).contains(&nbytes), "invalid number of bytes: {}", nbytes);
+    assert!(extend_sign(n as u64, nbytes) == n, "{} does not fit in {} bytes", n, nbytes);
+}
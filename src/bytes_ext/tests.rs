@@ -0,0 +1,155 @@
+use super::{ReadBytesExt, WriteBytesExt};
+use crate::{Cursor, ErrorKind, Read};
+
+#[test]
+fn read_integers() {
+    let mut reader: &[u8] = &[
+        0x80, // i8
+        0x12, 0x34, // u16
+        0xfe, 0xff, // i16
+        0x01, 0x02, 0x03, 0x04, // u32
+        0x04, 0x03, 0x02, 0x01, 0x00, 0x00, 0x00, 0x00, // u64
+    ];
+    assert_eq!(reader.read_i8().unwrap(), -128);
+    assert_eq!(reader.read_u16_be().unwrap(), 0x1234);
+    assert_eq!(reader.read_i16_le().unwrap(), -2);
+    assert_eq!(reader.read_u32_be().unwrap(), 0x01020304);
+    assert_eq!(reader.read_u64_le().unwrap(), 0x01020304);
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn round_trip_all_types() {
+    let mut storage = [0; 512];
+    let mut writer = Cursor::new(&mut storage[..]);
+    writer.write_u8(0xab).unwrap();
+    writer.write_i16_ne(-300).unwrap();
+    writer.write_u32_ne(0xdeadbeef).unwrap();
+    writer.write_i64_be(i64::MIN).unwrap();
+    writer.write_u128_le(u128::MAX - 1).unwrap();
+    writer.write_i128_be(-1).unwrap();
+    writer.write_f32_le(1.5).unwrap();
+    writer.write_f64_be(-0.25).unwrap();
+    writer.write_f64_ne(f64::INFINITY).unwrap();
+    let len = writer.position() as usize;
+    assert_eq!(len, 1 + 2 + 4 + 8 + 16 + 16 + 4 + 8 + 8);
+
+    let mut reader = &storage[..len];
+    assert_eq!(reader.read_u8().unwrap(), 0xab);
+    assert_eq!(reader.read_i16_ne().unwrap(), -300);
+    assert_eq!(reader.read_u32_ne().unwrap(), 0xdeadbeef);
+    assert_eq!(reader.read_i64_be().unwrap(), i64::MIN);
+    assert_eq!(reader.read_u128_le().unwrap(), u128::MAX - 1);
+    assert_eq!(reader.read_i128_be().unwrap(), -1);
+    assert_eq!(reader.read_f32_le().unwrap(), 1.5);
+    assert_eq!(reader.read_f64_be().unwrap(), -0.25);
+    assert_eq!(reader.read_f64_ne().unwrap(), f64::INFINITY);
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn unexpected_eof() {
+    let mut reader: &[u8] = &[1, 2, 3];
+    assert_eq!(reader.read_u32_le().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    let mut reader: &[u8] = &[];
+    assert_eq!(reader.read_u8().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    let mut reader: &[u8] = &[1, 2, 3];
+    assert_eq!(reader.read_uint_be(4).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    let mut dst = [0u16; 2];
+    let mut reader: &[u8] = &[1, 2, 3];
+    assert_eq!(reader.read_u16_le_into(&mut dst).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn write_zero() {
+    let mut storage = [0; 3];
+    let mut writer = Cursor::new(&mut storage[..]);
+    assert_eq!(writer.write_u32_be(1).unwrap_err().kind(), ErrorKind::WriteZero);
+}
+
+#[test]
+fn read_into() {
+    let data: [u8; 8] = [0x00, 0x01, 0x00, 0x02, 0xff, 0xff, 0x80, 0x00];
+    let mut dst = [0u16; 4];
+    (&data[..]).read_u16_be_into(&mut dst).unwrap();
+    assert_eq!(dst, [1, 2, 0xffff, 0x8000]);
+
+    let mut dst = [0i16; 4];
+    (&data[..]).read_i16_le_into(&mut dst).unwrap();
+    assert_eq!(dst, [0x100, 0x200, -1, 0x80]);
+
+    let mut dst = [0i8; 8];
+    (&data[..]).read_i8_into(&mut dst).unwrap();
+    assert_eq!(dst, [0, 1, 0, 2, -1, -1, -128, 0]);
+
+    let mut dst = [0f32; 1];
+    (&1.25f32.to_be_bytes()[..]).read_f32_be_into(&mut dst).unwrap();
+    assert_eq!(dst, [1.25]);
+}
+
+#[test]
+fn read_into_more_than_one_chunk() {
+    // (more values than fit in the buffer on the stack)
+    let data: [u8; 1000] = core::array::from_fn(|i| i as u8);
+    let mut dst = [0u32; 250];
+    let mut reader = &data[..];
+    reader.read_u32_le_into(&mut dst).unwrap();
+    assert!(reader.is_empty());
+    for (i, n) in dst.iter().enumerate() {
+        let b = (i * 4) as u8;
+        assert_eq!(*n, u32::from_le_bytes([b, b + 1, b + 2, b + 3]));
+    }
+
+    let mut dst = [0u64; 200];
+    let mut reader = (&data[..]).chain(&data[..]);
+    reader.read_u64_ne_into(&mut dst).unwrap();
+    assert_eq!(dst[125], u64::from_ne_bytes(data[..8].try_into().unwrap()));
+}
+
+#[test]
+fn variable_width() {
+    let mut reader: &[u8] = &[0x01, 0x02, 0x03, 0x03, 0x02, 0x01, 0xff, 0xfe, 0xff, 0xff, 0xff];
+    assert_eq!(reader.read_uint_be(3).unwrap(), 0x010203);
+    assert_eq!(reader.read_uint_le(3).unwrap(), 0x010203);
+    assert_eq!(reader.read_int_be(2).unwrap(), -2);
+    assert_eq!(reader.read_int_le(3).unwrap(), -1);
+
+    let mut reader: &[u8] = &[0x7f, 0xff, 0xff];
+    assert_eq!(reader.read_int_be(3).unwrap(), 0x7fffff);
+
+    let mut storage = [0; 19];
+    let mut writer = Cursor::new(&mut storage[..]);
+    writer.write_uint_be(0x010203, 3).unwrap();
+    writer.write_uint_le(0x010203, 3).unwrap();
+    writer.write_int_be(-2, 2).unwrap();
+    writer.write_int_le(-1, 3).unwrap();
+    writer.write_uint_ne(u64::MAX, 8).unwrap();
+    assert_eq!(storage[..11], [0x01, 0x02, 0x03, 0x03, 0x02, 0x01, 0xff, 0xfe, 0xff, 0xff, 0xff]);
+    assert_eq!(storage[11..], [0xff; 8]);
+
+    let mut writer = Cursor::new(&mut storage[..]);
+    writer.write_int_ne(-257, 5).unwrap();
+    assert_eq!((&storage[..5]).read_int_ne(5).unwrap(), -257);
+    assert_eq!((&storage[..5]).read_uint_ne(5).unwrap(), 0xff_ffff_feff);
+}
+
+#[test]
+#[should_panic = "invalid number of bytes: 9"]
+fn read_uint_too_wide() {
+    let _ = (&[0; 16][..]).read_uint_le(9);
+}
+
+#[test]
+#[should_panic = "256 does not fit in 1 bytes"]
+fn write_uint_too_large() {
+    let _ = Cursor::new(&mut [0; 8][..]).write_uint_be(256, 1);
+}
+
+#[test]
+#[should_panic = "-129 does not fit in 1 bytes"]
+fn write_int_too_small() {
+    let _ = Cursor::new(&mut [0; 8][..]).write_int_le(-129, 1);
+}
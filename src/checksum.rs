@@ -0,0 +1,280 @@
+//! Checksums, & adapters to compute them as bytes are read or written.
+//!
+//! [`HashingReader`] & [`HashingWriter`] wrap a reader or a writer & update a
+//! [`Checksum`] with the bytes that pass through them, so that a stream can be
+//! checksummed without a separate pass over it.
+//!
+//! The built-in checksums are table-driven, with the tables computed at
+//! compile time, & need neither `alloc` nor `std`:
+//!
+//! - [`Crc8`] - CRC-8/SMBUS
+//! - [`Crc16Ccitt`] - CRC-16/CCITT-FALSE
+//! - [`Crc16Modbus`] - CRC-16/MODBUS
+//! - [`Crc32`] - CRC-32/ISO-HDLC, as used by Ethernet, zlib & PNG
+//! - [`Crc32c`] - CRC-32C (Castagnoli), as used by iSCSI & ext4
+//! - [`Adler32`] - Adler-32, as used by zlib
+//! - [`Fletcher16`] - Fletcher-16
+
+// (ADDED in this fork)
+
+#[cfg(test)]
+mod tests;
+
+mod adler;
+mod crc;
+
+use core::ops::Deref;
+
+pub use self::adler::{Adler32, Fletcher16};
+pub use self::crc::{Crc8, Crc16Ccitt, Crc16Modbus, Crc32, Crc32c};
+use crate::observe::{Unconsumed, read_buf_with};
+use crate::{BufRead, IoSlice, IoSliceMut, Read, ReadBuf, Result, Write};
+
+/// A checksum, computed over a sequence of bytes.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::checksum::{Checksum, Crc32};
+///
+/// let mut crc = Crc32::new();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.value(), 0xcbf43926);
+/// ```
+pub trait Checksum {
+    /// The type of the value of this checksum.
+    type Output;
+
+    /// Updates this checksum with more bytes.
+    fn update(&mut self, bytes: &[u8]);
+
+    /// Returns the value of this checksum, for the bytes so far.
+    fn value(&self) -> Self::Output;
+
+    /// Resets this checksum to its initial state, as if no bytes were given.
+    fn reset(&mut self);
+}
+
+impl<C: Checksum + ?Sized> Checksum for &mut C {
+    type Output = C::Output;
+
+    #[inline]
+    fn update(&mut self, bytes: &[u8]) {
+        (**self).update(bytes)
+    }
+
+    #[inline]
+    fn value(&self) -> Self::Output {
+        (**self).value()
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+}
+
+// Updates `checksum` with the first `n` bytes of `bufs`
+fn update_vectored<C: Checksum, B: Deref<Target = [u8]>>(
+    checksum: &mut C,
+    bufs: &[B],
+    mut n: usize,
+) {
+    for buf in bufs {
+        if n == 0 {
+            break;
+        }
+        let len = buf.len().min(n);
+        checksum.update(&buf[..len]);
+        n -= len;
+    }
+}
+
+/// Updates a checksum with the bytes read from a reader.
+///
+/// All of the ways to read are covered, including vectored reads &
+/// [`read_buf`](Read::read_buf). With [`BufRead`], the bytes are counted once
+/// they are consumed, rather than when they are returned by
+/// [`fill_buf`](BufRead::fill_buf). Consuming does no I/O: only the bytes that
+/// `fill_buf` returned are counted, & if they cannot be got again, the error
+/// is returned by the next call to read.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::checksum::{Checksum, Crc32, HashingReader};
+/// use portable_io::Read;
+///
+/// let mut reader = HashingReader::new(Crc32::new(), &b"123456789"[..]);
+/// let mut buf = [0; 16];
+/// while reader.read(&mut buf).unwrap() > 0 {}
+/// assert_eq!(reader.checksum().value(), 0xcbf43926);
+/// ```
+#[derive(Debug)]
+pub struct HashingReader<R, H> {
+    inner: R,
+    checksum: H,
+    unconsumed: Unconsumed,
+}
+
+impl<R, H: Checksum> HashingReader<R, H> {
+    /// Creates a new `HashingReader<R, H>`, which updates `checksum`.
+    pub fn new(checksum: H, inner: R) -> HashingReader<R, H> {
+        HashingReader { inner, checksum, unconsumed: Unconsumed::default() }
+    }
+
+    /// Gets a reference to the checksum.
+    pub fn checksum(&self) -> &H {
+        &self.checksum
+    }
+
+    /// Gets a mutable reference to the checksum.
+    pub fn checksum_mut(&mut self) -> &mut H {
+        &mut self.checksum
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// The bytes read directly from the underlying reader are not counted.
+    pub fn get_mut(&mut self) -> &mut R {
+        self.unconsumed.reset();
+        &mut self.inner
+    }
+
+    /// Unwraps this `HashingReader<R, H>`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Unwraps this `HashingReader<R, H>`, returning the underlying reader &
+    /// the checksum.
+    pub fn into_parts(self) -> (R, H) {
+        (self.inner, self.checksum)
+    }
+}
+
+impl<R: Read, H: Checksum> Read for HashingReader<R, H> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.unconsumed.take_error()?;
+        let n = self.inner.read(buf)?;
+        self.checksum.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        self.unconsumed.take_error()?;
+        let n = self.inner.read_vectored(bufs)?;
+        update_vectored(&mut self.checksum, bufs, n);
+        Ok(n)
+    }
+
+    fn is_read_vectored(&self) -> bool {
+        self.inner.is_read_vectored()
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> Result<()> {
+        self.unconsumed.take_error()?;
+        // (the bytes that were filled are counted, even if there is an error)
+        read_buf_with(&mut self.inner, buf, |filled| self.checksum.update(filled))
+    }
+}
+
+impl<R: BufRead, H: Checksum> BufRead for HashingReader<R, H> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.unconsumed.fill_buf(&mut self.inner)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.unconsumed.consume(&mut self.inner, amt, |consumed| self.checksum.update(consumed))
+    }
+}
+
+/// Updates a checksum with the bytes written to a writer.
+///
+/// Only the bytes that the underlying writer accepts are counted, including
+/// with vectored writes.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::checksum::{Adler32, Checksum, HashingWriter};
+/// use portable_io::Write;
+///
+/// let mut storage = [0; 16];
+/// let mut writer = HashingWriter::new(Adler32::new(), &mut storage[..]);
+/// writer.write_all(b"Wikipedia").unwrap();
+/// assert_eq!(writer.checksum().value(), 0x11e60398);
+/// ```
+#[derive(Debug)]
+pub struct HashingWriter<W, H> {
+    inner: W,
+    checksum: H,
+}
+
+impl<W, H: Checksum> HashingWriter<W, H> {
+    /// Creates a new `HashingWriter<W, H>`, which updates `checksum`.
+    pub fn new(checksum: H, inner: W) -> HashingWriter<W, H> {
+        HashingWriter { inner, checksum }
+    }
+
+    /// Gets a reference to the checksum.
+    pub fn checksum(&self) -> &H {
+        &self.checksum
+    }
+
+    /// Gets a mutable reference to the checksum.
+    pub fn checksum_mut(&mut self) -> &mut H {
+        &mut self.checksum
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// The bytes written directly to the underlying writer are not counted.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `HashingWriter<W, H>`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Unwraps this `HashingWriter<W, H>`, returning the underlying writer &
+    /// the checksum.
+    pub fn into_parts(self) -> (W, H) {
+        (self.inner, self.checksum)
+    }
+}
+
+impl<W: Write, H: Checksum> Write for HashingWriter<W, H> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.checksum.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        let n = self.inner.write_vectored(bufs)?;
+        update_vectored(&mut self.checksum, bufs, n);
+        Ok(n)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,117 @@
+use super::Checksum;
+
+// The largest prime number smaller than 2^16
+const ADLER_MOD: u32 = 65521;
+
+// The largest number of bytes for which the sums of Adler-32 cannot overflow a
+// `u32` before they are reduced, as in zlib
+const ADLER_CHUNK_LEN: usize = 5552;
+
+// The same for the sums of Fletcher-16, which are reduced modulo 255
+const FLETCHER_CHUNK_LEN: usize = 5802;
+
+/// The Adler-32 checksum, as used by zlib.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::checksum::{Adler32, Checksum};
+///
+/// let mut adler = Adler32::new();
+/// adler.update(b"Wikipedia");
+/// assert_eq!(adler.value(), 0x11e60398);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    /// Creates a new `Adler32`.
+    pub const fn new() -> Adler32 {
+        Adler32 { a: 1, b: 0 }
+    }
+}
+
+impl Default for Adler32 {
+    fn default() -> Adler32 {
+        Adler32::new()
+    }
+}
+
+impl Checksum for Adler32 {
+    type Output = u32;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(ADLER_CHUNK_LEN) {
+            for &byte in chunk {
+                self.a += u32::from(byte);
+                self.b += self.a;
+            }
+            self.a %= ADLER_MOD;
+            self.b %= ADLER_MOD;
+        }
+    }
+
+    fn value(&self) -> u32 {
+        self.b << 16 | self.a
+    }
+
+    fn reset(&mut self) {
+        *self = Adler32::new();
+    }
+}
+
+/// The Fletcher-16 checksum, with two 8-bit sums modulo 255.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::checksum::{Checksum, Fletcher16};
+///
+/// let mut fletcher = Fletcher16::new();
+/// fletcher.update(b"abcde");
+/// assert_eq!(fletcher.value(), 0xc8f0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fletcher16 {
+    sum1: u32,
+    sum2: u32,
+}
+
+impl Fletcher16 {
+    /// Creates a new `Fletcher16`.
+    pub const fn new() -> Fletcher16 {
+        Fletcher16 { sum1: 0, sum2: 0 }
+    }
+}
+
+impl Default for Fletcher16 {
+    fn default() -> Fletcher16 {
+        Fletcher16::new()
+    }
+}
+
+impl Checksum for Fletcher16 {
+    type Output = u16;
+
+    fn update(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(FLETCHER_CHUNK_LEN) {
+            for &byte in chunk {
+                self.sum1 += u32::from(byte);
+                self.sum2 += self.sum1;
+            }
+            self.sum1 %= 255;
+            self.sum2 %= 255;
+        }
+    }
+
+    fn value(&self) -> u16 {
+        (self.sum2 << 8 | self.sum1) as u16
+    }
+
+    fn reset(&mut self) {
+        *self = Fletcher16::new();
+    }
+}
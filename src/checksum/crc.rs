@@ -0,0 +1,123 @@
+use super::Checksum;
+
+// Defines a table-driven CRC, with the parameters of the Rocksoft model: the
+// width is that of `$ty`, & the polynomial is given in the order in which the
+// bits are processed, i.e. reversed if the CRC is reflected
+macro_rules! crc {
+    (
+        $(#[$attr:meta])*
+        $name:ident: $ty:ty, poly = $poly:literal, init = $init:literal, reflected = $reflected:literal,
+        xorout = $xorout:literal, check = $check:literal
+    ) => {
+        $(#[$attr])*
+        ///
+        /// # Example code
+        ///
+        /// ```
+        #[doc = concat!("use portable_io::checksum::{Checksum, ", stringify!($name), "};")]
+        ///
+        #[doc = concat!("let mut crc = ", stringify!($name), "::new();")]
+        /// crc.update(b"123456789");
+        #[doc = concat!("assert_eq!(crc.value(), ", stringify!($check), ");")]
+        /// ```
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            crc: $ty,
+        }
+
+        impl $name {
+            const TABLE: [$ty; 256] = {
+                let mut table = [0; 256];
+                let mut i = 0;
+                while i < 256 {
+                    let mut crc = if $reflected { i as $ty } else { (i as $ty) << (<$ty>::BITS - 8) };
+                    let mut bit = 0;
+                    while bit < 8 {
+                        crc = if $reflected {
+                            if crc & 1 != 0 { (crc >> 1) ^ $poly } else { crc >> 1 }
+                        } else if crc >> (<$ty>::BITS - 1) != 0 {
+                            (crc << 1) ^ $poly
+                        } else {
+                            crc << 1
+                        };
+                        bit += 1;
+                    }
+                    table[i] = crc;
+                    i += 1;
+                }
+                table
+            };
+
+            #[doc = concat!("Creates a new `", stringify!($name), "`.")]
+            pub const fn new() -> $name {
+                $name { crc: $init }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> $name {
+                $name::new()
+            }
+        }
+
+        impl Checksum for $name {
+            type Output = $ty;
+
+            fn update(&mut self, bytes: &[u8]) {
+                let mut crc = self.crc;
+                for &byte in bytes {
+                    // (the shift by 8 bits is checked, as it overflows an 8-bit CRC)
+                    crc = if $reflected {
+                        crc.checked_shr(8).unwrap_or(0) ^ Self::TABLE[usize::from(crc as u8 ^ byte)]
+                    } else {
+                        let index = (crc >> (<$ty>::BITS - 8)) as u8 ^ byte;
+                        crc.checked_shl(8).unwrap_or(0) ^ Self::TABLE[usize::from(index)]
+                    };
+                }
+                self.crc = crc;
+            }
+
+            fn value(&self) -> $ty {
+                self.crc ^ $xorout
+            }
+
+            fn reset(&mut self) {
+                self.crc = $init;
+            }
+        }
+    };
+}
+
+crc! {
+    /// The CRC-8/SMBUS checksum, with the polynomial `0x07`, as used by SMBus
+    /// & ATM.
+    Crc8: u8, poly = 0x07, init = 0x00, reflected = false, xorout = 0x00, check = 0xf4
+}
+
+crc! {
+    /// The CRC-16/CCITT-FALSE checksum (also known as CRC-16/IBM-3740), with
+    /// the polynomial `0x1021` & the initial value `0xFFFF`, not reflected.
+    Crc16Ccitt: u16, poly = 0x1021, init = 0xffff, reflected = false, xorout = 0x0000, check = 0x29b1
+}
+
+crc! {
+    /// The CRC-16/MODBUS checksum, with the polynomial `0x8005` & the initial
+    /// value `0xFFFF`, reflected.
+    ///
+    /// Note that Modbus sends the value in little-endian byte order.
+    Crc16Modbus: u16, poly = 0xa001, init = 0xffff, reflected = true, xorout = 0x0000, check = 0x4b37
+}
+
+crc! {
+    /// The CRC-32/ISO-HDLC checksum, with the polynomial `0x04C11DB7`,
+    /// reflected, as used by Ethernet, zlib, gzip & PNG.
+    Crc32: u32, poly = 0xedb88320, init = 0xffffffff, reflected = true, xorout = 0xffffffff,
+    check = 0xcbf43926
+}
+
+crc! {
+    /// The CRC-32C (Castagnoli) checksum, with the polynomial `0x1EDC6F41`,
+    /// reflected, as used by iSCSI, SCTP & ext4.
+    Crc32c: u32, poly = 0x82f63b78, init = 0xffffffff, reflected = true, xorout = 0xffffffff,
+    check = 0xe3069283
+}
@@ -0,0 +1,190 @@
+use super::{
+    Adler32, Checksum, Crc8, Crc16Ccitt, Crc16Modbus, Crc32, Crc32c, Fletcher16, HashingReader,
+    HashingWriter,
+};
+use crate::test_util::Flaky;
+use crate::{BufRead, BufReader, Cursor, ErrorKind, IoSlice, IoSliceMut, Read, ReadBuf, Write};
+
+const CHECK_INPUT: &[u8] = b"123456789";
+
+// Returns the value of a new checksum, updated with `bytes`
+fn checksum_of<C: Checksum + Default>(bytes: &[u8]) -> C::Output {
+    let mut checksum = C::default();
+    checksum.update(bytes);
+    checksum.value()
+}
+
+#[test]
+fn check_values() {
+    assert_eq!(checksum_of::<Crc8>(CHECK_INPUT), 0xf4);
+    assert_eq!(checksum_of::<Crc16Ccitt>(CHECK_INPUT), 0x29b1);
+    assert_eq!(checksum_of::<Crc16Modbus>(CHECK_INPUT), 0x4b37);
+    assert_eq!(checksum_of::<Crc32>(CHECK_INPUT), 0xcbf43926);
+    assert_eq!(checksum_of::<Crc32c>(CHECK_INPUT), 0xe3069283);
+    assert_eq!(checksum_of::<Adler32>(CHECK_INPUT), 0x091e01de);
+    assert_eq!(checksum_of::<Fletcher16>(b"abcdef"), 0x2057);
+    assert_eq!(checksum_of::<Fletcher16>(b"abcdefgh"), 0x0627);
+}
+
+#[test]
+fn empty_input() {
+    assert_eq!(checksum_of::<Crc8>(b""), 0x00);
+    assert_eq!(checksum_of::<Crc16Ccitt>(b""), 0xffff);
+    assert_eq!(checksum_of::<Crc32>(b""), 0);
+    assert_eq!(checksum_of::<Adler32>(b""), 1);
+    assert_eq!(checksum_of::<Fletcher16>(b""), 0);
+}
+
+#[test]
+fn update_in_pieces_and_reset() {
+    let data: [u8; 1000] = core::array::from_fn(|i| (i * 7) as u8);
+    for split in [0, 1, 3, 500, 999, 1000] {
+        let (first, second) = data.split_at(split);
+        let mut crc = Crc32c::new();
+        crc.update(first);
+        crc.update(second);
+        assert_eq!(crc.value(), checksum_of::<Crc32c>(&data));
+
+        let mut fletcher = Fletcher16::new();
+        fletcher.update(first);
+        fletcher.update(second);
+        assert_eq!(fletcher.value(), checksum_of::<Fletcher16>(&data));
+    }
+
+    let mut crc = Crc16Modbus::new();
+    crc.update(&data);
+    crc.reset();
+    crc.update(CHECK_INPUT);
+    assert_eq!(crc.value(), 0x4b37);
+}
+
+#[test]
+fn long_input_does_not_overflow_sums() {
+    // (all bytes set, for the largest sums, over more than one chunk)
+    let data = [0xff; 20000];
+
+    let (mut a, mut b) = (1u32, 0u32);
+    let (mut sum1, mut sum2) = (0u32, 0u32);
+    for &byte in &data {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+        sum1 = (sum1 + u32::from(byte)) % 255;
+        sum2 = (sum2 + sum1) % 255;
+    }
+    assert_eq!(checksum_of::<Adler32>(&data), b << 16 | a);
+    assert_eq!(checksum_of::<Fletcher16>(&data), (sum2 << 8 | sum1) as u16);
+}
+
+#[test]
+fn hashing_reader() {
+    let mut reader = HashingReader::new(Crc32::new(), CHECK_INPUT);
+    let mut buf = [0; 4];
+    while reader.read(&mut buf).unwrap() > 0 {}
+    assert_eq!(reader.checksum().value(), 0xcbf43926);
+
+    // (vectored reads)
+    let mut reader = HashingReader::new(Crc32::new(), CHECK_INPUT);
+    let (mut first, mut second) = ([0; 2], [0; 3]);
+    loop {
+        let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+        if reader.read_vectored(&mut bufs).unwrap() == 0 {
+            break;
+        }
+    }
+    assert_eq!(reader.checksum().value(), 0xcbf43926);
+
+    // (`read_buf`, into a buffer that is partly filled already)
+    let mut reader = HashingReader::new(Crc32::new(), CHECK_INPUT);
+    let mut storage = [0; 16];
+    let mut buf = ReadBuf::new(&mut storage);
+    buf.append(b"xx");
+    reader.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled(), b"xx123456789");
+    assert_eq!(reader.checksum().value(), 0xcbf43926);
+}
+
+#[test]
+fn hashing_reader_counts_consumed_bytes() {
+    let mut storage = [0; 4];
+    let buffered = BufReader::with_slice(&mut storage, CHECK_INPUT);
+    let mut reader = HashingReader::new(Crc16Ccitt::new(), buffered);
+    assert_eq!(reader.fill_buf().unwrap(), b"1234");
+    reader.consume(1);
+    assert_eq!(reader.checksum().value(), checksum_of::<Crc16Ccitt>(b"1"));
+
+    // (bytes returned by `fill_buf` again are not counted twice)
+    assert_eq!(reader.fill_buf().unwrap(), b"234");
+    reader.consume(3);
+    assert_eq!(reader.checksum().value(), checksum_of::<Crc16Ccitt>(b"1234"));
+
+    let mut buf = [0; 2];
+    reader.read_exact(&mut buf).unwrap();
+    crate::copy_buf(&mut reader, &mut crate::sink()).unwrap();
+    assert_eq!(reader.checksum().value(), 0x29b1);
+}
+
+#[test]
+fn hashing_reader_consumes_without_io() {
+    // (a reader that counts the calls to read)
+    struct Reads<'a>(&'a [u8], usize);
+
+    impl Read for Reads<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> crate::Result<usize> {
+            self.1 += 1;
+            self.0.read(buf)
+        }
+    }
+
+    let mut storage = [0; 4];
+    let buffered = BufReader::with_slice(&mut storage, Reads(CHECK_INPUT, 0));
+    let mut reader = HashingReader::new(Crc32::new(), buffered);
+    reader.consume(0);
+    assert_eq!(reader.get_ref().get_ref().1, 0);
+
+    assert_eq!(reader.fill_buf().unwrap(), b"1234");
+    reader.consume(2);
+    reader.consume(2);
+    assert_eq!(reader.get_ref().get_ref().1, 1);
+    assert_eq!(reader.checksum().value(), checksum_of::<Crc32>(b"1234"));
+}
+
+#[test]
+fn hashing_reader_reports_consumed_bytes_it_missed() {
+    // (`fill_buf` fails with `WouldBlock` & `Interrupted` before each byte)
+    let mut reader = HashingReader::new(Crc32::new(), Flaky::new(CHECK_INPUT));
+    assert_eq!(reader.fill_buf().unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(reader.fill_buf().unwrap_err().kind(), ErrorKind::Interrupted);
+    assert_eq!(reader.fill_buf().unwrap(), b"1");
+    // (getting the byte again fails, so it is not hashed & the error is kept)
+    reader.consume(1);
+    assert_eq!(reader.checksum().value(), Crc32::new().value());
+    assert_eq!(reader.read(&mut [0; 4]).unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(reader.get_ref().inner, b"23456789");
+}
+
+#[test]
+fn hashing_writer_counts_accepted_bytes() {
+    // (the underlying writer only has room for part of the input)
+    let mut storage = [0; 6];
+    let mut writer = HashingWriter::new(Crc8::new(), &mut storage[..]);
+    assert_eq!(writer.write(b"1234").unwrap(), 4);
+    assert_eq!(writer.write(b"56789").unwrap(), 2);
+    assert_eq!(writer.checksum().value(), checksum_of::<Crc8>(b"123456"));
+
+    let mut storage = [0; 16];
+    let mut writer = HashingWriter::new(Crc8::new(), Cursor::new(&mut storage[..]));
+    let bufs = [IoSlice::new(b"1234"), IoSlice::new(b""), IoSlice::new(b"56789")];
+    assert_eq!(writer.write_vectored(&bufs).unwrap(), 9);
+    let (_, crc) = writer.into_parts();
+    assert_eq!(crc.value(), 0xf4);
+}
+
+#[test]
+fn hashing_with_borrowed_checksum() {
+    let mut adler = Adler32::new();
+    let mut storage = [0; 16];
+    let mut writer = HashingWriter::new(&mut adler, &mut storage[..]);
+    writer.write_all(b"Wiki").unwrap();
+    writer.write_all(b"pedia").unwrap();
+    assert_eq!(adler.value(), 0x11e60398);
+}
@@ -0,0 +1,349 @@
+//! Streaming encoders & decoders, for framing & for text encodings.
+//!
+//! All of them work in a fixed amount of memory, without `alloc`: the encoders
+//! implement [`Write`] & write the encoded bytes to an underlying writer, & the
+//! decoders implement [`Read`] & read the encoded bytes from an underlying
+//! reader. Malformed input results in an error of the kind
+//! [`ErrorKind::InvalidData`].
+//!
+//! ## Framing
+//!
+//! Byte stuffing reserves a byte value to mark the end of each frame, & encodes
+//! the payload of the frames so that it never contains that value. This way,
+//! after corrupted or missing data, the receiver can start again at the next
+//! frame by looking for the next delimiter. This module has:
+//!
+//! - [COBS](https://en.wikipedia.org/wiki/Consistent_Overhead_Byte_Stuffing),
+//!   with `0x00` as the delimiter & an overhead of one byte in 254, as well as
+//!   COBS/R, a variant that often saves the byte of overhead of small frames
+//! - [SLIP](https://www.rfc-editor.org/rfc/rfc1055) (RFC 1055), with `0xC0`
+//!   as the delimiter & an overhead of one byte for each `0xC0` or `0xDB`
+//!
+//! The bytes written to an encoder are the payload of the current frame, which
+//! is ended by `end_frame`. The decoders read from a [`BufRead`]: the bytes read
+//! are the payload of the current frame, & `Ok(0)` is returned at the end of
+//! each frame, as well as at EOF; `read_frame` reads a whole frame into a
+//! buffer.
+//!
+//! After malformed input, a decoder skips the rest of the frame, up to the next
+//! delimiter. Delimiters without a frame in between, which are sometimes sent
+//! to start from a clean state after line noise, are skipped as well.
+//!
+//! ## Text encodings
+//!
+//! [`HexWriter`] & [`HexReader`] encode & decode hexadecimal, & [`Base64Writer`]
+//! & [`Base64Reader`] encode & decode [base64](https://www.rfc-editor.org/rfc/rfc4648)
+//! with the standard or the URL-safe alphabet. The decoders can skip whitespace,
+//! & report an invalid character with its offset in the input, as an
+//! [`InvalidCharacter`].
+//!
+//! # Example code
+//!
+//! ```
+//! use portable_io::codec::{CobsDecoder, CobsEncoder};
+//! use portable_io::{Cursor, Write};
+//!
+//! let mut storage = [0; 16];
+//! let mut encoder = CobsEncoder::new(Cursor::new(&mut storage[..]));
+//! encoder.write_all(&[1, 0, 2]).unwrap();
+//! encoder.end_frame().unwrap();
+//! encoder.write_all(&[3, 4]).unwrap();
+//! encoder.end_frame().unwrap();
+//! let len = encoder.get_ref().position() as usize;
+//! assert_eq!(storage[..len], [2, 1, 2, 2, 0, 3, 3, 4, 0]);
+//!
+//! let mut decoder = CobsDecoder::new(&storage[..len]);
+//! let mut buf = [0; 8];
+//! assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(3));
+//! assert_eq!(buf[..3], [1, 0, 2]);
+//! assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(2));
+//! assert_eq!(buf[..2], [3, 4]);
+//! assert_eq!(decoder.read_frame(&mut buf).unwrap(), None);
+//! ```
+//!
+//! [`Read`]: crate::Read
+//! [`Write`]: crate::Write
+//! [`BufRead`]: crate::BufRead
+//! [`ErrorKind::InvalidData`]: crate::ErrorKind::InvalidData
+
+// (ADDED in this fork)
+
+#[cfg(test)]
+mod tests;
+
+mod base64;
+mod cobs;
+mod hex;
+mod slip;
+
+use core::fmt;
+
+use crate::{Error, ErrorKind, Read, Result, Write};
+
+pub use self::base64::{Base64Alphabet, Base64Reader, Base64Writer};
+pub use self::cobs::{CobsDecoder, CobsEncoder};
+pub use self::hex::{HexReader, HexWriter};
+pub use self::slip::{SlipDecoder, SlipEncoder};
+
+// The size of the buffers of the text encoders & decoders
+const TEXT_BUF_SIZE: usize = 256;
+
+// The progress of `read_frame` through the current frame
+#[derive(Debug, Default)]
+struct FrameProgress {
+    // (the number of bytes of the payload read so far)
+    filled: usize,
+    // (whether the rest of the frame is to be skipped, as it did not fit)
+    discard: bool,
+}
+
+// Implemented by the decoders, which return `Ok(0)` from `decode` at the end of
+// each frame
+trait Decode {
+    fn decode(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    // Skips any delimiters before the next frame, & returns whether there is one
+    fn start_frame(&mut self) -> Result<bool>;
+
+    fn progress(&mut self) -> &mut FrameProgress;
+}
+
+// Skips the rest of a frame that did not fit in the buffer given to `read_frame`
+fn discard<D: Decode>(decoder: &mut D) -> Result<()> {
+    if !decoder.progress().discard {
+        return Ok(());
+    }
+    let mut scratch = [0; 64];
+    loop {
+        match decoder.decode(&mut scratch) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                // (after malformed input, the decoder skips the rest of the frame anyway)
+                decoder.progress().discard = e.kind() == ErrorKind::WouldBlock;
+                return Err(e);
+            }
+        }
+    }
+    decoder.progress().discard = false;
+    Ok(())
+}
+
+fn read<D: Decode>(decoder: &mut D, buf: &mut [u8]) -> Result<usize> {
+    discard(decoder)?;
+    decoder.decode(buf)
+}
+
+fn read_frame<D: Decode>(decoder: &mut D, buf: &mut [u8]) -> Result<Option<usize>> {
+    discard(decoder)?;
+    if decoder.progress().filled == 0 && !decoder.start_frame()? {
+        return Ok(None);
+    }
+    loop {
+        let filled = decoder.progress().filled;
+        let result = if filled < buf.len() {
+            decoder.decode(&mut buf[filled..])
+        } else {
+            // (the frame fits only if it ends here)
+            decoder.decode(&mut [0])
+        };
+        match result {
+            Ok(0) => {
+                decoder.progress().filled = 0;
+                return Ok(Some(filled));
+            }
+            Ok(_) if filled == buf.len() => {
+                *decoder.progress() = FrameProgress { filled: 0, discard: true };
+                return Err(Error::new_const(
+                    ErrorKind::InvalidInput,
+                    &"frame is larger than the buffer",
+                ));
+            }
+            Ok(n) => decoder.progress().filled += n,
+            Err(e) => {
+                if e.kind() != ErrorKind::WouldBlock {
+                    decoder.progress().filled = 0;
+                }
+                return Err(e);
+            }
+        }
+    }
+}
+
+fn unexpected_eof() -> Error {
+    Error::new_const(ErrorKind::UnexpectedEof, &"stream ended in the middle of a frame")
+}
+
+/// The payload of the error for an invalid character in the input of a text
+/// decoder, such as [`HexReader`] or [`Base64Reader`].
+///
+/// With the `alloc` feature, the error of the kind [`ErrorKind::InvalidData`]
+/// has an `InvalidCharacter` as its payload, which is available from
+/// [`Error::get_ref`]. Otherwise, the error has the offset of the character as
+/// its [`code`](Error::code), saturated to `i32::MAX`, as there is no room for
+/// a payload.
+///
+/// [`ErrorKind::InvalidData`]: crate::ErrorKind::InvalidData
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use portable_io::codec::{HexReader, InvalidCharacter};
+/// use portable_io::Read;
+///
+/// let mut reader = HexReader::new(&b"00ff0g"[..]);
+/// let mut buf = [0; 4];
+/// assert_eq!(reader.read(&mut buf).unwrap(), 2);
+/// let error = reader.read(&mut buf).unwrap_err();
+/// assert_eq!(error.to_string(), "invalid character 'g' at offset 5");
+///
+/// let invalid = error.get_ref().unwrap().downcast_ref::<InvalidCharacter>().unwrap();
+/// assert_eq!(invalid.byte(), b'g');
+/// assert_eq!(invalid.offset(), 5);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidCharacter {
+    byte: u8,
+    offset: u64,
+}
+
+impl InvalidCharacter {
+    /// Returns the invalid character.
+    pub fn byte(&self) -> u8 {
+        self.byte
+    }
+
+    /// Returns the offset of the invalid character in the input.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl fmt::Display for InvalidCharacter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.byte.is_ascii_graphic() {
+            write!(f, "invalid character '{}' at offset {}", self.byte as char, self.offset)
+        } else {
+            write!(f, "invalid character 0x{:02x} at offset {}", self.byte, self.offset)
+        }
+    }
+}
+
+impl core::error::Error for InvalidCharacter {}
+
+#[cfg(feature = "alloc")]
+fn invalid_character(byte: u8, offset: u64) -> Error {
+    Error::new(ErrorKind::InvalidData, InvalidCharacter { byte, offset })
+}
+
+#[cfg(not(feature = "alloc"))]
+fn invalid_character(_byte: u8, offset: u64) -> Error {
+    let offset = i32::try_from(offset).unwrap_or(i32::MAX);
+    Error::new_const_with_code(ErrorKind::InvalidData, offset, &"invalid character")
+}
+
+// The input of the text decoders, read from the underlying reader in chunks
+#[derive(Debug)]
+struct TextInput<R> {
+    inner: R,
+    buf: [u8; TEXT_BUF_SIZE],
+    pos: usize,
+    len: usize,
+    // (the offset in the input of `buf[pos]`)
+    offset: u64,
+    ignore_whitespace: bool,
+}
+
+impl<R: Read> TextInput<R> {
+    fn new(inner: R) -> TextInput<R> {
+        TextInput {
+            inner,
+            buf: [0; TEXT_BUF_SIZE],
+            pos: 0,
+            len: 0,
+            offset: 0,
+            ignore_whitespace: false,
+        }
+    }
+
+    // Returns the next character that is not skipped & its offset, or `None` at EOF
+    fn next(&mut self) -> Result<Option<(u8, u64)>> {
+        loop {
+            if self.pos == self.len {
+                match self.inner.read(&mut self.buf) {
+                    Ok(0) => return Ok(None),
+                    Ok(n) => {
+                        self.pos = 0;
+                        self.len = n;
+                    }
+                    Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            let byte = self.buf[self.pos];
+            let offset = self.offset;
+            self.pos += 1;
+            self.offset += 1;
+            if !(self.ignore_whitespace && byte.is_ascii_whitespace()) {
+                return Ok(Some((byte, offset)));
+            }
+        }
+    }
+
+    // Puts back the character last returned by `next`
+    fn unget(&mut self) {
+        self.pos -= 1;
+        self.offset -= 1;
+    }
+}
+
+// The output of the text encoders, written to the underlying writer in chunks
+#[derive(Debug)]
+struct TextOutput<W> {
+    inner: W,
+    buf: [u8; TEXT_BUF_SIZE],
+    pos: usize,
+    len: usize,
+}
+
+impl<W: Write> TextOutput<W> {
+    fn new(inner: W) -> TextOutput<W> {
+        TextOutput { inner, buf: [0; TEXT_BUF_SIZE], pos: 0, len: 0 }
+    }
+
+    // Returns the room left for more output, after `write_out`
+    fn room(&self) -> usize {
+        TEXT_BUF_SIZE - self.len
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.buf[self.len..][..bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    fn write_out(&mut self) -> Result<()> {
+        while self.pos < self.len {
+            match self.inner.write(&self.buf[self.pos..self.len]) {
+                Ok(0) => {
+                    return Err(Error::new_const(
+                        ErrorKind::WriteZero,
+                        &"failed to write whole buffer",
+                    ));
+                }
+                Ok(n) => self.pos += n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.pos = 0;
+        self.len = 0;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.write_out()?;
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,357 @@
+use super::{TextInput, TextOutput, invalid_character};
+use crate::{Error, ErrorKind, Read, Result, Write};
+
+const STANDARD_SYMBOLS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_SYMBOLS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+const PAD: u8 = b'=';
+
+/// The alphabet of a base64 encoding, as defined by RFC 4648.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base64Alphabet {
+    /// The standard alphabet, with `+` & `/`.
+    Standard,
+    /// The URL & filename safe alphabet, with `-` & `_`.
+    UrlSafe,
+}
+
+impl Base64Alphabet {
+    fn symbols(self) -> &'static [u8; 64] {
+        match self {
+            Base64Alphabet::Standard => STANDARD_SYMBOLS,
+            Base64Alphabet::UrlSafe => URL_SAFE_SYMBOLS,
+        }
+    }
+
+    fn decode(self, byte: u8) -> Option<u8> {
+        match (byte, self) {
+            (b'A'..=b'Z', _) => Some(byte - b'A'),
+            (b'a'..=b'z', _) => Some(byte - b'a' + 26),
+            (b'0'..=b'9', _) => Some(byte - b'0' + 52),
+            (b'+', Base64Alphabet::Standard) | (b'-', Base64Alphabet::UrlSafe) => Some(62),
+            (b'/', Base64Alphabet::Standard) | (b'_', Base64Alphabet::UrlSafe) => Some(63),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes the bytes written to it as base64, & writes that to a writer.
+///
+/// The bytes are encoded in groups of three, so the last one or two bytes are
+/// only encoded by [`finish`](Base64Writer::finish), which must be called at
+/// the end of the input; [`flush`](Write::flush) only writes the groups that
+/// are complete.
+///
+/// The encoded bytes are written to the underlying writer in chunks; if that
+/// returns an error after the bytes given to [`write`](Write::write) are
+/// encoded, the error is returned by the next call instead, & the encoded
+/// bytes that were not written yet are written by that call.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::codec::{Base64Alphabet, Base64Writer};
+/// use portable_io::Write;
+///
+/// let mut storage = [0; 8];
+/// let mut writer = Base64Writer::new(Base64Alphabet::Standard, &mut storage[..]);
+/// writer.write_all(b"foob").unwrap();
+/// writer.finish().unwrap();
+/// assert_eq!(&storage, b"Zm9vYg==");
+/// ```
+#[derive(Debug)]
+pub struct Base64Writer<W> {
+    output: TextOutput<W>,
+    symbols: &'static [u8; 64],
+    padding: bool,
+    // (the bytes of the current group, which are not encoded yet)
+    group: [u8; 3],
+    group_len: usize,
+}
+
+impl<W: Write> Base64Writer<W> {
+    /// Creates a new `Base64Writer<W>`, which pads the last group with `=`.
+    pub fn new(alphabet: Base64Alphabet, inner: W) -> Base64Writer<W> {
+        Base64Writer::with_padding(alphabet, true, inner)
+    }
+
+    /// Creates a new `Base64Writer<W>`, which does not pad the last group.
+    pub fn new_unpadded(alphabet: Base64Alphabet, inner: W) -> Base64Writer<W> {
+        Base64Writer::with_padding(alphabet, false, inner)
+    }
+
+    fn with_padding(alphabet: Base64Alphabet, padding: bool, inner: W) -> Base64Writer<W> {
+        Base64Writer {
+            output: TextOutput::new(inner),
+            symbols: alphabet.symbols(),
+            padding,
+            group: [0; 3],
+            group_len: 0,
+        }
+    }
+
+    /// Encodes the last bytes of the input, & writes out & flushes all of the
+    /// encoded bytes.
+    ///
+    /// The bytes written after this are encoded as the start of a new input.
+    /// If this returns an error, calling it again resumes writing the
+    /// encoded bytes.
+    pub fn finish(&mut self) -> Result<()> {
+        self.output.write_out()?;
+        if self.group_len > 0 {
+            self.encode_group();
+        }
+        self.output.flush()
+    }
+
+    fn encode_group(&mut self) {
+        let [a, b, c] = self.group;
+        let bits = u32::from(a) << 16 | u32::from(b) << 8 | u32::from(c);
+        let mut chars = [PAD; 4];
+        for (i, char) in chars.iter_mut().take(self.group_len + 1).enumerate() {
+            *char = self.symbols[(bits >> (18 - 6 * i) & 0x3f) as usize];
+        }
+        let len = if self.padding { 4 } else { self.group_len + 1 };
+        self.output.push(&chars[..len]);
+        self.group = [0; 3];
+        self.group_len = 0;
+    }
+}
+
+impl<W> Base64Writer<W> {
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.output.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.output.inner
+    }
+
+    /// Unwraps this `Base64Writer<W>`, returning the underlying writer.
+    ///
+    /// Note that the bytes that are not encoded or not written yet are lost.
+    pub fn into_inner(self) -> W {
+        self.output.inner
+    }
+}
+
+impl<W: Write> Write for Base64Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.output.write_out()?;
+        let mut n = 0;
+        while n < buf.len() && self.output.room() >= 4 {
+            self.group[self.group_len] = buf[n];
+            self.group_len += 1;
+            n += 1;
+            if self.group_len == 3 {
+                self.encode_group();
+            }
+        }
+        // (the bytes are taken, so an error is left for the next call to return)
+        let _ = self.output.write_out();
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Decodes base64 read from a reader.
+///
+/// The padding of the last group is optional, but must be complete if present.
+/// Whitespace is skipped if
+/// [`set_ignore_whitespace`](Base64Reader::set_ignore_whitespace) is used.
+///
+/// # Errors
+///
+/// An invalid character results in an error of the kind
+/// [`ErrorKind::InvalidData`], with the offset of the character in the input
+/// (see [`InvalidCharacter`](super::InvalidCharacter)). This includes a
+/// character after the padding, & the last character of a group with bits
+/// that are not part of the decoded bytes set. If the underlying reader reaches
+/// EOF in the middle of a group that cannot be decoded, an error of the kind
+/// [`ErrorKind::UnexpectedEof`] is returned.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::codec::{Base64Alphabet, Base64Reader};
+/// use portable_io::Read;
+///
+/// let mut reader = Base64Reader::new(Base64Alphabet::Standard, &b"Zm9v\nYg=="[..]);
+/// reader.set_ignore_whitespace(true);
+///
+/// let mut buf = [0; 8];
+/// assert_eq!(reader.read(&mut buf).unwrap(), 4);
+/// assert_eq!(&buf[..4], b"foob");
+/// ```
+#[derive(Debug)]
+pub struct Base64Reader<R> {
+    input: TextInput<R>,
+    alphabet: Base64Alphabet,
+    // (the bits of the characters of the current group)
+    bits: u32,
+    count: usize,
+    // (the number of `=` after the current group, which are part of it)
+    padding: usize,
+    // (the last character of the current group & its offset)
+    last: (u8, u64),
+    // (the decoded bytes of the last group, which are not returned yet)
+    out: [u8; 3],
+    out_pos: usize,
+    out_len: usize,
+}
+
+impl<R: Read> Base64Reader<R> {
+    /// Creates a new `Base64Reader<R>`, which does not skip whitespace.
+    pub fn new(alphabet: Base64Alphabet, inner: R) -> Base64Reader<R> {
+        Base64Reader {
+            input: TextInput::new(inner),
+            alphabet,
+            bits: 0,
+            count: 0,
+            padding: 0,
+            last: (0, 0),
+            out: [0; 3],
+            out_pos: 0,
+            out_len: 0,
+        }
+    }
+
+    // Decodes the last group, which has fewer than four characters
+    fn end_group(&mut self) -> Result<()> {
+        let len = self.count - 1;
+        let extra = self.count * 6 - len * 8;
+        if self.bits & ((1 << extra) - 1) != 0 {
+            let (byte, offset) = self.last;
+            return Err(invalid_character(byte, offset));
+        }
+        let bits = self.bits >> extra;
+        for i in 0..len {
+            self.out[i] = (bits >> (8 * (len - 1 - i))) as u8;
+        }
+        self.out_pos = 0;
+        self.out_len = len;
+        self.bits = 0;
+        Ok(())
+    }
+
+    fn decode_char(&mut self, byte: u8, offset: u64) -> Result<()> {
+        if self.padding > 0 {
+            // (nothing but the rest of the padding is allowed after padding)
+            if byte != PAD || self.count + self.padding == 4 {
+                return Err(invalid_character(byte, offset));
+            }
+            self.padding += 1;
+            return Ok(());
+        }
+        if byte == PAD {
+            if self.count < 2 {
+                return Err(invalid_character(byte, offset));
+            }
+            self.end_group()?;
+            self.padding = 1;
+            return Ok(());
+        }
+        let value = self.alphabet.decode(byte).ok_or_else(|| invalid_character(byte, offset))?;
+        self.bits = self.bits << 6 | u32::from(value);
+        self.count += 1;
+        self.last = (byte, offset);
+        if self.count == 4 {
+            self.out = [(self.bits >> 16) as u8, (self.bits >> 8) as u8, self.bits as u8];
+            self.out_pos = 0;
+            self.out_len = 3;
+            self.bits = 0;
+            self.count = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<R> Base64Reader<R> {
+    /// Sets whether whitespace in the input is skipped.
+    pub fn set_ignore_whitespace(&mut self, ignore: bool) {
+        self.input.ignore_whitespace = ignore;
+    }
+
+    /// Returns whether whitespace in the input is skipped.
+    pub fn ignore_whitespace(&self) -> bool {
+        self.input.ignore_whitespace
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.input.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.input.inner
+    }
+
+    /// Unwraps this `Base64Reader<R>`, returning the underlying reader.
+    ///
+    /// Note that the input that was read from the underlying reader but not
+    /// decoded yet is lost.
+    pub fn into_inner(self) -> R {
+        self.input.inner
+    }
+}
+
+impl<R: Read> Read for Base64Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut n = 0;
+        loop {
+            while self.out_pos < self.out_len && n < buf.len() {
+                buf[n] = self.out[self.out_pos];
+                self.out_pos += 1;
+                n += 1;
+            }
+            if n == buf.len() {
+                return Ok(n);
+            }
+            let (byte, offset) = match self.input.next() {
+                Ok(Some(next)) => next,
+                Ok(None) => {
+                    let in_group =
+                        self.count > 0 && (self.padding == 0 || self.count + self.padding < 4);
+                    if !in_group || n > 0 {
+                        return Ok(n);
+                    }
+                    if self.count == 1 || self.padding > 0 {
+                        return Err(Error::new_const(
+                            ErrorKind::UnexpectedEof,
+                            &"stream ended in the middle of a base64 group",
+                        ));
+                    }
+                    // (the last group is not padded)
+                    self.end_group()?;
+                    self.count = 0;
+                    continue;
+                }
+                Err(_) if n > 0 => return Ok(n),
+                Err(e) => return Err(e),
+            };
+            if let Err(e) = self.decode_char(byte, offset) {
+                if n > 0 {
+                    // (the bytes before the invalid character are returned first)
+                    self.input.unget();
+                    return Ok(n);
+                }
+                return Err(e);
+            }
+        }
+    }
+}
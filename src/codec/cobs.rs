@@ -0,0 +1,410 @@
+use core::cmp;
+
+use super::{Decode, FrameProgress, unexpected_eof};
+use crate::{BufRead, Error, ErrorKind, Read, Result, Write};
+
+// The maximum number of data bytes in a block, with a code of 0xFF
+const MAX_BLOCK_LEN: usize = 254;
+
+/// Encodes frames with COBS, or COBS/R, & writes them to a writer.
+///
+/// The bytes written to a `CobsEncoder<W>` are the payload of the current
+/// frame, which is ended by [`end_frame`](CobsEncoder::end_frame). COBS encodes
+/// the payload in blocks, each starting with a code byte that depends on the
+/// rest of the block, so up to 254 bytes of the payload are kept in the
+/// `CobsEncoder<W>` until the end of their block is known. These are not
+/// written by [`flush`](Write::flush).
+///
+/// If the underlying writer returns an error, for example of the kind
+/// [`ErrorKind::WouldBlock`], the bytes that were encoded but not written yet
+/// are written by the next call.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::codec::CobsEncoder;
+/// use portable_io::Write;
+///
+/// let mut storage = [0; 8];
+/// let mut encoder = CobsEncoder::new(&mut storage[..]);
+/// encoder.write_all(&[0x11, 0x22, 0x00, 0x33]).unwrap();
+/// encoder.end_frame().unwrap();
+/// assert_eq!(storage[..6], [0x03, 0x11, 0x22, 0x02, 0x33, 0x00]);
+/// ```
+#[derive(Debug)]
+pub struct CobsEncoder<W> {
+    inner: W,
+    reduced: bool,
+    // (the code byte, the data bytes & the delimiter that may follow a block)
+    block: [u8; MAX_BLOCK_LEN + 2],
+    // (the number of data bytes in the current block)
+    len: usize,
+    // (the part of `block` that is encoded & yet to be written)
+    out_pos: usize,
+    out_len: usize,
+    // (whether the part yet to be written ends the frame)
+    ending: bool,
+}
+
+impl<W: Write> CobsEncoder<W> {
+    /// Creates a new `CobsEncoder<W>`, for COBS.
+    pub fn new(inner: W) -> CobsEncoder<W> {
+        CobsEncoder::with_reduced(false, inner)
+    }
+
+    /// Creates a new `CobsEncoder<W>`, for COBS/R.
+    ///
+    /// COBS/R is the same as COBS, except that if the last byte of the payload
+    /// is greater than the code byte of the last block, it replaces the code
+    /// byte, which often saves a byte for small frames. A COBS/R frame is
+    /// decoded by [`CobsDecoder::new_reduced`].
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::codec::CobsEncoder;
+    /// use portable_io::Write;
+    ///
+    /// let mut storage = [0; 8];
+    /// let mut encoder = CobsEncoder::new_reduced(&mut storage[..]);
+    /// encoder.write_all(&[0x11, 0x22, 0x33]).unwrap();
+    /// encoder.end_frame().unwrap();
+    /// assert_eq!(storage[..4], [0x33, 0x11, 0x22, 0x00]);
+    /// ```
+    pub fn new_reduced(inner: W) -> CobsEncoder<W> {
+        CobsEncoder::with_reduced(true, inner)
+    }
+
+    fn with_reduced(reduced: bool, inner: W) -> CobsEncoder<W> {
+        CobsEncoder {
+            inner,
+            reduced,
+            block: [0; MAX_BLOCK_LEN + 2],
+            len: 0,
+            out_pos: 0,
+            out_len: 0,
+            ending: false,
+        }
+    }
+
+    /// Ends the current frame, writing the rest of it & the delimiter.
+    ///
+    /// If this returns an error, for example of the kind
+    /// [`ErrorKind::WouldBlock`], calling it again writes the rest of the frame.
+    pub fn end_frame(&mut self) -> Result<()> {
+        if !self.ending {
+            self.write_out()?;
+            let mut code = self.len as u8 + 1;
+            if self.reduced && self.len > 0 && self.block[self.len] > code {
+                code = self.block[self.len];
+                self.len -= 1;
+            }
+            self.block[self.len + 1] = 0;
+            self.end_block(code, 1);
+            self.ending = true;
+        }
+        self.write_out()
+    }
+
+    // Ends the current block, to be written out before the next one starts
+    fn end_block(&mut self, code: u8, delimiter_len: usize) {
+        self.block[0] = code;
+        self.out_pos = 0;
+        self.out_len = 1 + self.len + delimiter_len;
+        self.len = 0;
+    }
+
+    fn write_out(&mut self) -> Result<()> {
+        while self.out_pos < self.out_len {
+            match self.inner.write(&self.block[self.out_pos..self.out_len]) {
+                Ok(0) => {
+                    return Err(Error::new_const(
+                        ErrorKind::WriteZero,
+                        &"failed to write whole buffer",
+                    ));
+                }
+                Ok(n) => self.out_pos += n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.ending = false;
+        Ok(())
+    }
+}
+
+impl<W> CobsEncoder<W> {
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `CobsEncoder<W>`, returning the underlying writer.
+    ///
+    /// Note that the part of the current frame that is not written yet is lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for CobsEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.write_out()?;
+        if self.len == MAX_BLOCK_LEN {
+            // (a full block is only ended once more of the payload follows, so
+            // that a frame that ends with a full block takes no extra byte)
+            self.end_block(0xff, 0);
+            self.write_out()?;
+        }
+        let run = &buf[..cmp::min(buf.len(), MAX_BLOCK_LEN - self.len)];
+        let len = run.iter().position(|&b| b == 0).unwrap_or(run.len());
+        self.block[1 + self.len..][..len].copy_from_slice(&run[..len]);
+        self.len += len;
+        if len < run.len() {
+            // (the zero ends the block)
+            self.end_block(self.len as u8 + 1, 0);
+            return Ok(len + 1);
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.write_out()?;
+        self.inner.flush()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    // (before the first code byte of a frame)
+    Start,
+    // (in a block, with the given code byte & number of data bytes left)
+    Block { code: u8, remaining: u8 },
+    // (after the delimiter of a COBS/R frame, the last byte of which is the
+    // code byte of the last block)
+    Tail(u8),
+    // (after the delimiter of a frame, the end of which is yet to be returned)
+    End,
+    // (after malformed input, before the next delimiter)
+    Resync,
+}
+
+/// Decodes frames encoded with COBS, or COBS/R, from a reader.
+///
+/// The bytes read from a `CobsDecoder<R>` are the payload of the current
+/// frame, & `Ok(0)` is returned at the end of each frame, as well as at EOF.
+/// [`read_frame`](CobsDecoder::read_frame) reads a whole frame at once.
+///
+/// Malformed input results in an error of the kind [`ErrorKind::InvalidData`],
+/// after which the rest of the frame, up to the next delimiter, is skipped. If
+/// the underlying reader reaches EOF in the middle of a frame, an error of the
+/// kind [`ErrorKind::UnexpectedEof`] is returned.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::codec::CobsDecoder;
+/// use portable_io::Read;
+///
+/// let data: &[u8] = &[0x03, 0x11, 0x22, 0x02, 0x33, 0x00, 0x01, 0x00];
+/// let mut decoder = CobsDecoder::new(data);
+///
+/// let mut buf = [0; 8];
+/// assert_eq!(decoder.read(&mut buf).unwrap(), 4);
+/// assert_eq!(buf[..4], [0x11, 0x22, 0x00, 0x33]);
+/// assert_eq!(decoder.read(&mut buf).unwrap(), 0);
+///
+/// // (an empty frame)
+/// assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(0));
+/// assert_eq!(decoder.read_frame(&mut buf).unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct CobsDecoder<R> {
+    inner: R,
+    reduced: bool,
+    state: State,
+    progress: FrameProgress,
+}
+
+impl<R: BufRead> CobsDecoder<R> {
+    /// Creates a new `CobsDecoder<R>`, for COBS.
+    pub fn new(inner: R) -> CobsDecoder<R> {
+        CobsDecoder::with_reduced(false, inner)
+    }
+
+    /// Creates a new `CobsDecoder<R>`, for COBS/R, as written by
+    /// [`CobsEncoder::new_reduced`].
+    ///
+    /// This also decodes frames encoded with COBS, the same way as
+    /// [`CobsDecoder::new`], but accepts some frames that are malformed for COBS.
+    pub fn new_reduced(inner: R) -> CobsDecoder<R> {
+        CobsDecoder::with_reduced(true, inner)
+    }
+
+    fn with_reduced(reduced: bool, inner: R) -> CobsDecoder<R> {
+        CobsDecoder { inner, reduced, state: State::Start, progress: FrameProgress::default() }
+    }
+
+    /// Reads the next frame into `buf`, returning the length of its payload,
+    /// or `None` at EOF.
+    ///
+    /// If this returns an error of the kind [`ErrorKind::WouldBlock`], calling
+    /// it again with the same buffer resumes the same frame.
+    ///
+    /// # Errors
+    ///
+    /// If the frame is longer than `buf`, an error of the kind
+    /// [`ErrorKind::InvalidInput`] is returned, & the rest of the frame is
+    /// skipped.
+    pub fn read_frame(&mut self, buf: &mut [u8]) -> Result<Option<usize>> {
+        super::read_frame(self, buf)
+    }
+
+    fn invalid(&mut self, n: usize) -> Result<usize> {
+        // (the bytes before the malformed input are returned first)
+        if n > 0 {
+            return Ok(n);
+        }
+        self.state = State::Resync;
+        Err(Error::new_const(ErrorKind::InvalidData, &"invalid COBS frame"))
+    }
+}
+
+impl<R> CobsDecoder<R> {
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `CobsDecoder<R>`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Decode for CobsDecoder<R> {
+    fn decode(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut n = 0;
+        loop {
+            match self.state {
+                State::End => {
+                    if n == 0 {
+                        self.state = State::Start;
+                    }
+                    return Ok(n);
+                }
+                State::Tail(_) | State::Block { .. } if n == buf.len() => return Ok(n),
+                State::Tail(byte) => {
+                    buf[n] = byte;
+                    n += 1;
+                    self.state = State::End;
+                    continue;
+                }
+                _ => {}
+            }
+            let available = match self.inner.fill_buf() {
+                Ok(available) => available,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) if n > 0 => return Ok(n),
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                if n > 0 {
+                    return Ok(n);
+                }
+                let in_frame = !matches!(self.state, State::Start | State::Resync);
+                self.state = State::Start;
+                return if in_frame { Err(unexpected_eof()) } else { Ok(0) };
+            }
+            match self.state {
+                State::Start => match available.iter().position(|&b| b != 0) {
+                    Some(i) => {
+                        let code = available[i];
+                        self.inner.consume(i + 1);
+                        self.state = State::Block { code, remaining: code - 1 };
+                    }
+                    None => {
+                        let len = available.len();
+                        self.inner.consume(len);
+                    }
+                },
+                State::Resync => match available.iter().position(|&b| b == 0) {
+                    Some(i) => {
+                        self.inner.consume(i + 1);
+                        self.state = State::Start;
+                    }
+                    None => {
+                        let len = available.len();
+                        self.inner.consume(len);
+                    }
+                },
+                State::Block { code, remaining: 0 } => {
+                    let next = available[0];
+                    self.inner.consume(1);
+                    if next == 0 {
+                        self.state = State::End;
+                        continue;
+                    }
+                    // (each block is followed by a zero, except for full blocks
+                    // & the last block)
+                    if code != 0xff {
+                        buf[n] = 0;
+                        n += 1;
+                    }
+                    self.state = State::Block { code: next, remaining: next - 1 };
+                }
+                State::Block { code, remaining } => {
+                    let max =
+                        cmp::min(usize::from(remaining), cmp::min(buf.len() - n, available.len()));
+                    let len = available[..max].iter().position(|&b| b == 0).unwrap_or(max);
+                    buf[n..n + len].copy_from_slice(&available[..len]);
+                    self.inner.consume(len);
+                    n += len;
+                    self.state = State::Block { code, remaining: remaining - len as u8 };
+                    if len < max {
+                        // (a delimiter before the end of the block, which for COBS/R
+                        // means that the code byte is the last byte of the frame)
+                        if !self.reduced {
+                            return self.invalid(n);
+                        }
+                        self.inner.consume(1);
+                        self.state = State::Tail(code);
+                    }
+                }
+                State::Tail(_) | State::End => unreachable!(),
+            }
+        }
+    }
+
+    fn start_frame(&mut self) -> Result<bool> {
+        self.decode(&mut [])?;
+        Ok(self.state != State::Start)
+    }
+
+    fn progress(&mut self) -> &mut FrameProgress {
+        &mut self.progress
+    }
+}
+
+impl<R: BufRead> Read for CobsDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        super::read(self, buf)
+    }
+}
@@ -0,0 +1,198 @@
+use core::cmp;
+
+use super::{TextInput, TextOutput, invalid_character};
+use crate::{Error, ErrorKind, Read, Result, Write};
+
+const LOWER_DIGITS: &[u8; 16] = b"0123456789abcdef";
+const UPPER_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Encodes the bytes written to it as hexadecimal, & writes that to a writer.
+///
+/// The encoded bytes are written to the underlying writer in chunks; if that
+/// returns an error after the bytes given to [`write`](Write::write) are
+/// encoded, the error is returned by the next call instead, & the encoded
+/// bytes that were not written yet are written by that call.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::codec::HexWriter;
+/// use portable_io::Write;
+///
+/// let mut storage = [0; 8];
+/// let mut writer = HexWriter::new(&mut storage[..]);
+/// writer.write_all(&[0x01, 0xab, 0xff]).unwrap();
+/// writer.flush().unwrap();
+/// assert_eq!(&storage[..6], b"01abff");
+/// ```
+#[derive(Debug)]
+pub struct HexWriter<W> {
+    output: TextOutput<W>,
+    digits: &'static [u8; 16],
+}
+
+impl<W: Write> HexWriter<W> {
+    /// Creates a new `HexWriter<W>`, with lowercase digits.
+    pub fn new(inner: W) -> HexWriter<W> {
+        HexWriter { output: TextOutput::new(inner), digits: LOWER_DIGITS }
+    }
+
+    /// Creates a new `HexWriter<W>`, with uppercase digits.
+    pub fn new_uppercase(inner: W) -> HexWriter<W> {
+        HexWriter { output: TextOutput::new(inner), digits: UPPER_DIGITS }
+    }
+}
+
+impl<W> HexWriter<W> {
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.output.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.output.inner
+    }
+
+    /// Unwraps this `HexWriter<W>`, returning the underlying writer.
+    ///
+    /// Note that the encoded bytes that are not written yet are lost.
+    pub fn into_inner(self) -> W {
+        self.output.inner
+    }
+}
+
+impl<W: Write> Write for HexWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.output.write_out()?;
+        let n = cmp::min(buf.len(), self.output.room() / 2);
+        for &byte in &buf[..n] {
+            let digits =
+                [self.digits[usize::from(byte >> 4)], self.digits[usize::from(byte & 0xf)]];
+            self.output.push(&digits);
+        }
+        // (the bytes are taken, so an error is left for the next call to return)
+        let _ = self.output.write_out();
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Decodes hexadecimal read from a reader.
+///
+/// Both lowercase & uppercase digits are accepted. Whitespace is skipped if
+/// [`set_ignore_whitespace`](HexReader::set_ignore_whitespace) is used.
+///
+/// # Errors
+///
+/// An invalid character results in an error of the kind
+/// [`ErrorKind::InvalidData`], with the offset of the character in the input
+/// (see [`InvalidCharacter`](super::InvalidCharacter)). If the underlying
+/// reader reaches EOF after an odd number of digits, an error of the kind
+/// [`ErrorKind::UnexpectedEof`] is returned.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::codec::HexReader;
+/// use portable_io::Read;
+///
+/// let mut reader = HexReader::new(&b"01 AB\nff"[..]);
+/// reader.set_ignore_whitespace(true);
+///
+/// let mut buf = [0; 4];
+/// assert_eq!(reader.read(&mut buf).unwrap(), 3);
+/// assert_eq!(buf[..3], [0x01, 0xab, 0xff]);
+/// ```
+#[derive(Debug)]
+pub struct HexReader<R> {
+    input: TextInput<R>,
+    // (the value of the first digit of a byte, once read)
+    high: Option<u8>,
+}
+
+impl<R: Read> HexReader<R> {
+    /// Creates a new `HexReader<R>`, which does not skip whitespace.
+    pub fn new(inner: R) -> HexReader<R> {
+        HexReader { input: TextInput::new(inner), high: None }
+    }
+}
+
+impl<R> HexReader<R> {
+    /// Sets whether whitespace in the input is skipped.
+    pub fn set_ignore_whitespace(&mut self, ignore: bool) {
+        self.input.ignore_whitespace = ignore;
+    }
+
+    /// Returns whether whitespace in the input is skipped.
+    pub fn ignore_whitespace(&self) -> bool {
+        self.input.ignore_whitespace
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.input.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.input.inner
+    }
+
+    /// Unwraps this `HexReader<R>`, returning the underlying reader.
+    ///
+    /// Note that the input that was read from the underlying reader but not
+    /// decoded yet is lost.
+    pub fn into_inner(self) -> R {
+        self.input.inner
+    }
+}
+
+impl<R: Read> Read for HexReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut n = 0;
+        while n < buf.len() {
+            let (byte, offset) = match self.input.next() {
+                Ok(Some(next)) => next,
+                Ok(None) if self.high.is_some() && n == 0 => {
+                    return Err(Error::new_const(
+                        ErrorKind::UnexpectedEof,
+                        &"stream ended in the middle of a hex byte",
+                    ));
+                }
+                Ok(None) => break,
+                Err(_) if n > 0 => break,
+                Err(e) => return Err(e),
+            };
+            let value = match byte {
+                b'0'..=b'9' => byte - b'0',
+                b'a'..=b'f' => byte - b'a' + 10,
+                b'A'..=b'F' => byte - b'A' + 10,
+                _ if n > 0 => {
+                    // (the bytes before the invalid character are returned first)
+                    self.input.unget();
+                    break;
+                }
+                _ => return Err(invalid_character(byte, offset)),
+            };
+            match self.high.take() {
+                Some(high) => {
+                    buf[n] = high << 4 | value;
+                    n += 1;
+                }
+                None => self.high = Some(value),
+            }
+        }
+        Ok(n)
+    }
+}
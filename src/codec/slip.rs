@@ -0,0 +1,292 @@
+use core::cmp;
+
+use super::{Decode, FrameProgress, unexpected_eof};
+use crate::{BufRead, Error, ErrorKind, Read, Result, Write};
+
+const END: u8 = 0xc0;
+const ESC: u8 = 0xdb;
+const ESC_END: u8 = 0xdc;
+const ESC_ESC: u8 = 0xdd;
+
+/// Encodes frames with SLIP & writes them to a writer.
+///
+/// The bytes written to a `SlipEncoder<W>` are the payload of the current
+/// frame, which is ended by [`end_frame`](SlipEncoder::end_frame).
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::codec::SlipEncoder;
+/// use portable_io::Write;
+///
+/// let mut storage = [0; 8];
+/// let mut encoder = SlipEncoder::new(&mut storage[..]);
+/// encoder.write_all(&[0x01, 0xc0, 0xdb]).unwrap();
+/// encoder.end_frame().unwrap();
+/// assert_eq!(storage[..6], [0x01, 0xdb, 0xdc, 0xdb, 0xdd, 0xc0]);
+/// ```
+#[derive(Debug)]
+pub struct SlipEncoder<W> {
+    inner: W,
+    // (the second byte of an escape sequence, the first byte of which is written)
+    pending: Option<u8>,
+}
+
+impl<W: Write> SlipEncoder<W> {
+    /// Creates a new `SlipEncoder<W>`.
+    pub fn new(inner: W) -> SlipEncoder<W> {
+        SlipEncoder { inner, pending: None }
+    }
+
+    /// Ends the current frame, writing the delimiter.
+    pub fn end_frame(&mut self) -> Result<()> {
+        self.write_pending()?;
+        self.inner.write_all(&[END])
+    }
+
+    fn write_pending(&mut self) -> Result<()> {
+        if let Some(byte) = self.pending {
+            self.inner.write_all(&[byte])?;
+            self.pending = None;
+        }
+        Ok(())
+    }
+}
+
+impl<W> SlipEncoder<W> {
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `SlipEncoder<W>`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for SlipEncoder<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.write_pending()?;
+        let escaped = match buf[0] {
+            END => ESC_END,
+            ESC => ESC_ESC,
+            _ => {
+                let len = buf.iter().position(|&b| b == END || b == ESC).unwrap_or(buf.len());
+                return self.inner.write(&buf[..len]);
+            }
+        };
+        match self.inner.write(&[ESC, escaped])? {
+            0 => Ok(0),
+            1 => {
+                self.pending = Some(escaped);
+                Ok(1)
+            }
+            _ => Ok(1),
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.write_pending()?;
+        self.inner.flush()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    // (before the first byte of a frame)
+    Start,
+    // (in a frame)
+    Data,
+    // (in a frame, after an escape byte)
+    Escape,
+    // (after the delimiter of a frame, the end of which is yet to be returned)
+    End,
+    // (after malformed input, before the next delimiter)
+    Resync,
+}
+
+/// Decodes frames encoded with SLIP from a reader.
+///
+/// The bytes read from a `SlipDecoder<R>` are the payload of the current
+/// frame, & `Ok(0)` is returned at the end of each frame, as well as at EOF.
+/// [`read_frame`](SlipDecoder::read_frame) reads a whole frame at once.
+///
+/// An escape byte that is not followed by one of the two escaped values results
+/// in an error of the kind [`ErrorKind::InvalidData`], after which the rest of
+/// the frame, up to the next delimiter, is skipped. If the underlying reader
+/// reaches EOF in the middle of a frame, an error of the kind
+/// [`ErrorKind::UnexpectedEof`] is returned.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::codec::SlipDecoder;
+///
+/// let data: &[u8] = &[0xc0, 0x01, 0xdb, 0xdc, 0xdb, 0xdd, 0xc0];
+/// let mut decoder = SlipDecoder::new(data);
+///
+/// let mut buf = [0; 8];
+/// assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(3));
+/// assert_eq!(buf[..3], [0x01, 0xc0, 0xdb]);
+/// assert_eq!(decoder.read_frame(&mut buf).unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct SlipDecoder<R> {
+    inner: R,
+    state: State,
+    progress: FrameProgress,
+}
+
+impl<R: BufRead> SlipDecoder<R> {
+    /// Creates a new `SlipDecoder<R>`.
+    pub fn new(inner: R) -> SlipDecoder<R> {
+        SlipDecoder { inner, state: State::Start, progress: FrameProgress::default() }
+    }
+
+    /// Reads the next frame into `buf`, returning the length of its payload,
+    /// or `None` at EOF.
+    ///
+    /// If this returns an error of the kind [`ErrorKind::WouldBlock`], calling
+    /// it again with the same buffer resumes the same frame.
+    ///
+    /// # Errors
+    ///
+    /// If the frame is longer than `buf`, an error of the kind
+    /// [`ErrorKind::InvalidInput`] is returned, & the rest of the frame is
+    /// skipped.
+    pub fn read_frame(&mut self, buf: &mut [u8]) -> Result<Option<usize>> {
+        super::read_frame(self, buf)
+    }
+
+    fn invalid(&mut self, n: usize) -> Result<usize> {
+        // (the bytes before the malformed input are returned first)
+        if n > 0 {
+            return Ok(n);
+        }
+        self.state = State::Resync;
+        Err(Error::new_const(ErrorKind::InvalidData, &"invalid SLIP escape sequence"))
+    }
+}
+
+impl<R> SlipDecoder<R> {
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `SlipDecoder<R>`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Decode for SlipDecoder<R> {
+    fn decode(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut n = 0;
+        loop {
+            match self.state {
+                State::End => {
+                    if n == 0 {
+                        self.state = State::Start;
+                    }
+                    return Ok(n);
+                }
+                State::Data | State::Escape if n == buf.len() => return Ok(n),
+                _ => {}
+            }
+            let available = match self.inner.fill_buf() {
+                Ok(available) => available,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) if n > 0 => return Ok(n),
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                if n > 0 {
+                    return Ok(n);
+                }
+                let in_frame = !matches!(self.state, State::Start | State::Resync);
+                self.state = State::Start;
+                return if in_frame { Err(unexpected_eof()) } else { Ok(0) };
+            }
+            match self.state {
+                State::Start => match available.iter().position(|&b| b != END) {
+                    Some(i) => {
+                        self.inner.consume(i);
+                        self.state = State::Data;
+                    }
+                    None => {
+                        let len = available.len();
+                        self.inner.consume(len);
+                    }
+                },
+                State::Resync => match available.iter().position(|&b| b == END) {
+                    Some(i) => {
+                        self.inner.consume(i + 1);
+                        self.state = State::Start;
+                    }
+                    None => {
+                        let len = available.len();
+                        self.inner.consume(len);
+                    }
+                },
+                State::Data => {
+                    let max = cmp::min(buf.len() - n, available.len());
+                    let len =
+                        available[..max].iter().position(|&b| b == END || b == ESC).unwrap_or(max);
+                    buf[n..n + len].copy_from_slice(&available[..len]);
+                    n += len;
+                    if len < max {
+                        self.state = if available[len] == END { State::End } else { State::Escape };
+                        self.inner.consume(len + 1);
+                    } else {
+                        self.inner.consume(len);
+                    }
+                }
+                State::Escape => {
+                    buf[n] = match available[0] {
+                        ESC_END => END,
+                        ESC_ESC => ESC,
+                        _ => return self.invalid(n),
+                    };
+                    n += 1;
+                    self.inner.consume(1);
+                    self.state = State::Data;
+                }
+                State::End => unreachable!(),
+            }
+        }
+    }
+
+    fn start_frame(&mut self) -> Result<bool> {
+        self.decode(&mut [])?;
+        Ok(self.state != State::Start)
+    }
+
+    fn progress(&mut self) -> &mut FrameProgress {
+        &mut self.progress
+    }
+}
+
+impl<R: BufRead> Read for SlipDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        super::read(self, buf)
+    }
+}
@@ -0,0 +1,559 @@
+use super::{
+    Base64Alphabet, Base64Reader, Base64Writer, CobsDecoder, CobsEncoder, HexReader, HexWriter,
+    SlipDecoder, SlipEncoder,
+};
+use crate::test_util::{Flaky, retry};
+use crate::{Cursor, Error, ErrorKind, Read, Write};
+
+// Writes a frame with `write` & `end_frame`, retrying after `WouldBlock` & `Interrupted`
+fn write_frame<W: Write>(
+    writer: &mut W,
+    mut payload: &[u8],
+    mut end_frame: impl FnMut(&mut W) -> crate::Result<()>,
+) {
+    while !payload.is_empty() {
+        match writer.write(payload) {
+            Ok(n) => payload = &payload[n..],
+            Err(ref e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::Interrupted) => {}
+            Err(e) => panic!("{}", e),
+        }
+    }
+    retry(|| end_frame(writer)).unwrap();
+}
+
+// Reads until EOF into `buf`, retrying after `WouldBlock`
+fn read_all<R: Read>(reader: &mut R, buf: &mut [u8]) -> crate::Result<usize> {
+    let mut n = 0;
+    loop {
+        match retry(|| reader.read(&mut buf[n..]))? {
+            0 => return Ok(n),
+            len => n += len,
+        }
+    }
+}
+
+// Payloads of lengths around the block boundaries of COBS, without zeros,
+// with some zeros, with only zeros & with all the special bytes of SLIP
+fn payloads() -> impl Iterator<Item = ([u8; 600], usize)> {
+    let patterns: [fn(usize) -> u8; 4] =
+        [|i| (i % 255) as u8 + 1, |i| i as u8, |_| 0, |i| [0xc0, 0xdb, 0xdc, 0xdd][i % 4]];
+    let lens = [0, 1, 2, 253, 254, 255, 256, 507, 508, 509, 600];
+    patterns.into_iter().flat_map(move |pattern| {
+        lens.into_iter().map(move |len| (core::array::from_fn(pattern), len))
+    })
+}
+
+#[test]
+fn cobs_known_encodings() {
+    let cases: [(&[u8], &[u8]); 7] = [
+        (&[], &[0x01, 0x00]),
+        (&[0x00], &[0x01, 0x01, 0x00]),
+        (&[0x00, 0x00], &[0x01, 0x01, 0x01, 0x00]),
+        (&[0x00, 0x11, 0x00], &[0x01, 0x02, 0x11, 0x01, 0x00]),
+        (&[0x11, 0x22, 0x00, 0x33], &[0x03, 0x11, 0x22, 0x02, 0x33, 0x00]),
+        (&[0x11, 0x22, 0x33, 0x44], &[0x05, 0x11, 0x22, 0x33, 0x44, 0x00]),
+        (&[0x11, 0x00, 0x00, 0x00], &[0x02, 0x11, 0x01, 0x01, 0x01, 0x00]),
+    ];
+    for (payload, encoded) in cases {
+        let mut storage = [0; 16];
+        let mut encoder = CobsEncoder::new(Cursor::new(&mut storage[..]));
+        encoder.write_all(payload).unwrap();
+        encoder.end_frame().unwrap();
+        let len = encoder.get_ref().position() as usize;
+        assert_eq!(&storage[..len], encoded, "for {:x?}", payload);
+
+        let mut buf = [0; 16];
+        let mut decoder = CobsDecoder::new(encoded);
+        assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(payload.len()));
+        assert_eq!(&buf[..payload.len()], payload);
+    }
+
+    // (a frame that ends with a full block takes no extra byte)
+    let payload: [u8; 254] = core::array::from_fn(|i| i as u8 + 1);
+    let mut storage = [0; 300];
+    let mut encoder = CobsEncoder::new(Cursor::new(&mut storage[..]));
+    encoder.write_all(&payload).unwrap();
+    encoder.end_frame().unwrap();
+    assert_eq!(encoder.get_ref().position(), 256);
+    assert_eq!(storage[0], 0xff);
+    assert_eq!(storage[1..255], payload);
+    assert_eq!(storage[255], 0x00);
+
+    // (the extra byte that other encoders may add is accepted)
+    storage[255] = 0x01;
+    let mut buf = [0; 300];
+    let mut decoder = CobsDecoder::new(&storage[..257]);
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(254));
+    assert_eq!(buf[..254], payload);
+}
+
+#[test]
+fn cobsr_known_encodings() {
+    let cases: [(&[u8], &[u8]); 6] = [
+        (&[], &[0x01, 0x00]),
+        (&[0x00], &[0x01, 0x01, 0x00]),
+        (&[0x02], &[0x02, 0x02, 0x00]),
+        (&[0x03], &[0x03, 0x00]),
+        (&[0x11, 0x22, 0x33, 0x44], &[0x44, 0x11, 0x22, 0x33, 0x00]),
+        (&[0x11, 0x00, 0x33, 0x44], &[0x02, 0x11, 0x44, 0x33, 0x00]),
+    ];
+    for (payload, encoded) in cases {
+        let mut storage = [0; 16];
+        let mut encoder = CobsEncoder::new_reduced(Cursor::new(&mut storage[..]));
+        encoder.write_all(payload).unwrap();
+        encoder.end_frame().unwrap();
+        let len = encoder.get_ref().position() as usize;
+        assert_eq!(&storage[..len], encoded, "for {:x?}", payload);
+
+        let mut buf = [0; 16];
+        let mut decoder = CobsDecoder::new_reduced(encoded);
+        assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(payload.len()));
+        assert_eq!(&buf[..payload.len()], payload);
+    }
+
+    // (a COBS/R frame is malformed for COBS)
+    let mut decoder = CobsDecoder::new(&[0x03, 0x00][..]);
+    assert_eq!(decoder.read_frame(&mut [0; 16]).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn slip_known_encodings() {
+    let cases: [(&[u8], &[u8]); 4] = [
+        (&[0x01], &[0x01, 0xc0]),
+        (&[0xc0], &[0xdb, 0xdc, 0xc0]),
+        (&[0xdb], &[0xdb, 0xdd, 0xc0]),
+        (&[0x01, 0xc0, 0x02, 0xdb, 0xdc], &[0x01, 0xdb, 0xdc, 0x02, 0xdb, 0xdd, 0xdc, 0xc0]),
+    ];
+    for (payload, encoded) in cases {
+        let mut storage = [0; 16];
+        let mut encoder = SlipEncoder::new(Cursor::new(&mut storage[..]));
+        encoder.write_all(payload).unwrap();
+        encoder.end_frame().unwrap();
+        let len = encoder.get_ref().position() as usize;
+        assert_eq!(&storage[..len], encoded, "for {:x?}", payload);
+
+        let mut buf = [0; 16];
+        let mut decoder = SlipDecoder::new(encoded);
+        assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(payload.len()));
+        assert_eq!(&buf[..payload.len()], payload);
+    }
+}
+
+#[test]
+fn round_trip() {
+    for (payload, len) in payloads() {
+        let payload = &payload[..len];
+        let mut buf = [0; 600];
+
+        for reduced in [false, true] {
+            let mut storage = [0; 2048];
+            let writer = Cursor::new(&mut storage[..]);
+            let mut encoder =
+                if reduced { CobsEncoder::new_reduced(writer) } else { CobsEncoder::new(writer) };
+            encoder.write_all(payload).unwrap();
+            encoder.end_frame().unwrap();
+            encoder.write_all(payload).unwrap();
+            encoder.end_frame().unwrap();
+            let written = encoder.get_ref().position() as usize;
+            assert!(storage[..written / 2 - 1].iter().all(|&b| b != 0));
+            assert!(written / 2 <= len + len / 254 + 2, "for {} bytes", len);
+
+            let reader = &storage[..written];
+            let mut decoder =
+                if reduced { CobsDecoder::new_reduced(reader) } else { CobsDecoder::new(reader) };
+            for _ in 0..2 {
+                assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(len), "for {} bytes", len);
+                assert_eq!(&buf[..len], payload);
+            }
+            assert_eq!(decoder.read_frame(&mut buf).unwrap(), None);
+        }
+
+        let mut storage = [0; 2048];
+        let mut encoder = SlipEncoder::new(Cursor::new(&mut storage[..]));
+        encoder.write_all(payload).unwrap();
+        encoder.end_frame().unwrap();
+        encoder.write_all(payload).unwrap();
+        encoder.end_frame().unwrap();
+        let written = encoder.get_ref().position() as usize;
+        assert!(storage[..written / 2 - 1].iter().all(|&b| b != 0xc0));
+
+        let mut decoder = SlipDecoder::new(&storage[..written]);
+        for _ in 0..2 {
+            if len == 0 {
+                // (an empty frame is the same as a delimiter on its own, which is skipped)
+                break;
+            }
+            assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(len), "for {} bytes", len);
+            assert_eq!(&buf[..len], payload);
+        }
+        assert_eq!(decoder.read_frame(&mut buf).unwrap(), None);
+    }
+}
+
+#[test]
+fn resume_after_would_block() {
+    for (payload, len) in payloads() {
+        let payload = &payload[..len];
+        let mut buf = [0; 600];
+
+        let mut storage = [0; 2048];
+        let mut encoder = CobsEncoder::new_reduced(Flaky::new(Cursor::new(&mut storage[..])));
+        write_frame(&mut encoder, payload, CobsEncoder::end_frame);
+        let written = encoder.get_ref().inner.position() as usize;
+
+        let mut decoder = CobsDecoder::new_reduced(Flaky::new(&storage[..written]));
+        assert_eq!(retry(|| decoder.read_frame(&mut buf)).unwrap(), Some(len));
+        assert_eq!(&buf[..len], payload);
+        assert_eq!(retry(|| decoder.read_frame(&mut buf)).unwrap(), None);
+
+        let mut storage = [0; 2048];
+        let mut encoder = SlipEncoder::new(Flaky::new(Cursor::new(&mut storage[..])));
+        write_frame(&mut encoder, payload, SlipEncoder::end_frame);
+        write_frame(&mut encoder, b"x", SlipEncoder::end_frame);
+        let written = encoder.get_ref().inner.position() as usize;
+
+        let mut decoder = SlipDecoder::new(Flaky::new(&storage[..written]));
+        if len > 0 {
+            assert_eq!(retry(|| decoder.read_frame(&mut buf)).unwrap(), Some(len));
+            assert_eq!(&buf[..len], payload);
+        }
+        assert_eq!(retry(|| decoder.read_frame(&mut buf)).unwrap(), Some(1));
+        assert_eq!(retry(|| decoder.read_frame(&mut buf)).unwrap(), None);
+    }
+}
+
+#[test]
+fn read_in_pieces() {
+    let data: &[u8] = &[0x03, 0x11, 0x22, 0x02, 0x33, 0x00, 0x02, 0x44, 0x00];
+    let mut decoder = CobsDecoder::new(data);
+    let mut buf = [0; 3];
+    assert_eq!(decoder.read(&mut buf).unwrap(), 3);
+    assert_eq!(buf, [0x11, 0x22, 0x00]);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 1);
+    assert_eq!(buf[0], 0x33);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 0);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 1);
+    assert_eq!(buf[0], 0x44);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 0);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn resynchronise_after_invalid_data() {
+    // (a delimiter before the end of a block)
+    let data: &[u8] = &[0x05, 0x11, 0x00, 0x02, 0x22, 0x00];
+    let mut decoder = CobsDecoder::new(data);
+    let mut buf = [0; 8];
+    assert_eq!(decoder.read_frame(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(1));
+    assert_eq!(buf[0], 0x22);
+
+    // (the bytes before the malformed input are read first)
+    let mut decoder = CobsDecoder::new(data);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 1);
+    assert_eq!(decoder.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 1);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 0);
+
+    // (an escape byte followed by an invalid byte, or by a delimiter)
+    let data: &[u8] = &[0x01, 0xdb, 0x05, 0x02, 0xc0, 0x03, 0xdb, 0xc0, 0x04, 0xc0];
+    let mut decoder = SlipDecoder::new(data);
+    assert_eq!(decoder.read_frame(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decoder.read_frame(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(1));
+    assert_eq!(buf[0], 0x04);
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), None);
+}
+
+#[test]
+fn frame_larger_than_buffer() {
+    let data: &[u8] = &[0x05, 0x11, 0x22, 0x33, 0x44, 0x00, 0x03, 0x55, 0x66, 0x00];
+    let mut decoder = CobsDecoder::new(data);
+    let mut buf = [0; 3];
+    assert_eq!(decoder.read_frame(&mut buf).unwrap_err().kind(), ErrorKind::InvalidInput);
+    // (the rest of the frame is skipped)
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(2));
+    assert_eq!(buf[..2], [0x55, 0x66]);
+
+    // (a frame that fills the buffer exactly fits)
+    let data: &[u8] = &[0x01, 0x02, 0x03, 0xc0, 0x04, 0x05, 0x06, 0x07, 0xc0, 0x08, 0xc0];
+    let mut decoder = SlipDecoder::new(data);
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(3));
+    assert_eq!(decoder.read_frame(&mut buf).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(1));
+    assert_eq!(buf[0], 0x08);
+}
+
+#[test]
+fn skip_empty_frames() {
+    let mut decoder = CobsDecoder::new(&[0x00, 0x00, 0x02, 0x11, 0x00, 0x00][..]);
+    let mut buf = [0; 4];
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(1));
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), None);
+
+    // (a delimiter is often sent before a SLIP frame, as well as after it)
+    let mut decoder = SlipDecoder::new(&[0xc0, 0x11, 0xc0, 0xc0, 0x22, 0xc0][..]);
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(1));
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(1));
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), None);
+}
+
+#[test]
+fn unexpected_eof() {
+    let cases: [&[u8]; 3] = [&[0x03, 0x11], &[0x02, 0x11], &[0x02, 0x11, 0x02]];
+    for data in cases {
+        let mut decoder = CobsDecoder::new(data);
+        let error = decoder.read_frame(&mut [0; 8]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof, "for {:x?}", data);
+    }
+
+    let mut decoder = SlipDecoder::new(&[0x11, 0xdb][..]);
+    assert_eq!(decoder.read_frame(&mut [0; 8]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn compose_with_take_chain_and_cursor() {
+    let mut storage = [0; 64];
+    let mut encoder = SlipEncoder::new(Cursor::new(&mut storage[..]));
+    crate::copy(&mut (&b"hello\xc0world"[..]).take(8), &mut encoder).unwrap();
+    encoder.end_frame().unwrap();
+    let written = encoder.get_ref().position() as usize;
+    assert_eq!(&storage[..written], b"hello\xdb\xdcwo\xc0");
+
+    // (a frame split between the two readers of a chain)
+    let (first, second) = storage[..written].split_at(6);
+    let mut decoder = SlipDecoder::new(first.chain(second));
+    let mut buf = [0; 16];
+    assert_eq!(decoder.read_frame(&mut buf).unwrap(), Some(8));
+    assert_eq!(&buf[..8], b"hello\xc0wo");
+
+    // (a frame cut short by the underlying reader)
+    let mut decoder = SlipDecoder::new(Cursor::new(&storage[..written]).take(4));
+    assert_eq!(decoder.read_frame(&mut buf).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    // (part of a frame, read through `take`)
+    let mut decoder = SlipDecoder::new(Cursor::new(&storage[..written]));
+    let mut part = [0; 16];
+    assert_eq!((&mut decoder).take(3).read(&mut part).unwrap(), 3);
+    assert_eq!(&part[..3], b"hel");
+    let mut rest = [0; 16];
+    let n = decoder.read(&mut rest).unwrap();
+    assert_eq!(&rest[..n], b"lo\xc0wo");
+}
+
+// RFC 4648 test vectors
+const BASE64_CASES: [(&[u8], &[u8]); 7] = [
+    (b"", b""),
+    (b"f", b"Zg=="),
+    (b"fo", b"Zm8="),
+    (b"foo", b"Zm9v"),
+    (b"foob", b"Zm9vYg=="),
+    (b"fooba", b"Zm9vYmE="),
+    (b"foobar", b"Zm9vYmFy"),
+];
+
+#[test]
+fn hex_known_encodings() {
+    let mut storage = [0; 16];
+    let mut writer = HexWriter::new(Cursor::new(&mut storage[..]));
+    writer.write_all(&[0x00, 0x1f, 0xa0, 0xff]).unwrap();
+    writer.flush().unwrap();
+    assert_eq!(&storage[..8], b"001fa0ff");
+
+    let mut storage = [0; 16];
+    let mut writer = HexWriter::new_uppercase(Cursor::new(&mut storage[..]));
+    writer.write_all(&[0x00, 0x1f, 0xa0, 0xff]).unwrap();
+    writer.flush().unwrap();
+    assert_eq!(&storage[..8], b"001FA0FF");
+
+    let mut reader = HexReader::new(&b"001fA0Ff"[..]);
+    let mut buf = [0; 8];
+    assert_eq!(read_all(&mut reader, &mut buf).unwrap(), 4);
+    assert_eq!(buf[..4], [0x00, 0x1f, 0xa0, 0xff]);
+}
+
+#[test]
+fn base64_known_encodings() {
+    for (data, encoded) in BASE64_CASES {
+        let mut storage = [0; 16];
+        let mut writer = Base64Writer::new(Base64Alphabet::Standard, Cursor::new(&mut storage[..]));
+        writer.write_all(data).unwrap();
+        writer.finish().unwrap();
+        let written = writer.get_ref().position() as usize;
+        assert_eq!(&storage[..written], encoded);
+
+        let unpadded = encoded.split(|&b| b == b'=').next().unwrap();
+        let mut storage = [0; 16];
+        let mut writer =
+            Base64Writer::new_unpadded(Base64Alphabet::Standard, Cursor::new(&mut storage[..]));
+        writer.write_all(data).unwrap();
+        writer.finish().unwrap();
+        let written = writer.get_ref().position() as usize;
+        assert_eq!(&storage[..written], unpadded);
+
+        for input in [encoded, unpadded] {
+            let mut reader = Base64Reader::new(Base64Alphabet::Standard, input);
+            let mut buf = [0; 8];
+            assert_eq!(read_all(&mut reader, &mut buf).unwrap(), data.len());
+            assert_eq!(&buf[..data.len()], data);
+        }
+    }
+}
+
+#[test]
+fn base64_url_safe_alphabet() {
+    let data = [0xfb, 0xff, 0xbf];
+    let mut storage = [0; 8];
+    let mut writer = Base64Writer::new(Base64Alphabet::UrlSafe, Cursor::new(&mut storage[..]));
+    writer.write_all(&data).unwrap();
+    writer.finish().unwrap();
+    assert_eq!(&storage[..4], b"-_-_");
+
+    let mut reader = Base64Reader::new(Base64Alphabet::UrlSafe, &b"-_-_"[..]);
+    let mut buf = [0; 4];
+    assert_eq!(read_all(&mut reader, &mut buf).unwrap(), 3);
+    assert_eq!(buf[..3], data);
+
+    let mut reader = Base64Reader::new(Base64Alphabet::Standard, &b"-_-_"[..]);
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    let mut reader = Base64Reader::new(Base64Alphabet::UrlSafe, &b"+/+/"[..]);
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn text_round_trip_after_would_block() {
+    for (payload, len) in payloads() {
+        let payload = &payload[..len];
+        let mut buf = [0; 600];
+
+        let mut storage = [0; 1200];
+        let mut writer = HexWriter::new(Flaky::new(Cursor::new(&mut storage[..])));
+        write_frame(&mut writer, payload, HexWriter::flush);
+        let written = writer.get_ref().inner.position() as usize;
+        assert_eq!(written, len * 2);
+
+        let mut reader = HexReader::new(Flaky::new(&storage[..written]));
+        assert_eq!(read_all(&mut reader, &mut buf).unwrap(), len);
+        assert_eq!(&buf[..len], payload);
+
+        for alphabet in [Base64Alphabet::Standard, Base64Alphabet::UrlSafe] {
+            let mut storage = [0; 800];
+            let mut writer = Base64Writer::new(alphabet, Flaky::new(Cursor::new(&mut storage[..])));
+            write_frame(&mut writer, payload, Base64Writer::finish);
+            let written = writer.get_ref().inner.position() as usize;
+            assert_eq!(written, (len / 3 + usize::from(len % 3 > 0)) * 4);
+
+            let mut reader = Base64Reader::new(alphabet, Flaky::new(&storage[..written]));
+            assert_eq!(read_all(&mut reader, &mut buf).unwrap(), len);
+            assert_eq!(&buf[..len], payload);
+        }
+    }
+}
+
+#[test]
+fn text_read_in_pieces() {
+    let mut reader = Base64Reader::new(Base64Alphabet::Standard, &b"Zm9vYmFy"[..]);
+    let mut buf = [0; 4];
+    for expected in [b"foo", b"bar"] {
+        assert_eq!(reader.read(&mut buf[..2]).unwrap(), 2);
+        assert_eq!(reader.read(&mut buf[2..3]).unwrap(), 1);
+        assert_eq!(&buf[..3], expected);
+    }
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+
+    let mut reader = HexReader::new(&b"a1b2c3"[..]);
+    assert_eq!(reader.read(&mut buf[..1]).unwrap(), 1);
+    assert_eq!(reader.read(&mut buf).unwrap(), 2);
+    assert_eq!(buf[..2], [0xb2, 0xc3]);
+}
+
+#[test]
+fn text_ignore_whitespace() {
+    let mut reader = HexReader::new(&b" 01\tab \r\n"[..]);
+    assert!(!reader.ignore_whitespace());
+    reader.set_ignore_whitespace(true);
+    let mut buf = [0; 8];
+    assert_eq!(read_all(&mut reader, &mut buf).unwrap(), 2);
+    assert_eq!(buf[..2], [0x01, 0xab]);
+
+    let mut reader = Base64Reader::new(Base64Alphabet::Standard, &b"Zm9v\r\nYg\n=\n=\n"[..]);
+    reader.set_ignore_whitespace(true);
+    assert_eq!(read_all(&mut reader, &mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"foob");
+
+    let mut reader = HexReader::new(&b"01 ab"[..]);
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+// Checks that `error` reports an invalid character at `offset`
+fn assert_invalid_character(error: Error, byte: u8, offset: u64) {
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    #[cfg(feature = "alloc")]
+    {
+        let invalid = error.get_ref().unwrap().downcast_ref::<super::InvalidCharacter>().unwrap();
+        assert_eq!((invalid.byte(), invalid.offset()), (byte, offset));
+    }
+    #[cfg(not(feature = "alloc"))]
+    {
+        let _ = byte;
+        assert_eq!(error.code(), Some(offset as i32));
+    }
+}
+
+#[test]
+fn invalid_character_offset() {
+    let mut buf = [0; 8];
+
+    let mut reader = HexReader::new(&b"0g"[..]);
+    assert_invalid_character(reader.read(&mut buf).unwrap_err(), b'g', 1);
+
+    // (the bytes before the invalid character are returned first)
+    let mut reader = Base64Reader::new(Base64Alphabet::Standard, &b"Zm9v\nYg=="[..]);
+    assert_eq!(reader.read(&mut buf).unwrap(), 3);
+    assert_invalid_character(reader.read(&mut buf).unwrap_err(), b'\n', 4);
+
+    let cases: [(&[u8], u8, u64); 5] = [
+        // (bits set after the last decoded byte)
+        (b"Zh==", b'h', 1),
+        (b"Zm9=", b'9', 2),
+        (b"Zh", b'h', 1),
+        // (padding too early or too long, & data after padding)
+        (b"Z===", b'=', 1),
+        (b"Zg===", b'=', 4),
+    ];
+    for (data, byte, offset) in cases {
+        let mut reader = Base64Reader::new(Base64Alphabet::Standard, data);
+        let error = read_all(&mut reader, &mut buf).unwrap_err();
+        assert_invalid_character(error, byte, offset);
+    }
+
+    let mut reader = Base64Reader::new(Base64Alphabet::Standard, &b"Zg==Zg=="[..]);
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    assert_invalid_character(reader.read(&mut buf).unwrap_err(), b'Z', 4);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn invalid_character_message() {
+    extern crate alloc;
+    use alloc::string::ToString;
+
+    let mut reader = HexReader::new(&b"\x00"[..]);
+    let error = reader.read(&mut [0; 4]).unwrap_err();
+    assert_eq!(error.to_string(), "invalid character 0x00 at offset 0");
+}
+
+#[test]
+fn text_unexpected_eof() {
+    let mut reader = HexReader::new(&b"abc"[..]);
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    for data in [&b"Zm9vZ"[..], b"Zg=", b"Zm9vZg="] {
+        let mut reader = Base64Reader::new(Base64Alphabet::Standard, data);
+        let error = read_all(&mut reader, &mut buf).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof, "for {:?}", data);
+    }
+}
@@ -0,0 +1,329 @@
+use core::mem::MaybeUninit;
+
+// for unstable feature: specialized copy
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))]
+use core::cmp;
+
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))] // for unstable feature: specialized copy
+extern crate alloc;
+// for unstable feature: specialized copy
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))]
+use alloc::vec::Vec;
+
+use crate::{BufRead, ErrorKind, Read, ReadBuf, Result, Write};
+#[cfg(portable_io_unstable_all)] // for unstable feature: specialized copy
+use crate::{BufReader, BufferStorage, DEFAULT_BUF_SIZE};
+
+/// Copies the entire contents of a reader into a writer.
+///
+/// This function will continuously read data from `reader` and then
+/// write it into `writer` in a streaming fashion until `reader`
+/// returns EOF.
+///
+/// On success, the total number of bytes that were copied from
+/// `reader` to `writer` is returned.
+///
+/// If you want to copy the contents of one file to another and you’re
+/// working with [`File`]s, see the [`fs::copy`] function.
+///
+/// [`File`]: https://doc.rust-lang.org/std/fs/struct.File.html
+/// [`fs::copy`]: https://doc.rust-lang.org/std/fs/fn.copy.html
+///
+/// # Errors
+///
+/// This function will return an error immediately if any call to [`read`] or
+/// [`write`] returns an error. All instances of [`ErrorKind::Interrupted`] are
+/// handled by this function and the underlying operation is retried.
+///
+/// [`read`]: Read::read
+/// [`write`]: Write::write
+///
+/// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+/// # Example code
+///
+/// ```
+/// use portable_io as io;
+///
+/// fn main() -> io::Result<()> {
+///     let mut reader: &[u8] = b"hello";
+///     let mut writer: Vec<u8> = vec![];
+///
+///     io::copy(&mut reader, &mut writer)?;
+///
+///     assert_eq!(&b"hello"[..], &writer[..]);
+///     Ok(())
+/// }
+/// ```
+///
+/// # Platform-specific behavior
+///
+/// With `--cfg portable_io_unstable_all` (requires Rust nightly), this function
+/// copies straight out of the internal buffer of a [`BufReader`](crate::BufReader)
+/// or out of a byte slice, and reads straight into a `Vec<u8>` writer, with no
+/// intermediate buffer. Otherwise, including always on stable Rust, it copies
+/// by way of a buffer of 1 KiB on the stack. [`copy_buf`] is the fast path on
+/// stable Rust: it copies out of any [`BufRead`] reader, with no intermediate
+/// buffer.
+pub fn copy<R: Read + ?Sized, W: Write + ?Sized>(reader: &mut R, writer: &mut W) -> Result<u64> {
+    generic_copy(reader, writer)
+}
+
+/// Copies the entire contents of a buffered reader into a writer.
+///
+/// This works like [`copy`], but writes the data straight out of the buffer
+/// returned by [`fill_buf`](BufRead::fill_buf), with no intermediate buffer.
+///
+/// On success, the total number of bytes that were copied from
+/// `reader` to `writer` is returned.
+///
+/// # Errors
+///
+/// This function will return an error immediately if any call to
+/// [`fill_buf`](BufRead::fill_buf) or [`write`](Write::write) returns an
+/// error. All instances of [`ErrorKind::Interrupted`] are handled by this
+/// function and the underlying operation is retried.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{self as io, BufReader};
+///
+/// fn main() -> io::Result<()> {
+///     let mut reader = BufReader::new(&b"hello"[..]);
+///     let mut writer: Vec<u8> = vec![];
+///
+///     io::copy_buf(&mut reader, &mut writer)?;
+///
+///     assert_eq!(&b"hello"[..], &writer[..]);
+///     Ok(())
+/// }
+/// ```
+pub fn copy_buf<R: BufRead + ?Sized, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<u64> {
+    let mut len = 0;
+    loop {
+        let buf = match reader.fill_buf() {
+            Ok(buf) => buf,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if buf.is_empty() {
+            return Ok(len);
+        }
+        writer.write_all(buf)?;
+        let n = buf.len();
+        len += n as u64;
+        reader.consume(n);
+    }
+}
+
+#[cfg(not(portable_io_unstable_all))]
+fn generic_copy<R: Read + ?Sized, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<u64> {
+    stack_buffer_copy(reader, writer)
+}
+
+#[cfg(portable_io_unstable_all)] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+fn generic_copy<R: Read + ?Sized, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<u64> {
+    let read_buf = BufferedReaderSpec::buffer_size(reader);
+    let write_buf = BufferedWriterSpec::buffer_size(writer);
+
+    if read_buf >= DEFAULT_BUF_SIZE && read_buf >= write_buf {
+        return BufferedReaderSpec::copy_to(reader, writer);
+    }
+
+    BufferedWriterSpec::copy_from(writer, reader)
+}
+
+/// Specialization of the read-write loop that reuses the internal
+/// buffer of a BufReader. If there's no buffer then the writer side
+/// should be used instead.
+#[cfg(portable_io_unstable_all)] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+trait BufferedReaderSpec {
+    fn buffer_size(&self) -> usize;
+
+    fn copy_to(&mut self, to: &mut (impl Write + ?Sized)) -> Result<u64>;
+}
+
+#[cfg(portable_io_unstable_all)] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+impl<T> BufferedReaderSpec for T
+where
+    Self: Read,
+    T: ?Sized,
+{
+    #[inline]
+    default fn buffer_size(&self) -> usize {
+        0
+    }
+
+    default fn copy_to(&mut self, _to: &mut (impl Write + ?Sized)) -> Result<u64> {
+        unreachable!("only called from specializations")
+    }
+}
+
+#[cfg(portable_io_unstable_all)] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+impl BufferedReaderSpec for &[u8] {
+    fn buffer_size(&self) -> usize {
+        // prefer this specialization since the source "buffer" is all we'll ever need,
+        // even if it's small
+        usize::MAX
+    }
+
+    fn copy_to(&mut self, to: &mut (impl Write + ?Sized)) -> Result<u64> {
+        let len = self.len();
+        to.write_all(self)?;
+        *self = &self[len..];
+        Ok(len as u64)
+    }
+}
+
+// (ADAPTED with one impl for each kind of storage, since specializing on `BufferStorage` is not allowed)
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+impl<I> BufferedReaderSpec for BufReader<I>
+where
+    Self: Read,
+{
+    fn buffer_size(&self) -> usize {
+        self.capacity()
+    }
+
+    fn copy_to(&mut self, to: &mut (impl Write + ?Sized)) -> Result<u64> {
+        buf_reader_copy_to(self, to)
+    }
+}
+
+#[cfg(portable_io_unstable_all)] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+impl<I, const N: usize> BufferedReaderSpec for BufReader<I, [MaybeUninit<u8>; N]>
+where
+    Self: Read,
+{
+    fn buffer_size(&self) -> usize {
+        self.capacity()
+    }
+
+    fn copy_to(&mut self, to: &mut (impl Write + ?Sized)) -> Result<u64> {
+        buf_reader_copy_to(self, to)
+    }
+}
+
+#[cfg(portable_io_unstable_all)] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+impl<I> BufferedReaderSpec for BufReader<I, &mut [MaybeUninit<u8>]>
+where
+    Self: Read,
+{
+    fn buffer_size(&self) -> usize {
+        self.capacity()
+    }
+
+    fn copy_to(&mut self, to: &mut (impl Write + ?Sized)) -> Result<u64> {
+        buf_reader_copy_to(self, to)
+    }
+}
+
+#[cfg(portable_io_unstable_all)] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+fn buf_reader_copy_to<I, B: BufferStorage>(
+    reader: &mut BufReader<I, B>,
+    to: &mut (impl Write + ?Sized),
+) -> Result<u64>
+where
+    BufReader<I, B>: Read,
+{
+    let mut len = 0;
+
+    loop {
+        // Hack: this relies on `impl Read for BufReader` always calling fill_buf
+        // if the buffer is empty, even for empty slices.
+        // It can't be called directly here since specialization prevents us
+        // from adding I: Read
+        match reader.read(&mut []) {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+        let buf = reader.buffer();
+        if buf.is_empty() {
+            return Ok(len);
+        }
+
+        // In case the writer side is a BufWriter then its write_all
+        // implements an optimization that passes through large
+        // buffers to the underlying writer. That code path is #[cold]
+        // but we're still avoiding redundant memcopies when doing
+        // a copy between buffered inputs and outputs.
+        to.write_all(buf)?;
+        len += buf.len() as u64;
+        reader.discard_buffer();
+    }
+}
+
+/// Specialization of the read-write loop that either uses a stack buffer
+/// or reads straight into the writer. If there's no buffer on the writer
+/// side then the stack buffer is used.
+#[cfg(portable_io_unstable_all)] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+trait BufferedWriterSpec: Write {
+    fn buffer_size(&self) -> usize;
+
+    fn copy_from<R: Read + ?Sized>(&mut self, reader: &mut R) -> Result<u64>;
+}
+
+#[cfg(portable_io_unstable_all)] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+impl<W: Write + ?Sized> BufferedWriterSpec for W {
+    #[inline]
+    default fn buffer_size(&self) -> usize {
+        0
+    }
+
+    default fn copy_from<R: Read + ?Sized>(&mut self, reader: &mut R) -> Result<u64> {
+        stack_buffer_copy(reader, self)
+    }
+}
+
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))] // unstable feature: specialized copy (requires Rust nightly for min_specialization)
+impl BufferedWriterSpec for Vec<u8> {
+    fn buffer_size(&self) -> usize {
+        cmp::max(DEFAULT_BUF_SIZE, self.capacity() - self.len())
+    }
+
+    fn copy_from<R: Read + ?Sized>(&mut self, reader: &mut R) -> Result<u64> {
+        reader.read_to_end(self).map(|bytes| u64::try_from(bytes).expect("usize overflowed u64"))
+    }
+}
+
+// (ADDED in this fork) smaller than `DEFAULT_BUF_SIZE`, for the small stacks of
+// embedded targets
+const STACK_BUF_SIZE: usize = 1024;
+
+fn stack_buffer_copy<R: Read + ?Sized, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<u64> {
+    let mut buf = [MaybeUninit::<u8>::uninit(); STACK_BUF_SIZE];
+    let mut buf = ReadBuf::uninit(&mut buf);
+
+    let mut len = 0;
+
+    loop {
+        match reader.read_buf(&mut buf) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        if buf.filled().is_empty() {
+            break;
+        }
+
+        len += buf.filled().len() as u64;
+        writer.write_all(buf.filled())?;
+        buf.clear();
+    }
+
+    Ok(len)
+}
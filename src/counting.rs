@@ -0,0 +1,283 @@
+// (ADDED in this fork) readers & writers that count the bytes that pass through
+// them, for progress reports & for the location of errors in the input
+
+#[cfg(test)]
+mod tests;
+
+use crate::observe::{Unconsumed, read_buf_with};
+use crate::{BufRead, IoSlice, IoSliceMut, Read, ReadBuf, Result, Seek, SeekFrom, Write};
+
+/// Counts the bytes read from a reader, & the lines & columns in them.
+///
+/// All of the ways to read are counted, including vectored reads,
+/// [`read_buf`](Read::read_buf) & [`BufRead`], with which the bytes are counted
+/// once they are consumed, for example by [`read_until`](BufRead::read_until).
+/// If the consumed bytes cannot be got again from the underlying reader, they
+/// are counted as columns, & the error is returned by the next call to read.
+///
+/// The count is the position in the stream: it starts at 0, or at the current
+/// position of a seekable reader with
+/// [`at_stream_position`](CountingReader::at_stream_position), & it follows
+/// the seeks if the underlying reader implements [`Seek`].
+///
+/// The line & column, both starting at 1, give the location of the next byte
+/// to be read: each `\n` starts a new line, & the column is counted in bytes.
+/// They can be used to annotate errors in text formats. They are not known
+/// after a seek to another position, so they are counted again from 1,
+/// relative to the new position.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{CountingReader, Read};
+///
+/// let mut reader = CountingReader::new(&b"first line\nsecond line\n"[..]);
+/// reader.read_exact(&mut [0; 18]).unwrap();
+/// assert_eq!(reader.count(), 18);
+/// assert_eq!((reader.line(), reader.column()), (2, 8));
+/// ```
+#[derive(Debug)]
+pub struct CountingReader<R> {
+    inner: R,
+    counter: Counter,
+    unconsumed: Unconsumed,
+}
+
+impl<R> CountingReader<R> {
+    /// Creates a new `CountingReader<R>`, with a count of 0, at line 1 &
+    /// column 1.
+    pub fn new(inner: R) -> CountingReader<R> {
+        CountingReader { inner, counter: Counter::new(0), unconsumed: Unconsumed::default() }
+    }
+
+    /// Returns the position in the stream, which is the number of bytes read
+    /// unless the count started at another position or there was a seek.
+    pub fn count(&self) -> u64 {
+        self.counter.count
+    }
+
+    /// Returns the line of the next byte to be read, starting at 1.
+    pub fn line(&self) -> u64 {
+        self.counter.line
+    }
+
+    /// Returns the column of the next byte to be read, in bytes, starting at 1.
+    pub fn column(&self) -> u64 {
+        self.counter.column
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// The bytes read directly from the underlying reader are not counted.
+    pub fn get_mut(&mut self) -> &mut R {
+        self.unconsumed.reset();
+        &mut self.inner
+    }
+
+    /// Unwraps this `CountingReader<R>`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Seek> CountingReader<R> {
+    /// Creates a new `CountingReader<R>`, with a count starting at the current
+    /// position of `inner` in the stream, at line 1 & column 1.
+    pub fn at_stream_position(mut inner: R) -> Result<CountingReader<R>> {
+        let pos = inner.stream_position()?;
+        Ok(CountingReader { inner, counter: Counter::new(pos), unconsumed: Unconsumed::default() })
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.unconsumed.take_error()?;
+        let n = self.inner.read(buf)?;
+        self.counter.advance(&buf[..n]);
+        Ok(n)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        self.unconsumed.take_error()?;
+        let n = self.inner.read_vectored(bufs)?;
+        let mut remaining = n;
+        for buf in bufs.iter() {
+            let len = buf.len().min(remaining);
+            self.counter.advance(&buf[..len]);
+            remaining -= len;
+        }
+        Ok(n)
+    }
+
+    fn is_read_vectored(&self) -> bool {
+        self.inner.is_read_vectored()
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> Result<()> {
+        self.unconsumed.take_error()?;
+        // (the bytes that were filled are counted, even if there is an error)
+        read_buf_with(&mut self.inner, buf, |filled| self.counter.advance(filled))
+    }
+}
+
+impl<R: BufRead> BufRead for CountingReader<R> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.unconsumed.fill_buf(&mut self.inner)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.unconsumed.consume(&mut self.inner, amt, |consumed| {
+            self.counter.advance(consumed);
+            // (bytes that were not returned by `fill_buf` are not known)
+            self.counter.advance_columns(amt - consumed.len());
+        })
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.unconsumed.reset();
+        let new_pos = self.inner.seek(pos)?;
+        self.counter.seek(new_pos);
+        Ok(new_pos)
+    }
+}
+
+/// Counts the bytes written to a writer.
+///
+/// Only the bytes that the underlying writer accepts are counted, including
+/// with vectored writes.
+///
+/// The count is the position in the stream: it starts at 0, or at the current
+/// position of a seekable writer with
+/// [`at_stream_position`](CountingWriter::at_stream_position), & it follows
+/// the seeks if the underlying writer implements [`Seek`].
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{CountingWriter, Write};
+///
+/// let mut storage = [0; 16];
+/// let mut writer = CountingWriter::new(&mut storage[..]);
+/// writer.write_all(b"some data").unwrap();
+/// assert_eq!(writer.count(), 9);
+/// ```
+#[derive(Debug)]
+pub struct CountingWriter<W> {
+    inner: W,
+    count: u64,
+}
+
+impl<W> CountingWriter<W> {
+    /// Creates a new `CountingWriter<W>`, with a count of 0.
+    pub fn new(inner: W) -> CountingWriter<W> {
+        CountingWriter { inner, count: 0 }
+    }
+
+    /// Returns the position in the stream, which is the number of bytes
+    /// written unless the count started at another position or there was a
+    /// seek.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// The bytes written directly to the underlying writer are not counted.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `CountingWriter<W>`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Seek> CountingWriter<W> {
+    /// Creates a new `CountingWriter<W>`, with a count starting at the current
+    /// position of `inner` in the stream.
+    pub fn at_stream_position(mut inner: W) -> Result<CountingWriter<W>> {
+        let count = inner.stream_position()?;
+        Ok(CountingWriter { inner, count })
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        let n = self.inner.write_vectored(bufs)?;
+        self.count += n as u64;
+        Ok(n)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Seek> Seek for CountingWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.count = self.inner.seek(pos)?;
+        Ok(self.count)
+    }
+}
+
+// The count, line & column of a `CountingReader`
+#[derive(Debug)]
+struct Counter {
+    count: u64,
+    line: u64,
+    column: u64,
+}
+
+impl Counter {
+    fn new(count: u64) -> Counter {
+        Counter { count, line: 1, column: 1 }
+    }
+
+    fn advance(&mut self, bytes: &[u8]) {
+        self.count += bytes.len() as u64;
+        match memchr::memrchr(b'\n', bytes) {
+            Some(last) => {
+                self.line += memchr::memchr_iter(b'\n', bytes).count() as u64;
+                self.column = (bytes.len() - last) as u64;
+            }
+            None => self.column += bytes.len() as u64,
+        }
+    }
+
+    // Counts bytes that are not known, as columns of the same line
+    fn advance_columns(&mut self, amt: usize) {
+        self.count += amt as u64;
+        self.column += amt as u64;
+    }
+
+    // Moves to a new position, where the line & column are not known, unless
+    // the position is unchanged
+    fn seek(&mut self, new_pos: u64) {
+        if new_pos != self.count {
+            *self = Counter::new(new_pos);
+        }
+    }
+}
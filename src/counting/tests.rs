@@ -0,0 +1,156 @@
+use super::{CountingReader, CountingWriter};
+use crate::test_util::Flaky;
+use crate::{
+    BufRead, BufReader, Cursor, ErrorKind, IoSlice, IoSliceMut, Read, ReadBuf, Seek, SeekFrom,
+    Write,
+};
+
+const TEXT: &[u8] = b"one\ntwo\n\nthree";
+
+#[test]
+fn count_lines_and_columns() {
+    let mut reader = CountingReader::new(TEXT);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (0, 1, 1));
+
+    let mut buf = [0; 3];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!((reader.count(), reader.line(), reader.column()), (3, 1, 4));
+    reader.read_exact(&mut buf[..1]).unwrap();
+    assert_eq!((reader.count(), reader.line(), reader.column()), (4, 2, 1));
+    reader.read_exact(&mut buf[..]).unwrap();
+    assert_eq!((reader.count(), reader.line(), reader.column()), (7, 2, 4));
+    // (two newlines in one read)
+    reader.read_exact(&mut buf[..2]).unwrap();
+    assert_eq!((reader.count(), reader.line(), reader.column()), (9, 4, 1));
+    reader.read_exact(&mut buf[..]).unwrap();
+    assert_eq!((reader.count(), reader.line(), reader.column()), (12, 4, 4));
+}
+
+#[test]
+fn count_consumed_bytes() {
+    let mut storage = [0; 5];
+    let mut reader = CountingReader::new(BufReader::with_slice(&mut storage, TEXT));
+    assert_eq!(reader.fill_buf().unwrap(), b"one\nt");
+    assert_eq!(reader.count(), 0);
+    reader.consume(2);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (2, 1, 3));
+
+    // (bytes returned by `fill_buf` again are not counted twice)
+    assert_eq!(reader.fill_buf().unwrap(), b"e\nt");
+    reader.consume(3);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (5, 2, 2));
+
+    crate::copy_buf(&mut reader, &mut crate::sink()).unwrap();
+    assert_eq!((reader.count(), reader.line(), reader.column()), (14, 4, 6));
+
+    // (bytes consumed without `fill_buf` are counted as columns of the same line)
+    let mut reader = CountingReader::new(TEXT);
+    reader.consume(4);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (4, 1, 5));
+}
+
+#[test]
+fn report_consumed_bytes_that_are_missed() {
+    // (`fill_buf` fails with `WouldBlock` & `Interrupted` before each byte)
+    let mut reader = CountingReader::new(Flaky::new(TEXT));
+    assert_eq!(reader.fill_buf().unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(reader.fill_buf().unwrap_err().kind(), ErrorKind::Interrupted);
+    assert_eq!(reader.fill_buf().unwrap(), b"o");
+    reader.consume(1);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (1, 1, 2));
+    assert_eq!(reader.read(&mut [0; 4]).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn count_read_until_and_read_line() {
+    extern crate alloc;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    let mut reader = CountingReader::new(TEXT);
+    let mut line = String::new();
+    assert_eq!(reader.read_line(&mut line).unwrap(), 4);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (4, 2, 1));
+
+    let mut buf = Vec::new();
+    assert_eq!(reader.read_until(b'w', &mut buf).unwrap(), 2);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (6, 2, 3));
+
+    let lines = (&mut reader).lines().count();
+    assert_eq!(lines, 3);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (14, 4, 6));
+}
+
+#[test]
+fn count_vectored_and_read_buf() {
+    let mut reader = CountingReader::new(TEXT);
+    let (mut first, mut second) = ([0; 2], [0; 3]);
+    let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+    assert_eq!(reader.read_vectored(&mut bufs).unwrap(), 5);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (5, 2, 2));
+
+    let mut storage = [0; 4];
+    let mut buf = ReadBuf::new(&mut storage);
+    reader.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled(), b"wo\n\n");
+    assert_eq!((reader.count(), reader.line(), reader.column()), (9, 4, 1));
+}
+
+#[test]
+fn seek_keeps_count_in_sync() {
+    let mut reader = CountingReader::new(Cursor::new(TEXT));
+    reader.read_exact(&mut [0; 5]).unwrap();
+    assert_eq!(reader.stream_position().unwrap(), 5);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (5, 2, 2));
+
+    assert_eq!(reader.seek(SeekFrom::Current(-2)).unwrap(), 3);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (3, 1, 1));
+    reader.read_exact(&mut [0; 1]).unwrap();
+    assert_eq!((reader.count(), reader.line(), reader.column()), (4, 2, 1));
+
+    let mut storage = [0; 16];
+    let mut writer = CountingWriter::new(Cursor::new(&mut storage[..]));
+    writer.write_all(b"some data").unwrap();
+    assert_eq!(writer.seek(SeekFrom::Start(2)).unwrap(), 2);
+    writer.write_all(b"me").unwrap();
+    assert_eq!(writer.count(), 4);
+    assert_eq!(writer.seek(SeekFrom::End(0)).unwrap(), 16);
+    assert_eq!(writer.count(), 16);
+}
+
+#[test]
+fn count_from_stream_position() {
+    let mut cursor = Cursor::new(TEXT);
+    cursor.set_position(3);
+    let mut reader = CountingReader::at_stream_position(cursor).unwrap();
+    assert_eq!((reader.count(), reader.line(), reader.column()), (3, 1, 1));
+    reader.read_exact(&mut [0; 3]).unwrap();
+    assert_eq!((reader.count(), reader.line(), reader.column()), (6, 2, 3));
+    // (the same position, so the line & column are still known)
+    assert_eq!(reader.stream_position().unwrap(), 6);
+    assert_eq!((reader.count(), reader.line(), reader.column()), (6, 2, 3));
+
+    let mut storage = [0; 16];
+    let mut cursor = Cursor::new(&mut storage[..]);
+    cursor.set_position(5);
+    let mut writer = CountingWriter::at_stream_position(cursor).unwrap();
+    writer.write_all(b"data").unwrap();
+    assert_eq!(writer.count(), 9);
+}
+
+#[test]
+fn count_accepted_bytes() {
+    // (the underlying writer only has room for part of the input)
+    let mut storage = [0; 6];
+    let mut writer = CountingWriter::new(&mut storage[..]);
+    assert_eq!(writer.write(b"1234").unwrap(), 4);
+    assert_eq!(writer.write(b"56789").unwrap(), 2);
+    assert_eq!(writer.count(), 6);
+
+    let mut storage = [0; 16];
+    let mut writer = CountingWriter::new(Cursor::new(&mut storage[..]));
+    let bufs = [IoSlice::new(b"1234"), IoSlice::new(b""), IoSlice::new(b"56789")];
+    assert_eq!(writer.write_vectored(&bufs).unwrap(), 9);
+    assert_eq!(writer.count(), 9);
+}
@@ -2,12 +2,18 @@
 mod tests;
 
 use core::cmp;
+#[cfg(feature = "alloc")]
 use core::convert::TryInto;
+use core::mem::MaybeUninit;
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "alloc")]
 use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
+use crate::bounded::{self, BoundedVec};
 use crate::prelude::*;
 use crate::{self as io, Error, ErrorKind, IoSlice, IoSliceMut, ReadBuf, SeekFrom};
 
@@ -207,6 +213,69 @@ where
     pub fn is_empty(&self) -> bool {
         self.pos >= self.inner.as_ref().len() as u64
     }
+
+    /// Returns both halves of the underlying buffer, split at the current
+    /// position: the part that was already read or written, & the remaining
+    /// slice.
+    ///
+    /// (ADDED in this fork, from a later version of `std::io`)
+    ///
+    /// NOTE: If [`BufRead`] is in scope, `cursor.split()` calls
+    /// [`BufRead::split`] instead, which takes the cursor by value; use
+    /// `Cursor::split(&cursor)` in that case.
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::Cursor;
+    ///
+    /// let mut buff = Cursor::new([1, 2, 3, 4, 5]);
+    ///
+    /// assert_eq!(buff.split(), (&[][..], &[1, 2, 3, 4, 5][..]));
+    ///
+    /// buff.set_position(2);
+    /// assert_eq!(buff.split(), (&[1, 2][..], &[3, 4, 5][..]));
+    ///
+    /// buff.set_position(6);
+    /// assert_eq!(buff.split(), (&[1, 2, 3, 4, 5][..], &[][..]));
+    /// ```
+    pub fn split(&self) -> (&[u8], &[u8]) {
+        let slice = self.inner.as_ref();
+        let pos = self.pos.min(slice.len() as u64);
+        slice.split_at(pos as usize)
+    }
+}
+
+impl<T> Cursor<T>
+where
+    T: AsMut<[u8]>,
+{
+    /// Returns both halves of the underlying buffer as mutable slices, split
+    /// at the current position: the part that was already read or written, &
+    /// the remaining slice.
+    ///
+    /// (ADDED in this fork, from a later version of `std::io`)
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{Cursor, Write};
+    ///
+    /// let mut buff = Cursor::new([0; 8]);
+    /// buff.write_all(b"head").unwrap();
+    ///
+    /// let (written, remaining) = buff.split_mut();
+    /// written.make_ascii_uppercase();
+    /// remaining.fill(b'!');
+    /// assert_eq!(buff.get_ref(), b"HEAD!!!!");
+    /// ```
+    pub fn split_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+        let slice = self.inner.as_mut();
+        let pos = self.pos.min(slice.len() as u64);
+        slice.split_at_mut(pos as usize)
+    }
 }
 
 impl<T> Clone for Cursor<T>
@@ -342,6 +411,7 @@ fn slice_write_vectored(
 }
 
 // Resizing write implementation
+#[cfg(feature = "alloc")]
 fn vec_write(pos_mut: &mut u64, vec: &mut Vec<u8>, buf: &[u8]) -> io::Result<usize> {
     let pos: usize = (*pos_mut).try_into().map_err(|_| {
         Error::new_const(
@@ -370,6 +440,7 @@ fn vec_write(pos_mut: &mut u64, vec: &mut Vec<u8>, buf: &[u8]) -> io::Result<usi
     Ok(buf.len())
 }
 
+#[cfg(feature = "alloc")]
 fn vec_write_vectored(
     pos_mut: &mut u64,
     vec: &mut Vec<u8>,
@@ -382,6 +453,37 @@ fn vec_write_vectored(
     Ok(nwritten)
 }
 
+// Fixed-capacity write implementation
+#[inline]
+fn bounded_vec_write<V: BoundedVec>(
+    pos_mut: &mut u64,
+    vec: &mut V,
+    buf: &[u8],
+) -> io::Result<usize> {
+    // (past the capacity, nothing is written)
+    let pos = usize::try_from(*pos_mut).unwrap_or(usize::MAX);
+    let amt = bounded::write_at(vec, pos, buf);
+    *pos_mut += amt as u64;
+    Ok(amt)
+}
+
+#[inline]
+fn bounded_vec_write_vectored<V: BoundedVec>(
+    pos_mut: &mut u64,
+    vec: &mut V,
+    bufs: &[IoSlice<'_>],
+) -> io::Result<usize> {
+    let mut nwritten = 0;
+    for buf in bufs {
+        let n = bounded_vec_write(pos_mut, vec, buf)?;
+        nwritten += n;
+        if n < buf.len() {
+            break;
+        }
+    }
+    Ok(nwritten)
+}
+
 impl Write for Cursor<&mut [u8]> {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
@@ -404,6 +506,29 @@ impl Write for Cursor<&mut [u8]> {
     }
 }
 
+impl<const N: usize> Write for Cursor<&mut [u8; N]> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        slice_write(&mut self.pos, &mut self.inner[..], buf)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        slice_write_vectored(&mut self.pos, &mut self.inner[..], bufs)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl Write for Cursor<&mut Vec<u8>> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         vec_write(&mut self.pos, self.inner, buf)
@@ -424,6 +549,7 @@ impl Write for Cursor<&mut Vec<u8>> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl Write for Cursor<Vec<u8>> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         vec_write(&mut self.pos, &mut self.inner, buf)
@@ -444,6 +570,7 @@ impl Write for Cursor<Vec<u8>> {
     }
 }
 
+#[cfg(feature = "alloc")]
 impl Write for Cursor<Box<[u8]>> {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
@@ -465,3 +592,201 @@ impl Write for Cursor<Box<[u8]>> {
         Ok(())
     }
 }
+
+impl<const N: usize> Write for Cursor<[u8; N]> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        slice_write(&mut self.pos, &mut self.inner, buf)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        slice_write_vectored(&mut self.pos, &mut self.inner, bufs)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// (ADDED in this fork) A cursor that writes into a buffer that may be
+/// uninitialized, for example to serialize into a buffer on the stack without
+/// zeroing it first.
+///
+/// Like a [`ReadBuf`], which it is built on, an `UninitCursor` tracks the
+/// part of the buffer that was initialized, which is the high-water mark of
+/// the bytes that were written: seeking back & writing again overwrites the
+/// bytes that were written before, without losing the bytes after them.
+/// Seeking past the initialized part fills the gap with zeros, & seeking past
+/// the end of the buffer is an error. [`SeekFrom::End`] is relative to the
+/// end of the initialized part.
+///
+/// Writing past the end of the buffer returns short writes, like for
+/// <code>Cursor<&mut \[u8\]></code>.
+///
+/// # Example code
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use portable_io::{Seek, SeekFrom, UninitCursor, Write};
+///
+/// let mut storage = [MaybeUninit::uninit(); 64];
+/// let mut cursor = UninitCursor::new(&mut storage);
+/// cursor.write_all(b"length: ??\n").unwrap();
+/// cursor.write_all(b"some data").unwrap();
+///
+/// // (go back to fill in the length)
+/// cursor.seek(SeekFrom::Start(8)).unwrap();
+/// cursor.write_all(b"09").unwrap();
+/// assert_eq!(cursor.initialized(), b"length: 09\nsome data");
+/// ```
+#[derive(Debug)]
+pub struct UninitCursor<'a> {
+    // (the filled part of the buffer ends at the position of the cursor)
+    buf: ReadBuf<'a>,
+}
+
+impl<'a> UninitCursor<'a> {
+    /// Creates a new `UninitCursor` at the start of the given buffer, which
+    /// is assumed to be uninitialized.
+    pub fn new(buf: &'a mut [MaybeUninit<u8>]) -> UninitCursor<'a> {
+        UninitCursor { buf: ReadBuf::uninit(buf) }
+    }
+
+    /// Returns the current position of this cursor.
+    pub fn position(&self) -> u64 {
+        self.buf.filled_len() as u64
+    }
+
+    /// Returns the total capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Returns the initialized part of the buffer, up to the high-water mark
+    /// of the bytes that were written.
+    pub fn initialized(&self) -> &[u8] {
+        self.buf.initialized()
+    }
+
+    /// Returns the initialized part of the buffer as a mutable slice.
+    pub fn initialized_mut(&mut self) -> &mut [u8] {
+        self.buf.initialized_mut()
+    }
+
+    /// Consumes this cursor, returning the initialized part of the buffer.
+    pub fn into_initialized(self) -> &'a mut [u8] {
+        self.buf.into_initialized()
+    }
+}
+
+impl Write for UninitCursor<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let amt = cmp::min(buf.len(), self.buf.remaining());
+        self.buf.append(&buf[..amt]);
+        Ok(amt)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let mut nwritten = 0;
+        for buf in bufs {
+            let n = self.write(buf)?;
+            nwritten += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Ok(nwritten)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl io::Seek for UninitCursor<'_> {
+    fn seek(&mut self, style: SeekFrom) -> io::Result<u64> {
+        let pos = match style {
+            SeekFrom::Start(n) => Some(n),
+            SeekFrom::End(n) => (self.buf.initialized_len() as u64).checked_add_signed(n),
+            SeekFrom::Current(n) => self.position().checked_add_signed(n),
+        };
+        let pos = match pos {
+            Some(n) => n,
+            None => {
+                return Err(Error::new_const(
+                    ErrorKind::InvalidInput,
+                    &"invalid seek to a negative or overflowing position",
+                ));
+            }
+        };
+        if pos > self.capacity() as u64 {
+            return Err(Error::new_const(
+                ErrorKind::InvalidInput,
+                &"invalid seek to a position past the end of the buffer",
+            ));
+        }
+        let pos = pos as usize;
+        let initialized = self.buf.initialized_len();
+        if pos > initialized {
+            // (zero the gap, so that the initialized part stays contiguous)
+            self.buf.set_filled(initialized);
+            self.buf.initialize_unfilled_to(pos - initialized);
+        }
+        self.buf.set_filled(pos);
+        Ok(pos as u64)
+    }
+
+    fn stream_len(&mut self) -> io::Result<u64> {
+        Ok(self.buf.initialized_len() as u64)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.position())
+    }
+}
+
+/// (ADDED in this fork) Write is implemented for a cursor over a vector of a
+/// fixed capacity, such as `heapless::Vec<u8, N>` or
+/// `arrayvec::ArrayVec<u8, N>` (with the `heapless` or `arrayvec` feature), or
+/// a mutable reference to one.
+///
+/// Like for `Cursor<Vec<u8>>`, the bytes of the vector are overwritten & then
+/// the vector grows, with zeros if the position is past the end, but only up
+/// to its capacity, after which writes are short like for
+/// <code>Cursor<&mut \[u8\]></code>.
+impl<V: BoundedVec> Write for Cursor<V> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        bounded_vec_write(&mut self.pos, &mut self.inner, buf)
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        bounded_vec_write_vectored(&mut self.pos, &mut self.inner, bufs)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,10 +1,19 @@
+use core::mem::MaybeUninit;
+
 extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::collections::VecDeque;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 
+#[cfg(feature = "alloc")]
+use crate::ReadBuf;
 use crate::prelude::*;
-use crate::{Cursor, IoSlice, IoSliceMut, SeekFrom};
+use crate::{Cursor, ErrorKind, IoSlice, IoSliceMut, SeekFrom, UninitCursor};
 
+#[cfg(feature = "alloc")]
 #[test]
 fn test_vec_writer() {
     let mut writer = Vec::new();
@@ -21,6 +30,137 @@ fn test_vec_writer() {
     assert_eq!(writer, b);
 }
 
+// Returns a `VecDeque` holding `[0, 1, 2, 3, 4, 5, 6, 7]`, which wraps around the end of its
+// storage after the first 4 bytes
+#[cfg(feature = "alloc")]
+fn wrapped_vec_deque() -> VecDeque<u8> {
+    // (moves the start of the ring buffer until the bytes wrap around its end,
+    // as evenly as possible, whatever the capacity & layout of `VecDeque`)
+    for offset in 0.. {
+        let mut deque = VecDeque::with_capacity(8);
+        for _ in 0..offset {
+            deque.push_back(9);
+            deque.pop_front();
+        }
+        deque.extend([0, 1, 2, 3, 4, 5, 6, 7]);
+        let (front, back) = deque.as_slices();
+        if back.len() >= front.len() {
+            assert!(!front.is_empty() && !back.is_empty());
+            return deque;
+        }
+        assert!(offset <= deque.capacity(), "the deque does not wrap around");
+    }
+    unreachable!()
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_vec_deque_writer() {
+    let mut writer = VecDeque::new();
+    assert_eq!(writer.write(&[0]).unwrap(), 1);
+    assert_eq!(writer.write(&[1, 2, 3]).unwrap(), 3);
+    assert_eq!(writer.write(&[4, 5, 6, 7]).unwrap(), 4);
+    assert_eq!(
+        writer
+            .write_vectored(&[IoSlice::new(&[]), IoSlice::new(&[8, 9]), IoSlice::new(&[10])],)
+            .unwrap(),
+        3
+    );
+    writer.write_all(&[11]).unwrap();
+    let b: &[_] = &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+    assert_eq!(writer, b);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_vec_deque_reader() {
+    let mut reader = wrapped_vec_deque();
+    let mut buf = [];
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    let mut buf = [0];
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    assert_eq!(reader.len(), 7);
+    assert_eq!(buf, [0]);
+    // (both halves of the deque in a single read)
+    let mut buf = [0; 5];
+    assert_eq!(reader.read(&mut buf).unwrap(), 5);
+    assert_eq!(buf, [1, 2, 3, 4, 5]);
+    assert_eq!(reader.read(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[..2], [6, 7]);
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_vec_deque_reader_vectored() {
+    let mut reader = wrapped_vec_deque();
+    let mut buf = [];
+    assert_eq!(reader.read_vectored(&mut [IoSliceMut::new(&mut buf)]).unwrap(), 0);
+    let mut buf = [0];
+    assert_eq!(
+        reader.read_vectored(&mut [IoSliceMut::new(&mut []), IoSliceMut::new(&mut buf),]).unwrap(),
+        1,
+    );
+    assert_eq!(reader.len(), 7);
+    let mut buf1 = [0; 2];
+    let mut buf2 = [0; 4];
+    assert_eq!(
+        reader
+            .read_vectored(&mut [IoSliceMut::new(&mut buf1), IoSliceMut::new(&mut buf2)],)
+            .unwrap(),
+        6,
+    );
+    assert_eq!(buf1, [1, 2]);
+    assert_eq!(buf2, [3, 4, 5, 6]);
+    assert_eq!(reader.read_vectored(&mut [IoSliceMut::new(&mut buf1)]).unwrap(), 1);
+    assert_eq!(reader.read(&mut buf1).unwrap(), 0);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_vec_deque_read_exact_and_read_buf() {
+    let mut reader = wrapped_vec_deque();
+    let mut buf = [0; 6];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [0, 1, 2, 3, 4, 5]);
+    assert_eq!(reader.read_exact(&mut buf).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert!(reader.is_empty());
+
+    let mut reader = wrapped_vec_deque();
+    let mut storage = [0; 7];
+    let mut buf = ReadBuf::new(&mut storage);
+    buf.append(&[9]);
+    reader.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled(), [9, 0, 1, 2, 3, 4, 5]);
+    assert_eq!(reader, [6, 7]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_vec_deque_read_to_end_and_buf_read() {
+    let mut reader = wrapped_vec_deque();
+    let (front, back) = (reader.as_slices().0.to_vec(), reader.as_slices().1.to_vec());
+    assert_eq!(reader.fill_buf().unwrap(), front);
+    reader.consume(front.len() - 1);
+    assert_eq!(reader.fill_buf().unwrap(), &front[front.len() - 1..]);
+    reader.consume(1);
+    assert_eq!(reader.fill_buf().unwrap(), back);
+
+    let mut buf = vec![9];
+    assert_eq!(reader.read_to_end(&mut buf).unwrap(), back.len());
+    assert_eq!(buf[0], 9);
+    assert_eq!(buf[1..], back);
+    assert!(reader.is_empty());
+
+    let mut reader: VecDeque<u8> = b"some text".iter().copied().collect();
+    let mut text = String::new();
+    assert_eq!(reader.read_to_string(&mut text).unwrap(), 9);
+    assert_eq!(text, "some text");
+    let mut reader: VecDeque<u8> = [0xff].into_iter().collect();
+    assert_eq!(reader.read_to_string(&mut text).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "alloc")]
 #[test]
 fn test_mem_writer() {
     let mut writer = Cursor::new(Vec::new());
@@ -37,6 +177,7 @@ fn test_mem_writer() {
     assert_eq!(&writer.get_ref()[..], b);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn test_mem_mut_writer() {
     let mut vec = Vec::new();
@@ -54,6 +195,7 @@ fn test_mem_mut_writer() {
     assert_eq!(&writer.get_ref()[..], b);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn test_box_slice_writer() {
     let mut writer = Cursor::new(vec![0u8; 9].into_boxed_slice());
@@ -72,6 +214,7 @@ fn test_box_slice_writer() {
     assert_eq!(&**writer.get_ref(), b);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn test_box_slice_writer_vectored() {
     let mut writer = Cursor::new(vec![0u8; 9].into_boxed_slice());
@@ -175,6 +318,93 @@ fn test_buf_writer_error() {
     assert_eq!(writer.write(&[0, 0]).unwrap(), 0);
 }
 
+#[test]
+fn test_array_writer() {
+    let mut writer = Cursor::new([0u8; 9]);
+    assert_eq!(writer.write(&[0]).unwrap(), 1);
+    assert_eq!(writer.write(&[1, 2, 3]).unwrap(), 3);
+    assert_eq!(
+        writer.write_vectored(&[IoSlice::new(&[4, 5, 6, 7]), IoSlice::new(&[8, 9])],).unwrap(),
+        5,
+    );
+    assert_eq!(writer.position(), 9);
+    assert_eq!(writer.write(&[10]).unwrap(), 0);
+    let b: &[_] = &[0, 1, 2, 3, 4, 5, 6, 7, 8];
+    assert_eq!(writer.get_ref(), b);
+
+    let mut buf = [0u8; 4];
+    {
+        let mut writer = Cursor::new(&mut buf);
+        assert_eq!(writer.write(&[1, 2]).unwrap(), 2);
+        assert_eq!(writer.seek(SeekFrom::End(-1)).unwrap(), 3);
+        assert_eq!(writer.write_vectored(&[IoSlice::new(&[3, 4])]).unwrap(), 1);
+    }
+    assert_eq!(buf, [1, 2, 0, 3]);
+}
+
+#[test]
+fn test_split() {
+    let mut cursor = Cursor::new([0u8, 1, 2, 3, 4]);
+    cursor.set_position(2);
+    // (with `BufRead` in scope, `cursor.split()` would be `BufRead::split`)
+    assert_eq!(Cursor::split(&cursor), (&[0, 1][..], &[2, 3, 4][..]));
+    let (consumed, remaining) = cursor.split_mut();
+    consumed[0] = 5;
+    remaining[2] = 6;
+    assert_eq!(cursor.get_ref(), &[5, 1, 2, 3, 6]);
+
+    // (past the end, everything is in the first half)
+    cursor.set_position(10);
+    assert_eq!(Cursor::split(&cursor), (&[5, 1, 2, 3, 6][..], &[][..]));
+    assert_eq!(cursor.split_mut().1, &mut []);
+}
+
+#[test]
+fn test_uninit_cursor() {
+    let mut storage = [MaybeUninit::uninit(); 8];
+    let mut writer = UninitCursor::new(&mut storage);
+    assert_eq!((writer.position(), writer.capacity()), (0, 8));
+    assert_eq!(writer.initialized(), []);
+    assert_eq!(writer.write(&[0, 1, 2]).unwrap(), 3);
+    assert_eq!(
+        writer
+            .write_vectored(&[IoSlice::new(&[3]), IoSlice::new(&[]), IoSlice::new(&[4])])
+            .unwrap(),
+        2,
+    );
+    assert_eq!(writer.initialized(), [0, 1, 2, 3, 4]);
+
+    // (overwriting keeps the high-water mark)
+    assert_eq!(writer.seek(SeekFrom::Start(1)).unwrap(), 1);
+    assert_eq!(writer.write(&[5]).unwrap(), 1);
+    assert_eq!(writer.position(), 2);
+    assert_eq!(writer.initialized(), [0, 5, 2, 3, 4]);
+    assert_eq!(writer.seek(SeekFrom::End(0)).unwrap(), 5);
+    assert_eq!(writer.stream_len().unwrap(), 5);
+
+    // (the gap is zeroed when seeking past the initialized part)
+    assert_eq!(writer.seek(SeekFrom::Current(1)).unwrap(), 6);
+    assert_eq!(writer.write(&[6, 7, 8]).unwrap(), 2);
+    assert_eq!(writer.write(&[8]).unwrap(), 0);
+    assert_eq!(writer.initialized(), [0, 5, 2, 3, 4, 0, 6, 7]);
+    writer.initialized_mut()[0] = 9;
+    assert_eq!(writer.into_initialized(), [9, 5, 2, 3, 4, 0, 6, 7]);
+}
+
+#[test]
+fn test_uninit_cursor_seek_errors() {
+    let mut storage = [MaybeUninit::uninit(); 4];
+    let mut writer = UninitCursor::new(&mut storage);
+    writer.write_all(&[1, 2]).unwrap();
+    assert_eq!(writer.seek(SeekFrom::Current(-3)).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(writer.seek(SeekFrom::Start(5)).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(writer.seek(SeekFrom::End(3)).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(writer.position(), 2);
+    assert_eq!(writer.seek(SeekFrom::Start(4)).unwrap(), 4);
+    assert_eq!(writer.write_all(&[3]).unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(writer.initialized(), [1, 2, 0, 0]);
+}
+
 #[test]
 fn test_mem_reader() {
     let mut reader = Cursor::new(vec![0, 1, 2, 3, 4, 5, 6, 7]);
@@ -277,6 +507,7 @@ fn test_boxed_slice_reader_vectored() {
     assert_eq!(reader.read(&mut buf).unwrap(), 0);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn read_to_end() {
     let mut reader = Cursor::new(vec![0, 1, 2, 3, 4, 5, 6, 7]);
@@ -377,6 +608,7 @@ fn test_buf_reader() {
     assert_eq!(reader.read(&mut buf).unwrap(), 0);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn seek_past_end() {
     let buf = [0xff];
@@ -452,6 +684,7 @@ fn seek_before_0() {
     assert!(r.seek(SeekFrom::End(-2)).is_err());
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn test_seekable_mem_writer() {
     let mut writer = Cursor::new(Vec::<u8>::new());
@@ -486,6 +719,7 @@ fn test_seekable_mem_writer() {
     assert_eq!(&writer.get_ref()[..], b);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn vec_seek_past_end() {
     let mut r = Cursor::new(Vec::new());
@@ -0,0 +1,389 @@
+// (ADDED in this fork) adapters between the traits of this crate & the traits of `embedded-io`
+
+#[cfg(test)]
+mod tests;
+
+use core::any::Any;
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+use crate::{BufRead, Error, ErrorKind, Read, Result, Seek, SeekFrom, Write};
+
+/// Adapts a reader or writer from `embedded-io` to the traits of this crate.
+///
+/// `FromEmbedded<T>` implements [`Read`], [`Write`], [`Seek`] & [`BufRead`] of
+/// this crate if `T` implements the same trait from `embedded-io`, so that a
+/// driver such as a UART from a HAL can be used with [`Read::take`],
+/// [`Read::chain`], [`BufReader`](crate::BufReader), [`copy`](crate::copy) &
+/// the like.
+///
+/// Errors from `T` are converted into an [`Error`] of the same kind. With the
+/// `alloc` feature, the error from `T` is kept as the payload, in an
+/// [`EmbeddedError`]. Otherwise, only the kind is kept. An [`Error`] from this
+/// crate, as returned by a [`ToEmbedded`], is passed on as is.
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(all(feature = "embedded-io", feature = "alloc"))] {
+/// use core::mem::MaybeUninit;
+/// use portable_io::{BufRead, BufReader, FromEmbedded};
+///
+/// // (`&[u8]` implements `embedded_io::Read`, like a driver from a HAL would)
+/// let uart: &[u8] = b"first line\nsecond line\n";
+/// let mut reader: BufReader<_, [MaybeUninit<u8>; 64]> = BufReader::with_array(FromEmbedded::new(uart));
+///
+/// let mut line = String::new();
+/// reader.read_line(&mut line).unwrap();
+/// assert_eq!(line, "first line\n");
+/// # }
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>embedded-io</code></div>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FromEmbedded<T> {
+    inner: T,
+}
+
+impl<T> FromEmbedded<T> {
+    /// Creates a new `FromEmbedded` that wraps the given reader or writer from `embedded-io`.
+    pub const fn new(inner: T) -> FromEmbedded<T> {
+        FromEmbedded { inner }
+    }
+
+    /// Gets a reference to the underlying reader or writer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader or writer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this `FromEmbedded`, returning the underlying reader or writer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> Read for FromEmbedded<T>
+where
+    T: embedded_io::Read,
+    T::Error: Send + Sync + 'static,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf).map_err(from_embedded_error)
+    }
+}
+
+impl<T> BufRead for FromEmbedded<T>
+where
+    T: embedded_io::Read + embedded_io::BufRead,
+    T::Error: Send + Sync + 'static,
+{
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.inner.fill_buf().map_err(from_embedded_error)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl<T> Write for FromEmbedded<T>
+where
+    T: embedded_io::Write,
+    T::Error: Send + Sync + 'static,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf).map_err(from_embedded_error)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush().map_err(from_embedded_error)
+    }
+}
+
+impl<T> Seek for FromEmbedded<T>
+where
+    T: embedded_io::Seek,
+    T::Error: Send + Sync + 'static,
+{
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.inner.seek(to_embedded_seek_from(pos)).map_err(from_embedded_error)
+    }
+
+    #[inline]
+    fn rewind(&mut self) -> Result<()> {
+        self.inner.rewind().map_err(from_embedded_error)
+    }
+
+    #[inline]
+    fn stream_position(&mut self) -> Result<u64> {
+        self.inner.stream_position().map_err(from_embedded_error)
+    }
+}
+
+/// Adapts a reader or writer of this crate to the traits of `embedded-io`.
+///
+/// `ToEmbedded<T>` implements `embedded_io::{Read, Write, Seek, BufRead}` if
+/// `T` implements the same trait of this crate, with [`Error`] as the error type.
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(feature = "embedded-io")] {
+/// use portable_io::{Cursor, ToEmbedded};
+///
+/// fn send<W: embedded_io::Write>(mut writer: W) -> Result<(), W::Error> {
+///     writer.write_all(b"hello")
+/// }
+///
+/// let mut buffer = [0; 8];
+/// send(ToEmbedded::new(Cursor::new(&mut buffer[..]))).unwrap();
+/// assert_eq!(&buffer[..5], b"hello");
+/// # }
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>embedded-io</code></div>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ToEmbedded<T> {
+    inner: T,
+}
+
+impl<T> ToEmbedded<T> {
+    /// Creates a new `ToEmbedded` that wraps the given reader or writer of this crate.
+    pub const fn new(inner: T) -> ToEmbedded<T> {
+        ToEmbedded { inner }
+    }
+
+    /// Gets a reference to the underlying reader or writer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader or writer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this `ToEmbedded`, returning the underlying reader or writer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> embedded_io::ErrorType for ToEmbedded<T> {
+    type Error = Error;
+}
+
+impl<T: Read> embedded_io::Read for ToEmbedded<T> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<T: BufRead> embedded_io::BufRead for ToEmbedded<T> {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl<T: Write> embedded_io::Write for ToEmbedded<T> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.inner.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        self.inner.write_all(buf)
+    }
+}
+
+impl<T: Seek> embedded_io::Seek for ToEmbedded<T> {
+    #[inline]
+    fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64> {
+        self.inner.seek(from_embedded_seek_from(pos))
+    }
+
+    #[inline]
+    fn rewind(&mut self) -> Result<()> {
+        self.inner.rewind()
+    }
+
+    #[inline]
+    fn stream_position(&mut self) -> Result<u64> {
+        self.inner.stream_position()
+    }
+}
+
+/// An error from `embedded-io`, kept as the payload of an [`Error`] that was
+/// converted by [`FromEmbedded`].
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(feature = "embedded-io")] {
+/// use portable_io::{EmbeddedError, ErrorKind, FromEmbedded, Write};
+///
+/// // (`&mut [u8]` returns an error from `embedded-io` when it is full)
+/// let mut storage = [0; 4];
+/// let mut writer = FromEmbedded::new(&mut storage[..]);
+///
+/// let error = writer.write_all(b"too long").unwrap_err();
+/// assert_eq!(error.kind(), ErrorKind::WriteZero);
+/// let inner = error.get_ref().unwrap().downcast_ref::<EmbeddedError<embedded_io::SliceWriteError>>();
+/// assert_eq!(inner.unwrap().get_ref(), &embedded_io::SliceWriteError::Full);
+/// # }
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES features to be enabled: <code>embedded-io</code> & <code>alloc</code></div>
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbeddedError<E> {
+    error: E,
+}
+
+#[cfg(feature = "alloc")]
+impl<E> EmbeddedError<E> {
+    /// Gets a reference to the error from `embedded-io`.
+    pub fn get_ref(&self) -> &E {
+        &self.error
+    }
+
+    /// Unwraps this `EmbeddedError`, returning the error from `embedded-io`.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+// (errors from `embedded-io` only need to implement `Debug`)
+#[cfg(feature = "alloc")]
+impl<E: fmt::Debug> fmt::Display for EmbeddedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: fmt::Debug> core::error::Error for EmbeddedError<E> {}
+
+impl embedded_io::Error for Error {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        self.kind().into()
+    }
+}
+
+// (kinds that `embedded-io` does not have are converted to `Other`)
+impl From<ErrorKind> for embedded_io::ErrorKind {
+    /// Converts an [`ErrorKind`] into an `embedded_io::ErrorKind`.
+    fn from(kind: ErrorKind) -> embedded_io::ErrorKind {
+        use embedded_io::ErrorKind as EmbeddedKind;
+        match kind {
+            ErrorKind::NotFound => EmbeddedKind::NotFound,
+            ErrorKind::PermissionDenied => EmbeddedKind::PermissionDenied,
+            ErrorKind::ConnectionRefused => EmbeddedKind::ConnectionRefused,
+            ErrorKind::ConnectionReset => EmbeddedKind::ConnectionReset,
+            ErrorKind::ConnectionAborted => EmbeddedKind::ConnectionAborted,
+            ErrorKind::NotConnected => EmbeddedKind::NotConnected,
+            ErrorKind::AddrInUse => EmbeddedKind::AddrInUse,
+            ErrorKind::AddrNotAvailable => EmbeddedKind::AddrNotAvailable,
+            ErrorKind::BrokenPipe => EmbeddedKind::BrokenPipe,
+            ErrorKind::AlreadyExists => EmbeddedKind::AlreadyExists,
+            ErrorKind::InvalidInput => EmbeddedKind::InvalidInput,
+            ErrorKind::InvalidData => EmbeddedKind::InvalidData,
+            ErrorKind::TimedOut => EmbeddedKind::TimedOut,
+            ErrorKind::Interrupted => EmbeddedKind::Interrupted,
+            ErrorKind::Unsupported => EmbeddedKind::Unsupported,
+            ErrorKind::OutOfMemory => EmbeddedKind::OutOfMemory,
+            ErrorKind::WriteZero => EmbeddedKind::WriteZero,
+            _ => EmbeddedKind::Other,
+        }
+    }
+}
+
+// (kinds that this crate does not know of are converted to `Uncategorized`)
+impl From<embedded_io::ErrorKind> for ErrorKind {
+    /// Converts an `embedded_io::ErrorKind` into an [`ErrorKind`].
+    fn from(kind: embedded_io::ErrorKind) -> ErrorKind {
+        use embedded_io::ErrorKind as EmbeddedKind;
+        match kind {
+            EmbeddedKind::Other => ErrorKind::Other,
+            EmbeddedKind::NotFound => ErrorKind::NotFound,
+            EmbeddedKind::PermissionDenied => ErrorKind::PermissionDenied,
+            EmbeddedKind::ConnectionRefused => ErrorKind::ConnectionRefused,
+            EmbeddedKind::ConnectionReset => ErrorKind::ConnectionReset,
+            EmbeddedKind::ConnectionAborted => ErrorKind::ConnectionAborted,
+            EmbeddedKind::NotConnected => ErrorKind::NotConnected,
+            EmbeddedKind::AddrInUse => ErrorKind::AddrInUse,
+            EmbeddedKind::AddrNotAvailable => ErrorKind::AddrNotAvailable,
+            EmbeddedKind::BrokenPipe => ErrorKind::BrokenPipe,
+            EmbeddedKind::AlreadyExists => ErrorKind::AlreadyExists,
+            EmbeddedKind::InvalidInput => ErrorKind::InvalidInput,
+            EmbeddedKind::InvalidData => ErrorKind::InvalidData,
+            EmbeddedKind::TimedOut => ErrorKind::TimedOut,
+            EmbeddedKind::Interrupted => ErrorKind::Interrupted,
+            EmbeddedKind::Unsupported => ErrorKind::Unsupported,
+            EmbeddedKind::OutOfMemory => ErrorKind::OutOfMemory,
+            EmbeddedKind::WriteZero => ErrorKind::WriteZero,
+            _ => ErrorKind::Uncategorized,
+        }
+    }
+}
+
+pub(crate) fn from_embedded_error<E>(error: E) -> Error
+where
+    E: embedded_io::Error + Send + Sync + 'static,
+{
+    // (an error from this crate is passed on as is)
+    let mut error = Some(error);
+    if let Some(error) = (&mut error as &mut dyn Any).downcast_mut::<Option<Error>>() {
+        return error.take().unwrap();
+    }
+    let error = error.unwrap();
+
+    let kind = ErrorKind::from(error.kind());
+    #[cfg(feature = "alloc")]
+    return Error::new(kind, EmbeddedError { error });
+    #[cfg(not(feature = "alloc"))]
+    return Error::from(kind);
+}
+
+pub(crate) fn to_embedded_seek_from(pos: SeekFrom) -> embedded_io::SeekFrom {
+    match pos {
+        SeekFrom::Start(n) => embedded_io::SeekFrom::Start(n),
+        SeekFrom::End(n) => embedded_io::SeekFrom::End(n),
+        SeekFrom::Current(n) => embedded_io::SeekFrom::Current(n),
+    }
+}
+
+pub(crate) fn from_embedded_seek_from(pos: embedded_io::SeekFrom) -> SeekFrom {
+    match pos {
+        embedded_io::SeekFrom::Start(n) => SeekFrom::Start(n),
+        embedded_io::SeekFrom::End(n) => SeekFrom::End(n),
+        embedded_io::SeekFrom::Current(n) => SeekFrom::Current(n),
+    }
+}
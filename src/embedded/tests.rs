@@ -0,0 +1,255 @@
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use core::mem::MaybeUninit;
+
+use super::{FromEmbedded, ToEmbedded};
+use crate::{BufRead, Cursor, Error, ErrorKind, Read, Seek, SeekFrom, Write};
+
+// An in-memory UART, as a driver from a HAL would implement the `embedded-io` traits
+struct Uart<'a> {
+    rx: &'a [u8],
+    tx: [u8; 32],
+    tx_len: usize,
+    // (returned by the next read, if any)
+    rx_error: Option<UartError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UartError {
+    Overrun,
+    Framing,
+}
+
+impl embedded_io::Error for UartError {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            UartError::Overrun => embedded_io::ErrorKind::Other,
+            UartError::Framing => embedded_io::ErrorKind::InvalidData,
+        }
+    }
+}
+
+impl<'a> Uart<'a> {
+    fn new(rx: &'a [u8]) -> Self {
+        Uart { rx, tx: [0; 32], tx_len: 0, rx_error: None }
+    }
+
+    fn sent(&self) -> &[u8] {
+        &self.tx[..self.tx_len]
+    }
+}
+
+impl embedded_io::ErrorType for Uart<'_> {
+    type Error = UartError;
+}
+
+impl embedded_io::Read for Uart<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, UartError> {
+        if let Some(error) = self.rx_error.take() {
+            return Err(error);
+        }
+        // (at most 3 bytes at a time, like a small hardware FIFO)
+        let n = buf.len().min(self.rx.len()).min(3);
+        buf[..n].copy_from_slice(&self.rx[..n]);
+        self.rx = &self.rx[n..];
+        Ok(n)
+    }
+}
+
+impl embedded_io::Write for Uart<'_> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, UartError> {
+        let n = buf.len().min(self.tx.len() - self.tx_len).min(3);
+        if n == 0 && !buf.is_empty() {
+            return Err(UartError::Overrun);
+        }
+        self.tx[self.tx_len..self.tx_len + n].copy_from_slice(&buf[..n]);
+        self.tx_len += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<(), UartError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn read_from_embedded() {
+    let mut reader = FromEmbedded::new(Uart::new(b"hello world"));
+
+    let mut buf = [0; 8];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello wo");
+
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"rld");
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn take_and_chain_with_embedded() {
+    let first = FromEmbedded::new(Uart::new(b"abcdef"));
+    let second = FromEmbedded::new(&b"ghi"[..]);
+    let mut reader = first.take(4).chain(second);
+
+    let mut buf = [0; 16];
+    let mut len = 0;
+    loop {
+        let n = reader.read(&mut buf[len..]).unwrap();
+        if n == 0 {
+            break;
+        }
+        len += n;
+    }
+    assert_eq!(&buf[..len], b"abcdghi");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn read_until_and_lines_with_embedded() {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use crate::BufReader;
+
+    let uart = Uart::new(b"one,two\nthree\n");
+    let mut reader: BufReader<_, [MaybeUninit<u8>; 8]> =
+        BufReader::with_array(FromEmbedded::new(uart));
+
+    let mut buf = Vec::new();
+    reader.read_until(b',', &mut buf).unwrap();
+    assert_eq!(buf, b"one,");
+
+    let lines: Vec<String> = reader.lines().map(|l| l.unwrap()).collect();
+    assert_eq!(lines, ["two", "three"]);
+}
+
+#[test]
+fn buf_read_from_embedded() {
+    // (`&[u8]` implements `embedded_io::BufRead`)
+    let mut reader = FromEmbedded::new(&b"abc"[..]);
+    assert_eq!(reader.fill_buf().unwrap(), b"abc");
+    reader.consume(2);
+    assert_eq!(reader.fill_buf().unwrap(), b"c");
+}
+
+#[test]
+fn write_to_embedded() {
+    let mut writer = FromEmbedded::new(Uart::new(b""));
+    writer.write_all(b"hello world").unwrap();
+    write!(writer, " {}", 42).unwrap();
+    writer.flush().unwrap();
+    assert_eq!(writer.get_ref().sent(), b"hello world 42");
+}
+
+#[test]
+fn copy_embedded_into_cursor() {
+    let mut reader = FromEmbedded::new(Uart::new(b"from the uart"));
+    let mut storage = [0; 16];
+    let mut cursor = Cursor::new(&mut storage[..]);
+
+    assert_eq!(crate::copy(&mut reader, &mut cursor).unwrap(), 13);
+    assert_eq!(&storage[..13], b"from the uart");
+}
+
+#[test]
+fn errors_from_embedded() {
+    let mut uart = Uart::new(b"abc");
+    uart.rx_error = Some(UartError::Framing);
+    let mut reader = FromEmbedded::new(uart);
+
+    let error = reader.read(&mut [0; 4]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    #[cfg(feature = "alloc")]
+    {
+        let inner = error.get_ref().unwrap().downcast_ref::<super::EmbeddedError<UartError>>();
+        assert_eq!(inner.unwrap().get_ref(), &UartError::Framing);
+        assert_eq!(alloc::format!("{}", error), "Framing");
+    }
+
+    let mut writer = FromEmbedded::new(Uart::new(b""));
+    let error = writer.write_all(&[0; 40]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Other);
+    #[cfg(feature = "alloc")]
+    assert!(error.get_ref().unwrap().is::<super::EmbeddedError<UartError>>());
+}
+
+#[test]
+fn errors_pass_through_adapters() {
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> crate::Result<usize> {
+            Err(Error::new_const_with_code(ErrorKind::TimedOut, 5, &"no answer"))
+        }
+    }
+
+    let mut reader = FromEmbedded::new(ToEmbedded::new(FailingReader));
+    let error = reader.read(&mut [0; 4]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::TimedOut);
+    assert_eq!(error.code(), Some(5));
+    assert!(error.get_ref().is_none());
+}
+
+#[test]
+fn to_embedded() {
+    use embedded_io::{BufRead as _, Read as _, Seek as _, Write as _};
+
+    let mut reader = ToEmbedded::new(Cursor::new(b"hello"));
+    let mut buf = [0; 3];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hel");
+    assert_eq!(reader.fill_buf().unwrap(), b"lo");
+    reader.consume(1);
+    assert_eq!(reader.stream_position().unwrap(), 4);
+    assert_eq!(reader.seek(embedded_io::SeekFrom::End(-5)).unwrap(), 0);
+
+    let mut storage = [0; 4];
+    let mut writer = ToEmbedded::new(Cursor::new(&mut storage[..]));
+    writer.write_all(b"abc").unwrap();
+    let error = writer.write_all(b"de").unwrap_err();
+    assert_eq!(embedded_io::Error::kind(&error), embedded_io::ErrorKind::WriteZero);
+    assert_eq!(&storage, b"abcd");
+}
+
+#[test]
+fn seek_from_embedded() {
+    struct Storage {
+        pos: u64,
+    }
+
+    impl embedded_io::ErrorType for Storage {
+        type Error = embedded_io::ErrorKind;
+    }
+
+    impl embedded_io::Seek for Storage {
+        fn seek(&mut self, pos: embedded_io::SeekFrom) -> Result<u64, Self::Error> {
+            self.pos = match pos {
+                embedded_io::SeekFrom::Start(n) => n,
+                embedded_io::SeekFrom::End(n) => (100 + n) as u64,
+                embedded_io::SeekFrom::Current(n) => (self.pos as i64 + n) as u64,
+            };
+            Ok(self.pos)
+        }
+    }
+
+    let mut storage = FromEmbedded::new(Storage { pos: 0 });
+    assert_eq!(storage.seek(SeekFrom::End(-10)).unwrap(), 90);
+    assert_eq!(storage.seek(SeekFrom::Current(5)).unwrap(), 95);
+    assert_eq!(storage.stream_position().unwrap(), 95);
+    storage.rewind().unwrap();
+    assert_eq!(storage.get_ref().pos, 0);
+}
+
+#[test]
+fn error_kind_conversions() {
+    assert_eq!(
+        embedded_io::ErrorKind::from(ErrorKind::BrokenPipe),
+        embedded_io::ErrorKind::BrokenPipe
+    );
+    assert_eq!(embedded_io::ErrorKind::from(ErrorKind::WouldBlock), embedded_io::ErrorKind::Other);
+    assert_eq!(ErrorKind::from(embedded_io::ErrorKind::WriteZero), ErrorKind::WriteZero);
+    assert_eq!(ErrorKind::from(embedded_io::ErrorKind::Other), ErrorKind::Other);
+}
@@ -6,9 +6,19 @@ use core::error;
 use core::fmt;
 use core::result;
 
+#[cfg(feature = "os-error")]
+use crate::sys;
+#[cfg(feature = "os-error")]
+use crate::sys::os::{decode_error_kind, with_error_string};
+
+#[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "alloc")]
 use alloc::boxed::Box;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 /// A specialized [`Result`] type for I/O operations.
 ///
 /// <!-- TODO INCLUDE & ADAPT MORE DOC COMMENTS HERE -->
@@ -44,9 +54,15 @@ enum Repr {
     Simple(ErrorKind),
     // &str is a fat pointer, but &&str is a thin pointer.
     SimpleMessage(ErrorKind, &'static &'static str),
+    // (ADDED in this fork, for rich errors that do not need the heap)
+    SimpleMessageWithCode(ErrorKind, i32, &'static &'static str),
+    // &dyn Error is a fat pointer, but &&dyn Error is a thin pointer.
+    Static(ErrorKind, &'static &'static (dyn error::Error + Send + Sync)),
+    #[cfg(feature = "alloc")]
     Custom(Box<Custom>),
 }
 
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
 struct Custom {
     kind: ErrorKind,
@@ -341,6 +357,10 @@ impl Error {
     /// // creating an error without payload
     /// let eof_error = Error::from(ErrorKind::UnexpectedEof);
     /// ```
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
     pub fn new<E>(kind: ErrorKind, error: E) -> Error
     where
         E: Into<Box<dyn error::Error + Send + Sync>>,
@@ -366,6 +386,10 @@ impl Error {
     /// // errors can also be created from other errors
     /// let custom_error2 = Error::other(custom_error);
     /// ```
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
     pub fn other<E>(error: E) -> Error
     where
         E: Into<Box<dyn error::Error + Send + Sync>>,
@@ -373,6 +397,7 @@ impl Error {
         Self::_new(ErrorKind::Other, error.into())
     }
 
+    #[cfg(feature = "alloc")]
     fn _new(kind: ErrorKind, error: Box<dyn error::Error + Send + Sync>) -> Error {
         Error { repr: Repr::Custom(Box::new(Custom { kind, error })) }
     }
@@ -385,25 +410,113 @@ impl Error {
     /// This function should maybe change to
     /// `new_const<const MSG: &'static str>(kind: ErrorKind)`
     /// in the future, when const generics allow that.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{Error, ErrorKind};
+    ///
+    /// const NO_DEVICE: Error = Error::new_const(ErrorKind::NotFound, &"no device attached");
+    ///
+    /// assert_eq!(NO_DEVICE.kind(), ErrorKind::NotFound);
+    /// assert_eq!(NO_DEVICE.to_string(), "no device attached");
+    /// ```
     #[inline]
-    pub(crate) const fn new_const(kind: ErrorKind, message: &'static &'static str) -> Error {
+    pub const fn new_const(kind: ErrorKind, message: &'static &'static str) -> Error {
         Self { repr: Repr::SimpleMessage(kind, message) }
     }
 
-    /// <!-- (using compile_fail "code block" to show this message as a failure block) -->
-    /// ```compile_fail
-    /// NOT IMPLEMENTED - WILL PANIC WITH "MISSING FUNCTIONALITY" MESSAGE
+    /// Creates a new I/O error from a known kind of error, a numeric code and
+    /// a constant message.
+    ///
+    /// The code is not interpreted in any way. It may be used for a code from
+    /// a device or a protocol, and can be retrieved with [`Error::code`].
+    ///
+    /// This function does not allocate.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{Error, ErrorKind};
+    ///
+    /// const NAK: Error = Error::new_const_with_code(ErrorKind::Other, 0x15, &"command rejected");
+    ///
+    /// assert_eq!(NAK.kind(), ErrorKind::Other);
+    /// assert_eq!(NAK.code(), Some(0x15));
+    /// assert_eq!(NAK.to_string(), "command rejected (code 21)");
     /// ```
+    #[inline]
+    pub const fn new_const_with_code(
+        kind: ErrorKind,
+        code: i32,
+        message: &'static &'static str,
+    ) -> Error {
+        Self { repr: Repr::SimpleMessageWithCode(kind, code, message) }
+    }
+
+    /// Creates a new I/O error from a known kind of error as well as a
+    /// reference to an error payload which lives for the whole program.
+    ///
+    /// This works like [`Error::new`], but does not allocate. The payload is
+    /// available from [`Error::get_ref`].
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{Error, ErrorKind};
+    /// use core::{error, fmt};
+    ///
+    /// #[derive(Debug)]
+    /// struct ChecksumMismatch;
+    ///
+    /// impl fmt::Display for ChecksumMismatch {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         f.write_str("checksum mismatch")
+    ///     }
+    /// }
+    ///
+    /// impl error::Error for ChecksumMismatch {}
+    ///
+    /// static CHECKSUM_MISMATCH: &(dyn error::Error + Send + Sync) = &ChecksumMismatch;
+    ///
+    /// let err = Error::new_static(ErrorKind::InvalidData, &CHECKSUM_MISMATCH);
+    /// assert_eq!(err.kind(), ErrorKind::InvalidData);
+    /// assert_eq!(err.to_string(), "checksum mismatch");
+    /// assert!(err.get_ref().unwrap().is::<ChecksumMismatch>());
+    /// ```
+    #[inline]
+    pub const fn new_static(
+        kind: ErrorKind,
+        error: &'static &'static (dyn error::Error + Send + Sync),
+    ) -> Error {
+        Self { repr: Repr::Static(kind, error) }
+    }
+
+    /// Returns an error representing the last OS error which occurred.
+    ///
+    /// This function reads the value of `errno` for the target platform and will
+    /// return a corresponding instance of [`Error`] for the error code.
+    ///
+    /// This should be called immediately after a call to a platform function,
+    /// otherwise the state of the error value is indeterminate. In particular,
+    /// other functions may call platform functions that may (or may not) reset
+    /// the error value even if they succeed.
+    ///
+    /// (ADAPTED in this fork) `errno` is read via libc on Unix platforms with the
+    /// `os-error-libc` feature enabled. Otherwise the code comes from the function
+    /// registered with [`set_last_os_error_hook`](crate::set_last_os_error_hook),
+    /// or is 0 if no function was registered.
     ///
     /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
     /// # Example code
     ///
-    /// <!-- TODO FIX & REMOVE no_run here -->
-    /// ```no_run
+    /// ```
+    /// # #[cfg(feature = "os-error")] {
     /// use portable_io::Error;
     ///
     /// let os_error = Error::last_os_error();
     /// println!("last OS error: {:?}", os_error);
+    /// # }
     /// ```
     ///
     /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
@@ -412,13 +525,31 @@ impl Error {
     #[must_use]
     #[inline]
     pub fn last_os_error() -> Error {
-        // TODO ADD MISSING FUNCTIONALITY
-        panic!("MISSING FUNCTIONALITY")
+        Error::from_raw_os_error(sys::os::errno())
     }
 
     /// Creates a new instance of an [`Error`] from a particular OS error code.
     ///
-    /// <!-- TODO ADD EXAMPLE CODE -->
+    /// The code is decoded with a built-in table that uses the Linux (& POSIX)
+    /// numbering of error codes, for [`kind`] and for the message shown by
+    /// `Display` & `Debug`. With the `os-error-libc` feature on Unix platforms,
+    /// [`kind`] is decoded with the error constants from libc instead, and with
+    /// the `os-error-strerror` feature the message comes from `strerror_r`.
+    ///
+    /// [`kind`]: Error::kind
+    ///
+    /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
+    /// # Example code
+    ///
+    /// ```
+    /// # #[cfg(feature = "os-error")] {
+    /// use portable_io::{Error, ErrorKind};
+    ///
+    /// let error = Error::from_raw_os_error(22);
+    /// assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    /// assert_eq!(error.to_string(), "Invalid argument (os error 22)");
+    /// # }
+    /// ```
     ///
     /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
     /// <div class="warning">REQUIRES feature to be enabled: <code>os-error</code></div>
@@ -466,18 +597,51 @@ impl Error {
         match self.repr {
             #[cfg(feature = "os-error")]
             Repr::Os(i) => Some(i),
+            #[cfg(feature = "alloc")]
             Repr::Custom(..) => None,
             Repr::Simple(..) => None,
             Repr::SimpleMessage(..) => None,
+            Repr::SimpleMessageWithCode(..) => None,
+            Repr::Static(..) => None,
+        }
+    }
+
+    /// Returns the numeric code that this error carries (if any).
+    ///
+    /// If this [`Error`] was constructed via [`new_const_with_code`], then
+    /// this function will return [`Some`], otherwise it will return [`None`].
+    /// Codes from the OS are returned by [`raw_os_error`] instead.
+    ///
+    /// [`new_const_with_code`]: Error::new_const_with_code
+    /// [`raw_os_error`]: Error::raw_os_error
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{Error, ErrorKind};
+    ///
+    /// let err = Error::new_const_with_code(ErrorKind::TimedOut, 7, &"no response");
+    /// assert_eq!(err.code(), Some(7));
+    ///
+    /// let err = Error::new_const(ErrorKind::TimedOut, &"no response");
+    /// assert_eq!(err.code(), None);
+    /// ```
+    #[must_use]
+    #[inline]
+    pub fn code(&self) -> Option<i32> {
+        match self.repr {
+            Repr::SimpleMessageWithCode(_, code, _) => Some(code),
+            _ => None,
         }
     }
 
     /// Returns a reference to the inner error wrapped by this error (if any).
     ///
-    /// If this [`Error`] was constructed via [`new`] then this function will
-    /// return [`Some`], otherwise it will return [`None`].
+    /// If this [`Error`] was constructed via [`new`] or [`new_static`] then
+    /// this function will return [`Some`], otherwise it will return [`None`].
     ///
     /// [`new`]: Error::new
+    /// [`new_static`]: Error::new_static
     ///
     /// <!-- UPDATED TITLE in this fork to avoid singular vs plural issue - TODO PROPOSE UPDATE IN UPSTREAM RUST -->
     /// # Example code
@@ -509,6 +673,9 @@ impl Error {
             Repr::Os(..) => None,
             Repr::Simple(..) => None,
             Repr::SimpleMessage(..) => None,
+            Repr::SimpleMessageWithCode(..) => None,
+            Repr::Static(_, &error) => Some(error),
+            #[cfg(feature = "alloc")]
             Repr::Custom(ref c) => Some(&*c.error),
         }
     }
@@ -585,6 +752,9 @@ impl Error {
             Repr::Os(..) => None,
             Repr::Simple(..) => None,
             Repr::SimpleMessage(..) => None,
+            Repr::SimpleMessageWithCode(..) => None,
+            Repr::Static(..) => None,
+            #[cfg(feature = "alloc")]
             Repr::Custom(ref mut c) => Some(&mut *c.error),
         }
     }
@@ -618,6 +788,10 @@ impl Error {
     ///     print_error(Error::new(ErrorKind::Other, "oh no!"));
     /// }
     /// ```
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
     #[must_use = "`self` will be dropped if the result is not used"]
     #[inline]
     pub fn into_inner(self) -> Option<Box<dyn error::Error + Send + Sync>> {
@@ -626,6 +800,9 @@ impl Error {
             Repr::Os(..) => None,
             Repr::Simple(..) => None,
             Repr::SimpleMessage(..) => None,
+            Repr::SimpleMessageWithCode(..) => None,
+            Repr::Static(..) => None,
+            #[cfg(feature = "alloc")]
             Repr::Custom(c) => Some(c.error),
         }
     }
@@ -644,9 +821,9 @@ impl Error {
     /// }
     ///
     /// fn main() {
-    ///     // Will panic (MISSING FUNCTIONALITY) - SHOULD print "Uncategorized".
+    ///     // Will print "InvalidInput".
     ///     // (only compiles with `os-error` feature enabled)
-    ///     // print_error(Error::last_os_error());
+    ///     // print_error(Error::from_raw_os_error(22));
     ///     // Will print "AddrInUse".
     ///     print_error(Error::new(ErrorKind::AddrInUse, "oh no!"));
     /// }
@@ -655,27 +832,82 @@ impl Error {
     #[inline]
     pub fn kind(&self) -> ErrorKind {
         match self.repr {
-            // TODO ADD MISSING FUNCTIONALITY
             #[cfg(feature = "os-error")]
-            Repr::Os(_) => panic!("MISSING FUNCTIONALITY"),
+            Repr::Os(code) => decode_error_kind(code),
+            #[cfg(feature = "alloc")]
             Repr::Custom(ref c) => c.kind,
             Repr::Simple(kind) => kind,
             Repr::SimpleMessage(kind, _) => kind,
+            Repr::SimpleMessageWithCode(kind, _, _) => kind,
+            Repr::Static(kind, _) => kind,
         }
     }
 }
 
+/// Registers the function that [`Error::last_os_error`] calls to get the code
+/// of the last OS error.
+///
+/// (ADDED in this fork) This is for bare-metal & other platforms where `errno`
+/// is not read via libc, so that the platform can supply its own source of the
+/// last error. The function should return 0 if there was no error. Registering
+/// another function replaces the previous one.
+///
+/// With the `os-error-libc` feature on Unix platforms, `errno` is always read
+/// via libc and the registered function is never called.
+///
+/// # Example code
+///
+/// ```
+/// # #[cfg(feature = "os-error")] {
+/// use portable_io::{self as io, Error};
+///
+/// fn last_error() -> i32 {
+///     // e.g. read a status register, or a value kept by the platform
+///     22
+/// }
+///
+/// io::set_last_os_error_hook(last_error);
+///
+/// let error = Error::last_os_error();
+/// # #[cfg(not(all(unix, feature = "os-error-libc")))]
+/// assert_eq!(error.raw_os_error(), Some(22));
+/// # }
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>os-error</code></div>
+#[cfg(feature = "os-error")]
+pub fn set_last_os_error_hook(hook: fn() -> i32) {
+    sys::os::set_last_os_error_hook(hook)
+}
+
 impl fmt::Debug for Repr {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
-            // TODO ADD MISSING FUNCTIONALITY
             #[cfg(feature = "os-error")]
-            Repr::Os(_) => panic!("MISSING FUNCTIONALITY"),
+            Repr::Os(code) => with_error_string(code, |message| {
+                fmt.debug_struct("Os")
+                    .field("code", &code)
+                    .field("kind", &decode_error_kind(code))
+                    .field("message", &message)
+                    .finish()
+            }),
+            #[cfg(feature = "alloc")]
             Repr::Custom(ref c) => fmt::Debug::fmt(&c, fmt),
             Repr::Simple(kind) => fmt.debug_tuple("Kind").field(&kind).finish(),
             Repr::SimpleMessage(kind, &message) => {
                 fmt.debug_struct("Error").field("kind", &kind).field("message", &message).finish()
             }
+            Repr::SimpleMessageWithCode(kind, code, &message) => fmt
+                .debug_struct("Error")
+                .field("kind", &kind)
+                .field("code", &code)
+                .field("message", &message)
+                .finish(),
+            // (shown in the same way as an error from `Error::new`)
+            Repr::Static(kind, &error) => {
+                fmt.debug_struct("Custom").field("kind", &kind).field("error", &error).finish()
+            }
         }
     }
 }
@@ -685,14 +917,14 @@ impl fmt::Display for Error {
         match self.repr {
             #[cfg(feature = "os-error")]
             Repr::Os(code) => {
-                // TODO ADD MISSING FUNCTIONALITY
-                // (ignore unused argument for now)
-                _ = code;
-                panic!("MISSING FUNCTIONALITY")
+                with_error_string(code, |detail| write!(fmt, "{} (os error {})", detail, code))
             }
+            #[cfg(feature = "alloc")]
             Repr::Custom(ref c) => c.error.fmt(fmt),
             Repr::Simple(kind) => write!(fmt, "{}", kind.as_str()),
             Repr::SimpleMessage(_, &msg) => msg.fmt(fmt),
+            Repr::SimpleMessageWithCode(_, code, &msg) => write!(fmt, "{msg} (code {code})"),
+            Repr::Static(_, &error) => fmt::Display::fmt(error, fmt),
         }
     }
 }
@@ -705,6 +937,9 @@ impl error::Error for Error {
             Repr::Os(..) => self.kind().as_str(),
             Repr::Simple(..) => self.kind().as_str(),
             Repr::SimpleMessage(_, &msg) => msg,
+            Repr::SimpleMessageWithCode(_, _, &msg) => msg,
+            Repr::Static(_, &error) => error.description(),
+            #[cfg(feature = "alloc")]
             Repr::Custom(ref c) => c.error.description(),
         }
     }
@@ -716,6 +951,9 @@ impl error::Error for Error {
             Repr::Os(..) => None,
             Repr::Simple(..) => None,
             Repr::SimpleMessage(..) => None,
+            Repr::SimpleMessageWithCode(..) => None,
+            Repr::Static(_, &error) => error.cause(),
+            #[cfg(feature = "alloc")]
             Repr::Custom(ref c) => c.error.cause(),
         }
     }
@@ -726,11 +964,160 @@ impl error::Error for Error {
             Repr::Os(..) => None,
             Repr::Simple(..) => None,
             Repr::SimpleMessage(..) => None,
+            Repr::SimpleMessageWithCode(..) => None,
+            Repr::Static(_, &error) => error.source(),
+            #[cfg(feature = "alloc")]
             Repr::Custom(ref c) => c.error.source(),
         }
     }
 }
 
+// (ADDED in this fork) conversions to & from `std::io`
+
+// (kinds that are not stable in `std::io` as of the MSRV are converted to `Other`)
+#[cfg(feature = "std")]
+impl From<ErrorKind> for std::io::ErrorKind {
+    /// Converts an [`ErrorKind`] into a [`std::io::ErrorKind`].
+    fn from(kind: ErrorKind) -> std::io::ErrorKind {
+        use std::io::ErrorKind as StdKind;
+        match kind {
+            ErrorKind::NotFound => StdKind::NotFound,
+            ErrorKind::PermissionDenied => StdKind::PermissionDenied,
+            ErrorKind::ConnectionRefused => StdKind::ConnectionRefused,
+            ErrorKind::ConnectionReset => StdKind::ConnectionReset,
+            ErrorKind::ConnectionAborted => StdKind::ConnectionAborted,
+            ErrorKind::NotConnected => StdKind::NotConnected,
+            ErrorKind::AddrInUse => StdKind::AddrInUse,
+            ErrorKind::AddrNotAvailable => StdKind::AddrNotAvailable,
+            ErrorKind::BrokenPipe => StdKind::BrokenPipe,
+            ErrorKind::AlreadyExists => StdKind::AlreadyExists,
+            ErrorKind::WouldBlock => StdKind::WouldBlock,
+            ErrorKind::InvalidInput => StdKind::InvalidInput,
+            ErrorKind::InvalidData => StdKind::InvalidData,
+            ErrorKind::TimedOut => StdKind::TimedOut,
+            ErrorKind::WriteZero => StdKind::WriteZero,
+            ErrorKind::Interrupted => StdKind::Interrupted,
+            ErrorKind::Unsupported => StdKind::Unsupported,
+            ErrorKind::UnexpectedEof => StdKind::UnexpectedEof,
+            ErrorKind::OutOfMemory => StdKind::OutOfMemory,
+            _ => StdKind::Other,
+        }
+    }
+}
+
+// (kinds that this crate does not know of are converted to `Uncategorized`)
+#[cfg(feature = "std")]
+impl From<std::io::ErrorKind> for ErrorKind {
+    /// Converts a [`std::io::ErrorKind`] into an [`ErrorKind`].
+    fn from(kind: std::io::ErrorKind) -> ErrorKind {
+        use std::io::ErrorKind as StdKind;
+        match kind {
+            StdKind::NotFound => ErrorKind::NotFound,
+            StdKind::PermissionDenied => ErrorKind::PermissionDenied,
+            StdKind::ConnectionRefused => ErrorKind::ConnectionRefused,
+            StdKind::ConnectionReset => ErrorKind::ConnectionReset,
+            StdKind::ConnectionAborted => ErrorKind::ConnectionAborted,
+            StdKind::NotConnected => ErrorKind::NotConnected,
+            StdKind::AddrInUse => ErrorKind::AddrInUse,
+            StdKind::AddrNotAvailable => ErrorKind::AddrNotAvailable,
+            StdKind::BrokenPipe => ErrorKind::BrokenPipe,
+            StdKind::AlreadyExists => ErrorKind::AlreadyExists,
+            StdKind::WouldBlock => ErrorKind::WouldBlock,
+            StdKind::InvalidInput => ErrorKind::InvalidInput,
+            StdKind::InvalidData => ErrorKind::InvalidData,
+            StdKind::TimedOut => ErrorKind::TimedOut,
+            StdKind::WriteZero => ErrorKind::WriteZero,
+            StdKind::Interrupted => ErrorKind::Interrupted,
+            StdKind::Unsupported => ErrorKind::Unsupported,
+            StdKind::UnexpectedEof => ErrorKind::UnexpectedEof,
+            StdKind::OutOfMemory => ErrorKind::OutOfMemory,
+            StdKind::Other => ErrorKind::Other,
+            _ => ErrorKind::Uncategorized,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    /// Converts a [`std::io::Error`] into an [`Error`].
+    ///
+    /// On Unix platforms with the `os-error` feature enabled, a raw OS error
+    /// keeps its code. The payload of a custom error is moved over as is, and
+    /// any other error is kept as the payload, with the same kind. An error
+    /// that was converted from an [`Error`] is unwrapped.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// use portable_io::{Error, ErrorKind};
+    ///
+    /// let std_error = std::io::Error::new(std::io::ErrorKind::InvalidData, "oh no!");
+    /// let error = Error::from(std_error);
+    /// assert_eq!(error.kind(), ErrorKind::InvalidData);
+    /// assert_eq!(error.to_string(), "oh no!");
+    /// # }
+    /// ```
+    fn from(error: std::io::Error) -> Error {
+        #[cfg(all(unix, feature = "os-error"))]
+        if let Some(code) = error.raw_os_error() {
+            return Error::from_raw_os_error(code);
+        }
+
+        let kind = ErrorKind::from(error.kind());
+        if error.get_ref().is_none() {
+            return Error::_new(kind, Box::new(error));
+        }
+        match error.into_inner().unwrap().downcast::<Error>() {
+            Ok(error) => *error,
+            Err(payload) => Error::_new(kind, payload),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    /// Converts an [`Error`] into a [`std::io::Error`].
+    ///
+    /// On Unix platforms, a raw OS error keeps its code. An error that only
+    /// has a kind is converted to an error of the same kind, the payload of a
+    /// custom error is moved over as is, and any other error is kept as the
+    /// payload. An error that was converted from a [`std::io::Error`] is unwrapped.
+    /// An error of a kind that [`std::io::ErrorKind`] does not have is kept as the
+    /// payload too, so that converting it back gives the same kind.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// use portable_io::{Error, ErrorKind};
+    ///
+    /// let error = Error::new_const(ErrorKind::InvalidData, &"oh no!");
+    /// let std_error = std::io::Error::from(error);
+    /// assert_eq!(std_error.kind(), std::io::ErrorKind::InvalidData);
+    /// assert_eq!(std_error.to_string(), "oh no!");
+    /// # }
+    /// ```
+    fn from(error: Error) -> std::io::Error {
+        let kind = std::io::ErrorKind::from(error.kind());
+        // (a kind that `std::io` does not have is kept by wrapping the whole
+        // error, which the reverse conversion unwraps)
+        let lossy = kind == std::io::ErrorKind::Other && error.kind() != ErrorKind::Other;
+        match error.repr {
+            #[cfg(all(unix, feature = "os-error"))]
+            Repr::Os(code) => std::io::Error::from_raw_os_error(code),
+            Repr::Simple(_) if !lossy => std::io::Error::from(kind),
+            Repr::Custom(c) if !lossy || c.error.is::<std::io::Error>() => {
+                match c.error.downcast::<std::io::Error>() {
+                    Ok(error) => *error,
+                    Err(payload) => std::io::Error::new(kind, payload),
+                }
+            }
+            _ => std::io::Error::new(kind, error),
+        }
+    }
+}
+
 fn _assert_error_is_sync_send() {
     fn _is_sync_send<T: Sync + Send>() {}
     _is_sync_send::<Error>();
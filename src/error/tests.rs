@@ -7,14 +7,132 @@ use alloc::format;
 use alloc::string::ToString;
 
 use super::{Error, ErrorKind};
+#[cfg(all(target_os = "linux", target_env = "gnu", feature = "os-error"))]
+use crate::sys::errno::error_string;
+#[cfg(all(feature = "alloc", feature = "os-error"))]
+use crate::sys::os::{decode_error_kind, with_error_string};
 
 #[test]
 fn test_size() {
     assert!(size_of::<Error>() <= size_of::<[usize; 2]>());
 }
 
-// TODO ADD & TEST MISSING FUNCTIONALITY: DEBUG ERROR - OS ERROR
+#[cfg(all(feature = "alloc", feature = "os-error"))]
+#[test]
+fn test_debug_error() {
+    let code = 6;
+    let msg = with_error_string(code, |msg| msg.to_string());
+    let kind = decode_error_kind(code);
+    let err = Error::new(ErrorKind::InvalidInput, Error::from_raw_os_error(code));
+    let expected = format!(
+        "Custom {{ \
+         kind: InvalidInput, \
+         error: Os {{ \
+         code: {:?}, \
+         kind: {:?}, \
+         message: {:?} \
+         }} \
+         }}",
+        code, kind, msg
+    );
+    assert_eq!(format!("{:?}", err), expected);
+}
+
+#[cfg(feature = "os-error")]
+#[test]
+fn test_os_error() {
+    let err = Error::from_raw_os_error(2);
+    assert_eq!(err.raw_os_error(), Some(2));
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(err.get_ref().is_none());
+
+    let err = Error::from_raw_os_error(9999);
+    assert_eq!(err.kind(), ErrorKind::Uncategorized);
+}
+
+// (messages from `strerror_r` are different on some platforms)
+#[cfg(all(feature = "os-error", not(feature = "os-error-strerror")))]
+#[test]
+fn test_os_error_message() {
+    let err = Error::from_raw_os_error(2);
+    assert_eq!(err.to_string(), "No such file or directory (os error 2)");
+    assert_eq!(
+        format!("{:?}", err),
+        r#"Os { code: 2, kind: NotFound, message: "No such file or directory" }"#
+    );
+
+    let err = Error::from_raw_os_error(9999);
+    assert_eq!(err.to_string(), "Unknown error (os error 9999)");
+}
+
+#[cfg(all(unix, feature = "os-error-strerror"))]
+#[test]
+fn test_os_error_message_from_strerror() {
+    let expected = unsafe { core::ffi::CStr::from_ptr(libc::strerror(libc::ENOENT)) };
+    let expected = expected.to_str().unwrap();
+    let err = Error::from_raw_os_error(libc::ENOENT);
+    assert_eq!(err.to_string(), format!("{} (os error {})", expected, libc::ENOENT));
+}
 
+#[cfg(all(unix, feature = "os-error-libc"))]
+#[test]
+fn test_last_os_error_from_errno() {
+    // close an fd that is not open, which sets errno to EBADF
+    let ret = unsafe { libc::close(-1) };
+    assert_eq!(ret, -1);
+
+    let err = Error::last_os_error();
+    assert_eq!(err.raw_os_error(), Some(libc::EBADF));
+    assert_eq!(err.kind(), ErrorKind::Uncategorized);
+}
+
+#[cfg(all(feature = "os-error", not(all(unix, feature = "os-error-libc"))))]
+#[test]
+fn test_last_os_error_from_hook() {
+    crate::set_last_os_error_hook(|| 32);
+
+    let err = Error::last_os_error();
+    assert_eq!(err.raw_os_error(), Some(32));
+    assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+}
+
+#[cfg(feature = "os-error")]
+#[test]
+fn test_decode_error_kind() {
+    use crate::sys::errno::*;
+    // (the built-in table, whether or not libc is used)
+
+    assert_eq!(decode_error_kind(EPERM), ErrorKind::PermissionDenied);
+    assert_eq!(decode_error_kind(EACCES), ErrorKind::PermissionDenied);
+    assert_eq!(decode_error_kind(EAGAIN), ErrorKind::WouldBlock);
+    assert_eq!(decode_error_kind(EINTR), ErrorKind::Interrupted);
+    assert_eq!(decode_error_kind(EPIPE), ErrorKind::BrokenPipe);
+    assert_eq!(decode_error_kind(ECONNRESET), ErrorKind::ConnectionReset);
+    assert_eq!(decode_error_kind(ENOSPC), ErrorKind::StorageFull);
+    assert_eq!(decode_error_kind(EIO), ErrorKind::Uncategorized);
+    assert_eq!(decode_error_kind(0), ErrorKind::Uncategorized);
+    assert_eq!(decode_error_kind(-1), ErrorKind::Uncategorized);
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu", feature = "os-error"))]
+#[test]
+fn test_errno_table_matches_libc() {
+    // every message in the built-in table should be the same as from the GNU C Library
+    extern "C" {
+        fn strerror(errnum: i32) -> *const core::ffi::c_char;
+    }
+
+    for code in 1..=133 {
+        let msg = error_string(code);
+        if msg == "Unknown error" {
+            continue;
+        }
+        let expected = unsafe { core::ffi::CStr::from_ptr(strerror(code)) };
+        assert_eq!(msg, expected.to_str().unwrap(), "errno {}", code);
+    }
+}
+
+#[cfg(feature = "alloc")]
 #[test]
 fn test_downcasting() {
     #[derive(Debug)]
@@ -47,3 +165,53 @@ fn test_const() {
     assert!(format!("{:?}", E).contains("\"hello\""));
     assert!(format!("{:?}", E).contains("NotFound"));
 }
+
+#[test]
+fn test_const_with_code() {
+    const E: Error = Error::new_const_with_code(ErrorKind::TimedOut, -3, &"no response");
+
+    assert_eq!(E.kind(), ErrorKind::TimedOut);
+    assert_eq!(E.code(), Some(-3));
+    assert!(E.get_ref().is_none());
+    assert_eq!(E.to_string(), "no response (code -3)");
+    assert_eq!(format!("{:?}", E), r#"Error { kind: TimedOut, code: -3, message: "no response" }"#);
+
+    assert_eq!(Error::new_const(ErrorKind::TimedOut, &"no response").code(), None);
+    assert_eq!(Error::from(ErrorKind::TimedOut).code(), None);
+}
+
+#[derive(Debug)]
+struct StaticError;
+
+impl fmt::Display for StaticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("static error")
+    }
+}
+
+impl error::Error for StaticError {}
+
+static STATIC_ERROR: &(dyn error::Error + Send + Sync) = &StaticError;
+
+#[test]
+fn test_static() {
+    let mut err = Error::new_static(ErrorKind::InvalidData, &STATIC_ERROR);
+
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.code(), None);
+    assert_eq!(err.raw_os_error(), None);
+    assert!(err.get_ref().unwrap().is::<StaticError>());
+    assert!(err.get_mut().is_none());
+    assert_eq!(err.to_string(), "static error");
+    assert_eq!(format!("{:?}", err), "Custom { kind: InvalidData, error: StaticError }");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_static_debug_matches_custom() {
+    let err = Error::new_static(ErrorKind::InvalidData, &STATIC_ERROR);
+    let custom = Error::new(ErrorKind::InvalidData, StaticError);
+
+    assert_eq!(format!("{:?}", err), format!("{:?}", custom));
+    assert!(err.into_inner().is_none());
+}
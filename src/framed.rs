@@ -0,0 +1,469 @@
+// (ADDED in this fork) readers & writers of length-prefixed frames, as used for
+// messages on serial links & other byte streams
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::varint::{MAX_LEB128_LEN, prefix_varint_len};
+use crate::{
+    Error, ErrorKind, IoSlice, Read, ReadBytesExt, ReadVarintExt, Result, Write, WriteBytesExt,
+    WriteVarintExt,
+};
+
+// The maximum length of the header of a frame (for unsigned LEB128)
+const MAX_HEADER_LEN: usize = MAX_LEB128_LEN;
+
+/// The encoding of the length that comes before the payload of each frame,
+/// used by [`FramedReader`] & [`FramedWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LengthPrefix {
+    /// One byte, for frames of up to 255 bytes.
+    U8,
+    /// Two bytes, little-endian.
+    U16Le,
+    /// Two bytes, big-endian.
+    U16Be,
+    /// Four bytes, little-endian.
+    U32Le,
+    /// Four bytes, big-endian.
+    U32Be,
+    /// Unsigned LEB128, as written by [`WriteVarintExt::write_uleb128_u64`].
+    Uleb128,
+    /// Prefix varint, as written by [`WriteVarintExt::write_prefix_varint_u64`].
+    PrefixVarint,
+}
+
+impl LengthPrefix {
+    /// Returns the largest frame length that this prefix can encode, saturated
+    /// to `usize::MAX`.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::LengthPrefix;
+    ///
+    /// assert_eq!(LengthPrefix::U8.max_frame_len(), 255);
+    /// assert_eq!(LengthPrefix::U16Be.max_frame_len(), 65535);
+    /// ```
+    pub fn max_frame_len(self) -> usize {
+        let max = match self {
+            LengthPrefix::U8 => u64::from(u8::MAX),
+            LengthPrefix::U16Le | LengthPrefix::U16Be => u64::from(u16::MAX),
+            LengthPrefix::U32Le | LengthPrefix::U32Be => u64::from(u32::MAX),
+            LengthPrefix::Uleb128 | LengthPrefix::PrefixVarint => u64::MAX,
+        };
+        usize::try_from(max).unwrap_or(usize::MAX)
+    }
+
+    // Returns the length of the header, as far as it is known from the bytes
+    // read so far; the header is complete once this is `header.len()`
+    fn header_len(self, header: &[u8]) -> usize {
+        match self {
+            LengthPrefix::U8 => 1,
+            LengthPrefix::U16Le | LengthPrefix::U16Be => 2,
+            LengthPrefix::U32Le | LengthPrefix::U32Be => 4,
+            LengthPrefix::Uleb128 => match header.last() {
+                // (a header that is too long is left for `decode` to reject)
+                Some(byte) if byte & 0x80 != 0 && header.len() < MAX_HEADER_LEN => header.len() + 1,
+                Some(_) => header.len(),
+                None => 1,
+            },
+            LengthPrefix::PrefixVarint => {
+                header.first().map_or(1, |&first| prefix_varint_len(first))
+            }
+        }
+    }
+
+    fn decode(self, mut header: &[u8]) -> Result<u64> {
+        match self {
+            LengthPrefix::U8 => header.read_u8().map(u64::from),
+            LengthPrefix::U16Le => header.read_u16_le().map(u64::from),
+            LengthPrefix::U16Be => header.read_u16_be().map(u64::from),
+            LengthPrefix::U32Le => header.read_u32_le().map(u64::from),
+            LengthPrefix::U32Be => header.read_u32_be().map(u64::from),
+            LengthPrefix::Uleb128 => header.read_uleb128_u64(),
+            LengthPrefix::PrefixVarint => header.read_prefix_varint_u64(),
+        }
+    }
+
+    // Returns the number of bytes of the header written to `header`
+    fn encode(self, len: usize, header: &mut [u8; MAX_HEADER_LEN]) -> usize {
+        let mut writer = &mut header[..];
+        let len = len as u64;
+        // (writing to the array cannot fail, & `len` has been checked to fit)
+        let _ = match self {
+            LengthPrefix::U8 => writer.write_u8(len as u8),
+            LengthPrefix::U16Le => writer.write_u16_le(len as u16),
+            LengthPrefix::U16Be => writer.write_u16_be(len as u16),
+            LengthPrefix::U32Le => writer.write_u32_le(len as u32),
+            LengthPrefix::U32Be => writer.write_u32_be(len as u32),
+            LengthPrefix::Uleb128 => writer.write_uleb128_u64(len),
+            LengthPrefix::PrefixVarint => writer.write_prefix_varint_u64(len),
+        };
+        MAX_HEADER_LEN - writer.len()
+    }
+}
+
+/// Reads length-prefixed frames from a reader.
+///
+/// Each frame is a header with the length of the payload, encoded as given
+/// by a [`LengthPrefix`], followed by the payload itself. A frame with a
+/// length greater than the maximum frame length results in an error of the
+/// kind [`ErrorKind::InvalidData`], without reading the payload, so that a
+/// corrupted header cannot cause an unbounded read or allocation.
+///
+/// If the underlying reader returns an error of the kind
+/// [`ErrorKind::WouldBlock`] in the middle of a frame, the error is returned
+/// & the progress is kept, so that the next call to [`read_frame`] or
+/// [`read_frame_to_vec`] resumes the same frame. For this to work, the next
+/// call must be given the same buffer, with the part of the frame that was
+/// already read left as it was. Errors of the kind [`ErrorKind::Interrupted`]
+/// are retried.
+///
+/// [`read_frame`]: FramedReader::read_frame
+/// [`read_frame_to_vec`]: FramedReader::read_frame_to_vec
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{FramedReader, LengthPrefix};
+///
+/// let data: &[u8] = &[0, 5, b'h', b'e', b'l', b'l', b'o', 0, 0];
+/// let mut reader = FramedReader::new(LengthPrefix::U16Be, data);
+///
+/// let mut buf = [0; 16];
+/// assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(5));
+/// assert_eq!(&buf[..5], b"hello");
+/// assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(0));
+/// assert_eq!(reader.read_frame(&mut buf).unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct FramedReader<R> {
+    inner: R,
+    prefix: LengthPrefix,
+    max_frame_len: usize,
+    header: [u8; MAX_HEADER_LEN],
+    header_filled: usize,
+    // (the length of the current frame, once its header has been read)
+    frame_len: Option<usize>,
+    // (the number of bytes of the payload of the current frame read so far)
+    payload_filled: usize,
+}
+
+impl<R: Read> FramedReader<R> {
+    /// Creates a new `FramedReader<R>`, with a maximum frame length of the
+    /// largest length that `prefix` can encode.
+    pub fn new(prefix: LengthPrefix, inner: R) -> FramedReader<R> {
+        FramedReader::with_max_frame_len(prefix, prefix.max_frame_len(), inner)
+    }
+
+    /// Creates a new `FramedReader<R>` with the specified maximum frame length.
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{ErrorKind, FramedReader, LengthPrefix};
+    ///
+    /// let data: &[u8] = &[200, 0];
+    /// let mut reader = FramedReader::with_max_frame_len(LengthPrefix::Uleb128, 64, data);
+    ///
+    /// let error = reader.read_frame(&mut [0; 64]).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidData);
+    /// ```
+    pub fn with_max_frame_len(
+        prefix: LengthPrefix,
+        max_frame_len: usize,
+        inner: R,
+    ) -> FramedReader<R> {
+        FramedReader {
+            inner,
+            prefix,
+            max_frame_len,
+            header: [0; MAX_HEADER_LEN],
+            header_filled: 0,
+            frame_len: None,
+            payload_filled: 0,
+        }
+    }
+
+    /// Reads the next frame into `buf`, returning the length of its payload,
+    /// or `None` if the underlying reader is at EOF at the start of a frame.
+    ///
+    /// # Errors
+    ///
+    /// If the frame is longer than `buf`, an error of the kind
+    /// [`ErrorKind::InvalidInput`] is returned & the payload is left unread, so
+    /// that the frame can be read by calling this again with a larger buffer.
+    ///
+    /// If the underlying reader reaches EOF in the middle of a frame, an error
+    /// of the kind [`ErrorKind::UnexpectedEof`] is returned.
+    pub fn read_frame(&mut self, buf: &mut [u8]) -> Result<Option<usize>> {
+        let len = match self.read_header()? {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+        if len > buf.len() {
+            return Err(Error::new_const(
+                ErrorKind::InvalidInput,
+                &"frame is larger than the buffer",
+            ));
+        }
+        self.read_payload(&mut buf[..len])?;
+        Ok(Some(len))
+    }
+
+    /// Reads the next frame, appending its payload to `buf`, & returns the
+    /// length of the payload, or `None` if the underlying reader is at EOF at
+    /// the start of a frame.
+    ///
+    /// If an error is returned in the middle of the payload, the part of the
+    /// payload that was read is left appended to `buf`.
+    ///
+    /// # Errors
+    ///
+    /// If the underlying reader reaches EOF in the middle of a frame, an error
+    /// of the kind [`ErrorKind::UnexpectedEof`] is returned.
+    ///
+    /// If a call is resumed with a vector that is shorter than the part of the
+    /// payload that a previous call left appended, an error of the kind
+    /// [`ErrorKind::InvalidInput`] is returned, & the frame can still be
+    /// resumed with the right vector.
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
+    pub fn read_frame_to_vec(&mut self, buf: &mut Vec<u8>) -> Result<Option<usize>> {
+        let len = match self.read_header()? {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+        // (the part of the payload read by a previous call is at the end of `buf`)
+        let start = buf.len().checked_sub(self.payload_filled).ok_or_else(|| {
+            Error::new_const(
+                ErrorKind::InvalidInput,
+                &"buffer does not hold the part of the frame that was read",
+            )
+        })?;
+        buf.resize(start + len, 0);
+        if let Err(e) = self.read_payload(&mut buf[start..]) {
+            buf.truncate(start + self.payload_filled);
+            return Err(e);
+        }
+        Ok(Some(len))
+    }
+
+    // Reads the header of the current frame, unless it has already been read
+    fn read_header(&mut self) -> Result<Option<usize>> {
+        if let Some(len) = self.frame_len {
+            return Ok(Some(len));
+        }
+        loop {
+            let header_len = self.prefix.header_len(&self.header[..self.header_filled]);
+            if self.header_filled == header_len {
+                break;
+            }
+            match self.inner.read(&mut self.header[self.header_filled..header_len]) {
+                Ok(0) if self.header_filled == 0 => return Ok(None),
+                Ok(0) => {
+                    return Err(Error::new_const(
+                        ErrorKind::UnexpectedEof,
+                        &"failed to fill whole buffer",
+                    ));
+                }
+                Ok(n) => self.header_filled += n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        let header_len = core::mem::replace(&mut self.header_filled, 0);
+        let len = self.prefix.decode(&self.header[..header_len])?;
+        match usize::try_from(len) {
+            Ok(len) if len <= self.max_frame_len => {
+                self.frame_len = Some(len);
+                Ok(Some(len))
+            }
+            _ => Err(Error::new_const(ErrorKind::InvalidData, &"frame is too large")),
+        }
+    }
+
+    // Reads the rest of the payload of the current frame into `payload`
+    fn read_payload(&mut self, payload: &mut [u8]) -> Result<()> {
+        while self.payload_filled < payload.len() {
+            match self.inner.read(&mut payload[self.payload_filled..]) {
+                Ok(0) => {
+                    return Err(Error::new_const(
+                        ErrorKind::UnexpectedEof,
+                        &"failed to fill whole buffer",
+                    ));
+                }
+                Ok(n) => self.payload_filled += n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.frame_len = None;
+        self.payload_filled = 0;
+        Ok(())
+    }
+}
+
+impl<R> FramedReader<R> {
+    /// Returns the maximum frame length.
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `FramedReader<R>`, returning the underlying reader.
+    ///
+    /// Note that the part of a frame that was read by a call that returned an
+    /// error is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Writes length-prefixed frames to a writer.
+///
+/// The header & the payload of each frame are written together with
+/// [`write_vectored`](Write::write_vectored), so that they go out in one call
+/// if the underlying writer supports it.
+///
+/// If the underlying writer returns an error of the kind
+/// [`ErrorKind::WouldBlock`] in the middle of a frame, the error is returned
+/// & the progress is kept, so that the next call to [`write_frame`] resumes
+/// the same frame. For this to work, the next call must be given the same
+/// payload. Errors of the kind [`ErrorKind::Interrupted`] are retried.
+///
+/// [`write_frame`]: FramedWriter::write_frame
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{FramedWriter, LengthPrefix};
+///
+/// let mut storage = [0; 16];
+/// let mut writer = FramedWriter::new(LengthPrefix::U16Le, &mut storage[..]);
+/// writer.write_frame(b"hello").unwrap();
+/// writer.write_frame(b"").unwrap();
+///
+/// assert_eq!(storage[..9], [5, 0, b'h', b'e', b'l', b'l', b'o', 0, 0]);
+/// ```
+#[derive(Debug)]
+pub struct FramedWriter<W> {
+    inner: W,
+    prefix: LengthPrefix,
+    max_frame_len: usize,
+    // (the number of bytes of the current frame, including its header, written so far)
+    written: usize,
+}
+
+impl<W: Write> FramedWriter<W> {
+    /// Creates a new `FramedWriter<W>`, with a maximum frame length of the
+    /// largest length that `prefix` can encode.
+    pub fn new(prefix: LengthPrefix, inner: W) -> FramedWriter<W> {
+        FramedWriter::with_max_frame_len(prefix, prefix.max_frame_len(), inner)
+    }
+
+    /// Creates a new `FramedWriter<W>` with the specified maximum frame length,
+    /// which is limited to the largest length that `prefix` can encode.
+    pub fn with_max_frame_len(
+        prefix: LengthPrefix,
+        max_frame_len: usize,
+        inner: W,
+    ) -> FramedWriter<W> {
+        let max_frame_len = max_frame_len.min(prefix.max_frame_len());
+        FramedWriter { inner, prefix, max_frame_len, written: 0 }
+    }
+
+    /// Writes `payload` as a frame.
+    ///
+    /// # Errors
+    ///
+    /// If the payload is longer than the maximum frame length, an error of the
+    /// kind [`ErrorKind::InvalidInput`] is returned & nothing is written.
+    ///
+    /// If the underlying writer returns `Ok(0)`, an error of the kind
+    /// [`ErrorKind::WriteZero`] is returned.
+    pub fn write_frame(&mut self, payload: &[u8]) -> Result<()> {
+        if payload.len() > self.max_frame_len {
+            return Err(Error::new_const(ErrorKind::InvalidInput, &"frame is too large"));
+        }
+        let mut header = [0; MAX_HEADER_LEN];
+        let header_len = self.prefix.encode(payload.len(), &mut header);
+        let mut bufs = [IoSlice::new(&header[..header_len]), IoSlice::new(payload)];
+        let mut bufs = &mut bufs[..];
+        // (like `write_all_vectored`, but keeping track of the progress so
+        // that the frame can be resumed after `WouldBlock`)
+        IoSlice::advance_slices(&mut bufs, self.written);
+        while !bufs.is_empty() {
+            match self.inner.write_vectored(bufs) {
+                Ok(0) => {
+                    self.written = 0;
+                    return Err(Error::new_const(
+                        ErrorKind::WriteZero,
+                        &"failed to write whole buffer",
+                    ));
+                }
+                Ok(n) => {
+                    self.written += n;
+                    IoSlice::advance_slices(&mut bufs, n);
+                }
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    if e.kind() != ErrorKind::WouldBlock {
+                        self.written = 0;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        self.written = 0;
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W> FramedWriter<W> {
+    /// Returns the maximum frame length.
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `FramedWriter<W>`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
@@ -0,0 +1,248 @@
+use super::{FramedReader, FramedWriter, LengthPrefix};
+use crate::test_util::{Flaky, retry};
+use crate::{Cursor, ErrorKind, IoSlice, Write};
+
+const PREFIXES: [LengthPrefix; 7] = [
+    LengthPrefix::U8,
+    LengthPrefix::U16Le,
+    LengthPrefix::U16Be,
+    LengthPrefix::U32Le,
+    LengthPrefix::U32Be,
+    LengthPrefix::Uleb128,
+    LengthPrefix::PrefixVarint,
+];
+
+const PAYLOAD: [u8; 300] = {
+    let mut payload = [0; 300];
+    let mut i = 0;
+    while i < payload.len() {
+        payload[i] = i as u8;
+        i += 1;
+    }
+    payload
+};
+
+// Frame lengths around the boundaries of the 1 & 2 byte varint encodings
+const LENS: [usize; 6] = [0, 1, 63, 64, 127, 128];
+
+#[test]
+fn round_trip() {
+    for prefix in PREFIXES {
+        let mut storage = [0; 1024];
+        let mut writer = FramedWriter::new(prefix, Cursor::new(&mut storage[..]));
+        for len in LENS {
+            writer.write_frame(&PAYLOAD[..len]).unwrap();
+        }
+        // (a frame of 300 bytes does not fit with a one byte length)
+        assert_eq!(writer.write_frame(&PAYLOAD).is_ok(), prefix != LengthPrefix::U8);
+        let written = writer.get_ref().position() as usize;
+
+        let mut reader = FramedReader::new(prefix, &storage[..written]);
+        let mut buf = [0; 300];
+        for len in LENS {
+            assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(len), "for {:?}", prefix);
+            assert_eq!(buf[..len], PAYLOAD[..len]);
+        }
+        if prefix != LengthPrefix::U8 {
+            assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(300));
+            assert_eq!(buf, PAYLOAD);
+        }
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), None);
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), None);
+    }
+}
+
+#[test]
+fn headers() {
+    let cases: [(LengthPrefix, &[u8]); 7] = [
+        (LengthPrefix::U8, &[200]),
+        (LengthPrefix::U16Le, &[200, 0]),
+        (LengthPrefix::U16Be, &[0, 200]),
+        (LengthPrefix::U32Le, &[200, 0, 0, 0]),
+        (LengthPrefix::U32Be, &[0, 0, 0, 200]),
+        (LengthPrefix::Uleb128, &[0xc8, 0x01]),
+        (LengthPrefix::PrefixVarint, &[0x22, 0x03]),
+    ];
+    for (prefix, header) in cases {
+        let mut storage = [0; 256];
+        let mut writer = FramedWriter::new(prefix, &mut storage[..]);
+        writer.write_frame(&PAYLOAD[..200]).unwrap();
+        assert_eq!(&storage[..header.len()], header, "for {:?}", prefix);
+        assert_eq!(storage[header.len()..][..200], PAYLOAD[..200]);
+    }
+}
+
+#[test]
+fn resume_read_frame() {
+    for prefix in PREFIXES {
+        let mut storage = [0; 1024];
+        let mut writer = FramedWriter::new(prefix, Cursor::new(&mut storage[..]));
+        for len in LENS {
+            writer.write_frame(&PAYLOAD[..len]).unwrap();
+        }
+        let written = writer.get_ref().position() as usize;
+
+        let mut reader = FramedReader::new(prefix, Flaky::new(&storage[..written]));
+        let mut buf = [0; 128];
+        for len in LENS {
+            assert_eq!(retry(|| reader.read_frame(&mut buf)).unwrap(), Some(len));
+            assert_eq!(buf[..len], PAYLOAD[..len]);
+        }
+        assert_eq!(retry(|| reader.read_frame(&mut buf)).unwrap(), None);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn resume_read_frame_to_vec() {
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    let mut storage = [0; 1024];
+    let mut writer = FramedWriter::new(LengthPrefix::Uleb128, Cursor::new(&mut storage[..]));
+    for len in LENS {
+        writer.write_frame(&PAYLOAD[..len]).unwrap();
+    }
+    let written = writer.get_ref().position() as usize;
+
+    let mut reader = FramedReader::new(LengthPrefix::Uleb128, Flaky::new(&storage[..written]));
+    let mut buf = Vec::new();
+    for len in LENS {
+        let start = buf.len();
+        assert_eq!(retry(|| reader.read_frame_to_vec(&mut buf)).unwrap(), Some(len));
+        assert_eq!(buf[start..], PAYLOAD[..len]);
+    }
+    assert_eq!(buf.len(), LENS.iter().sum());
+
+    // (the part of the payload that was read is left in the vector)
+    let mut reader = FramedReader::new(LengthPrefix::U8, Flaky::new(&[3, 7, 8, 9][..]));
+    let mut buf = Vec::new();
+    while buf.len() < 2 {
+        assert_eq!(reader.read_frame_to_vec(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    }
+    assert_eq!(buf, [7, 8]);
+    // (an error, rather than a panic, if resumed with another vector)
+    let mut other = Vec::new();
+    assert_eq!(reader.read_frame_to_vec(&mut other).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert!(other.is_empty());
+    assert_eq!(retry(|| reader.read_frame_to_vec(&mut buf)).unwrap(), Some(3));
+    assert_eq!(buf, [7, 8, 9]);
+}
+
+#[test]
+fn resume_write_frame() {
+    for prefix in PREFIXES {
+        let mut storage = [0; 1024];
+        let mut writer = FramedWriter::new(prefix, Flaky::new(Cursor::new(&mut storage[..])));
+        for len in LENS {
+            retry(|| writer.write_frame(&PAYLOAD[..len])).unwrap();
+        }
+        let written = writer.get_ref().inner.position() as usize;
+
+        let mut reader = FramedReader::new(prefix, &storage[..written]);
+        let mut buf = [0; 128];
+        for len in LENS {
+            assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(len));
+            assert_eq!(buf[..len], PAYLOAD[..len]);
+        }
+        assert_eq!(reader.read_frame(&mut buf).unwrap(), None);
+    }
+}
+
+#[test]
+fn one_vectored_write_per_frame() {
+    struct CountingWriter<'a> {
+        inner: Cursor<&'a mut [u8]>,
+        calls: usize,
+    }
+
+    impl Write for CountingWriter<'_> {
+        fn write(&mut self, buf: &[u8]) -> crate::Result<usize> {
+            self.calls += 1;
+            self.inner.write(buf)
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> crate::Result<usize> {
+            self.calls += 1;
+            self.inner.write_vectored(bufs)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            true
+        }
+
+        fn flush(&mut self) -> crate::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut storage = [0; 64];
+    let inner = CountingWriter { inner: Cursor::new(&mut storage[..]), calls: 0 };
+    let mut writer = FramedWriter::new(LengthPrefix::U32Be, inner);
+    writer.write_frame(b"hello").unwrap();
+    writer.write_frame(b"world").unwrap();
+    assert_eq!(writer.get_ref().calls, 2);
+    assert_eq!(storage[..9], [0, 0, 0, 5, b'h', b'e', b'l', b'l', b'o']);
+}
+
+#[test]
+fn max_frame_len() {
+    let data: &[u8] = &[0x81, 0x01, 0x03, b'a', b'b', b'c'];
+    let mut reader = FramedReader::with_max_frame_len(LengthPrefix::Uleb128, 128, data);
+    assert_eq!(reader.max_frame_len(), 128);
+    let error = reader.read_frame(&mut [0; 256]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    // (the payload of the frame that is too large is not read)
+    assert_eq!(reader.get_ref().len(), 4);
+
+    let mut storage = [0; 256];
+    let mut writer = FramedWriter::with_max_frame_len(LengthPrefix::U8, 1000, &mut storage[..]);
+    assert_eq!(writer.max_frame_len(), 255);
+    let error = writer.write_frame(&PAYLOAD[..256]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    writer.write_frame(&PAYLOAD[..255]).unwrap();
+    assert_eq!(writer.get_ref().len(), 0);
+}
+
+#[test]
+fn buffer_too_small() {
+    let mut reader = FramedReader::new(LengthPrefix::U8, &[3, b'a', b'b', b'c'][..]);
+    let error = reader.read_frame(&mut [0; 2]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    // (the frame can still be read with a larger buffer)
+    let mut buf = [0; 3];
+    assert_eq!(reader.read_frame(&mut buf).unwrap(), Some(3));
+    assert_eq!(&buf, b"abc");
+}
+
+#[test]
+fn invalid_header() {
+    let mut reader = FramedReader::new(LengthPrefix::Uleb128, &[0x80; 16][..]);
+    let error = reader.read_frame(&mut [0; 16]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn unexpected_eof() {
+    let cases: [(LengthPrefix, &[u8]); 4] = [
+        (LengthPrefix::U16Be, &[0]),
+        (LengthPrefix::Uleb128, &[0x80]),
+        (LengthPrefix::PrefixVarint, &[0b100, 0]),
+        (LengthPrefix::U8, &[3, b'a', b'b']),
+    ];
+    for (prefix, data) in cases {
+        let mut reader = FramedReader::new(prefix, data);
+        let error = reader.read_frame(&mut [0; 16]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof, "for {:?}", prefix);
+    }
+}
+
+#[test]
+fn write_zero() {
+    let mut storage = [0; 4];
+    let mut writer = FramedWriter::new(LengthPrefix::U8, &mut storage[..]);
+    let error = writer.write_frame(b"hello").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::WriteZero);
+}
@@ -2,9 +2,15 @@ use core::cmp;
 use core::fmt;
 use core::mem;
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "alloc")]
 use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::collections::VecDeque;
+#[cfg(feature = "alloc")]
 use alloc::string::String;
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
 use crate::{
@@ -36,11 +42,13 @@ impl<R: Read + ?Sized> Read for &mut R {
         (**self).is_read_vectored()
     }
 
+    #[cfg(feature = "alloc")]
     #[inline]
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
         (**self).read_to_end(buf)
     }
 
+    #[cfg(feature = "alloc")]
     #[inline]
     fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
         (**self).read_to_string(buf)
@@ -104,17 +112,20 @@ impl<B: BufRead + ?Sized> BufRead for &mut B {
         (**self).consume(amt)
     }
 
+    #[cfg(feature = "alloc")]
     #[inline]
     fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> io::Result<usize> {
         (**self).read_until(byte, buf)
     }
 
+    #[cfg(feature = "alloc")]
     #[inline]
     fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
         (**self).read_line(buf)
     }
 }
 
+#[cfg(feature = "alloc")]
 impl<R: Read + ?Sized> Read for Box<R> {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
@@ -151,6 +162,7 @@ impl<R: Read + ?Sized> Read for Box<R> {
         (**self).read_exact(buf)
     }
 }
+#[cfg(feature = "alloc")]
 impl<W: Write + ?Sized> Write for Box<W> {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
@@ -182,6 +194,7 @@ impl<W: Write + ?Sized> Write for Box<W> {
         (**self).write_fmt(fmt)
     }
 }
+#[cfg(feature = "alloc")]
 impl<S: Seek + ?Sized> Seek for Box<S> {
     #[inline]
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
@@ -193,6 +206,7 @@ impl<S: Seek + ?Sized> Seek for Box<S> {
         (**self).stream_position()
     }
 }
+#[cfg(feature = "alloc")]
 impl<B: BufRead + ?Sized> BufRead for Box<B> {
     #[inline]
     fn fill_buf(&mut self) -> io::Result<&[u8]> {
@@ -290,6 +304,7 @@ impl Read for &[u8] {
         Ok(())
     }
 
+    #[cfg(feature = "alloc")]
     #[inline]
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
         buf.extend_from_slice(*self);
@@ -367,6 +382,7 @@ impl Write for &mut [u8] {
 /// The vector will grow as needed.
 ///
 /// NOTE: Unlike `std::io`, this does not support `Write` for `Vec` with a custom allocator.
+#[cfg(feature = "alloc")]
 impl Write for Vec<u8> {
     #[inline]
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
@@ -400,3 +416,136 @@ impl Write for Vec<u8> {
         Ok(())
     }
 }
+
+/// Read is implemented for `VecDeque<u8>` by consuming bytes from the front of the `VecDeque`.
+///
+/// (ADDED in this fork, from a later version of `std::io`) Unlike `std::io`, reads copy from both
+/// of the slices returned by [`as_slices`](VecDeque::as_slices), so that a single call to `read`
+/// returns all of the content that fits in `buf`, even if the content is discontiguous.
+///
+/// NOTE: Unlike `std::io`, this does not support `VecDeque` with a custom allocator.
+#[cfg(feature = "alloc")]
+impl Read for VecDeque<u8> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (mut front, mut back) = self.as_slices();
+        let n = front.read(buf)?;
+        let n = n + back.read(&mut buf[n..])?;
+        self.drain(..n);
+        Ok(n)
+    }
+
+    #[inline]
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<()> {
+        let prev_filled = buf.filled_len();
+        let (mut front, mut back) = self.as_slices();
+        front.read_buf(buf)?;
+        back.read_buf(buf)?;
+        self.drain(..buf.filled_len() - prev_filled);
+        Ok(())
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let mut nread = 0;
+        for buf in bufs {
+            nread += self.read(buf)?;
+            if self.is_empty() {
+                break;
+            }
+        }
+
+        Ok(nread)
+    }
+
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if buf.len() > self.len() {
+            // (like `std::io`, the content is discarded when there is not enough of it)
+            self.clear();
+            return Err(Error::new_const(ErrorKind::UnexpectedEof, &"failed to fill whole buffer"));
+        }
+        self.read(buf).map(|_| ())
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        // (the total length is known upfront, so that it can be reserved in a single call)
+        let len = self.len();
+        buf.reserve(len);
+
+        let (front, back) = self.as_slices();
+        buf.extend_from_slice(front);
+        buf.extend_from_slice(back);
+        self.clear();
+        Ok(len)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        // SAFETY: only appends to the buffer
+        unsafe { io::append_to_string(buf, |buf| self.read_to_end(buf)) }
+    }
+}
+
+/// BufRead is implemented for `VecDeque<u8>` by reading bytes from the front of the `VecDeque`.
+///
+/// (ADDED in this fork, from a later version of `std::io`) [`fill_buf`](BufRead::fill_buf)
+/// returns the first of the slices returned by [`as_slices`](VecDeque::as_slices); if the content
+/// is discontiguous, the rest is returned once the first slice is consumed.
+#[cfg(feature = "alloc")]
+impl BufRead for VecDeque<u8> {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let (front, _) = self.as_slices();
+        Ok(front)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.drain(..amt);
+    }
+}
+
+/// Write is implemented for `VecDeque<u8>` by appending to the `VecDeque`, growing it as needed.
+///
+/// (ADDED in this fork, from a later version of `std::io`)
+#[cfg(feature = "alloc")]
+impl Write for VecDeque<u8> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.extend(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let len = bufs.iter().map(|b| b.len()).sum();
+        self.reserve(len);
+        for buf in bufs {
+            self.extend(&**buf);
+        }
+        Ok(len)
+    }
+
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.extend(buf);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -5,19 +5,42 @@
 //! - stable: `1.81.0`
 //! - nightly: `nightly-2022-08-24`
 //!
+//! NOTE: some optional features require a more recent Rust version than the MSRV:
+//! - `tokio` - the MSRV of the `tokio` crate (more recent than `nightly-2022-08-24`)
+//! - `embedded-io-async` - Rust `1.75.0` or newer (for `async fn` in traits)
+//!
 //! NOTE: unstable configuration `--cfg portable_io_unstable_all` in Rust flags is required for Rust nightly
 //! pre-`2024-06-09` to enable `error_in_core` feature directive (stabilized in June 2024).
 //!
+//! The `async_io` module has poll-based counterparts of the `Read`, `Write`, `Seek` & `BufRead` traits,
+//! with extension traits that provide futures & a tiny `block_on` executor, all available with `no_std`.
+//!
 //! ## Features
 //!
-//! - `alloc` (enabled by default) - mandatory feature - for alloc-related functionality
-//! - `os-error` (unstable feature) - support raw OS errors - with some KNOWN PANICS due to MISSING FUNCTIONALITY
+//! - `alloc` (enabled by default) - for functionality that uses `Box`, `String` or `Vec`, such as
+//!   `read_to_end`, `read_line`, `lines`, `split` & heap-allocated buffers for `BufReader` & `BufWriter`
+//! - `std` - conversions between `Error` & `ErrorKind` and the same types from `std::io`, and the
+//!   `FromStd` & `ToStd` adapters between the `Read`, `Write`, `Seek` & `BufRead` traits and the same traits from `std::io` (enables `alloc`)
+//! - `embedded-io` - conversions between `ErrorKind` and the same type from `embedded-io`, and the `FromEmbedded` &
+//!   `ToEmbedded` adapters between the `Read`, `Write`, `Seek` & `BufRead` traits and the same traits from `embedded-io`
+//! - `embedded-io-async` - the `async_io::embedded_compat::FromEmbeddedAsync` (with `alloc`) & `ToEmbeddedAsync` adapters between the async traits of this crate
+//!   and the `Read`, `Write`, `Seek` & `BufRead` traits of `embedded-io-async` (enables `embedded-io`)
+//! - `heapless` - `Write` for `heapless::Vec<u8, N>`, and the `BoundedVec` & `BoundedString` traits for `heapless::Vec<u8, N>` &
+//!   `heapless::String<N>`, to write to them with a `Cursor` & read into them with `read_to_end_bounded` & `read_line_bounded`
+//! - `arrayvec` - the same for `arrayvec::ArrayVec<u8, N>` & `arrayvec::ArrayString<N>`
+//! - `futures-io` - the `async_io::futures_compat::Compat` adapter between the async traits of this crate & the traits of `futures-io` (enables `std`)
+//! - `tokio` - the `async_io::tokio_compat::Compat` adapter between the async traits of this crate & the `AsyncRead`, `AsyncWrite` & `AsyncBufRead` traits of `tokio` (enables `std`)
+//! - `os-error` (unstable feature) - support raw OS errors, decoded with a built-in table of Linux (& POSIX) error codes
+//!   - `Error::last_os_error` gets the error code from a function registered with `set_last_os_error_hook` (or 0 if none is registered), except as below
+//! - `os-error-libc` (unstable feature) - on Unix platforms (Linux, Android, Apple platforms, FreeBSD, NetBSD, OpenBSD, Solaris & a few more): read `errno` via libc for `Error::last_os_error` & decode error codes with the error constants from libc, with error messages from `strerror_r` on Unix platforms other than Linux (enables `os-error`)
+//! - `os-error-strerror` (unstable feature) - on Unix platforms: get error messages from `strerror_r` rather than the built-in table (enables `os-error-libc`)
 //! - `unix-iovec` (unstable feature) - use `iovec` from `libc` for data stored in IoSlice & IoSliceMut
 //!
 //! ## CFG options
 //!
 //! - `portable_io_unstable_all` - enable all unstable option(s):
 //!   - size hint optimization for Read iterator - uses Rust unstable `min_specialization` feature
+//!   - `copy` optimization for buffered readers, byte slices & `Vec<u8>` writers - uses Rust unstable `min_specialization` feature
 //!
 //! To enable: use `--cfg portable_io_unstable_all` in Rust flags, set `RUSTFLAGS` env variable
 //! when running `cargo build` or `cargo test` for example.
@@ -37,6 +60,8 @@
     feature(allocator_api, min_specialization, error_in_core, mixed_integer_ops)
 )]
 
+#[cfg(test)]
+mod test_util;
 #[cfg(test)]
 mod tests;
 
@@ -49,37 +74,110 @@ use core::ops::{Deref, DerefMut};
 use core::slice;
 use core::str;
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
-#[cfg(portable_io_unstable_all)] // for unstable feature: size hint optimization
+// for unstable feature: size hint optimization
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))]
 use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
 use alloc::string::String;
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 
 // TODO: port & export more items from Rust std::io
-pub use self::cursor::Cursor;
+pub use self::bounded::{BoundedString, BoundedVec, BufReadBoundedExt, ReadBoundedExt};
+#[cfg(feature = "alloc")]
+pub use self::buffered::WriterPanicked;
+pub use self::buffered::{BufReader, BufWriter, BufferStorage, IntoInnerError, LineWriter};
+pub use self::bytes_ext::{ReadBytesExt, WriteBytesExt};
+pub use self::copy::{copy, copy_buf};
+pub use self::counting::{CountingReader, CountingWriter};
+pub use self::cursor::{Cursor, UninitCursor};
+#[cfg(all(feature = "embedded-io", feature = "alloc"))]
+pub use self::embedded::EmbeddedError;
+#[cfg(feature = "embedded-io")]
+pub use self::embedded::{FromEmbedded, ToEmbedded};
+#[cfg(feature = "os-error")]
+pub use self::error::set_last_os_error_hook;
 pub use self::error::{Error, ErrorKind, Result};
+pub use self::framed::{FramedReader, FramedWriter, LengthPrefix};
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub use self::pipe::pipe;
+pub use self::pipe::{Pipe, PipeReader, PipeWriter};
 pub use self::readbuf::ReadBuf;
-
+pub use self::ring_buffer::RingBuffer;
+#[cfg(feature = "std")]
+pub use self::std_io::{FromStd, ToStd};
+pub use self::tee::{Broadcast, BroadcastPolicy, BroadcastWriters, TeeReader};
+pub use self::util::{Empty, Repeat, Sink, empty, repeat, sink};
+pub use self::varint::{BufReadVarintExt, ReadVarintExt, WriteVarintExt};
+
+pub mod async_io;
+mod bounded;
+mod buffered;
+mod bytes_ext;
+pub mod checksum;
+pub mod codec;
+mod copy;
+mod counting;
 mod cursor;
+#[cfg(feature = "embedded-io")]
+mod embedded;
 mod error;
+mod framed;
 mod impls;
+mod observe;
+mod pipe;
 pub mod prelude;
 mod readbuf;
+mod ring_buffer;
+#[cfg(feature = "std")]
+mod std_io;
+mod tee;
+mod util;
+mod varint;
 
 mod sys;
 
-// TODO: support limited features with no use of `alloc` crate
-#[cfg(not(any(doc, feature = "alloc")))]
-compile_error!("`alloc` feature is currently required for this library to build");
-
 #[cfg(all(feature = "unix-iovec", not(unix)))]
 compile_error!("`unix-iovec` feature requires a Unix platform");
 
+// (the platforms for which `sys::os` knows the name of the errno accessor)
+#[cfg(all(
+    feature = "os-error-libc",
+    unix,
+    not(any(
+        target_os = "linux",
+        target_os = "emscripten",
+        target_os = "fuchsia",
+        target_os = "l4re",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "android",
+        target_os = "redox",
+        target_env = "newlib",
+        target_os = "solaris",
+        target_os = "illumos",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "watchos",
+        target_os = "haiku"
+    ))
+))]
+compile_error!(
+    "`os-error-libc` feature does not support this Unix platform (use `set_last_os_error_hook` instead)"
+);
+
+pub(crate) const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+#[cfg(feature = "alloc")]
 struct Guard<'a> {
     buf: &'a mut Vec<u8>,
     len: usize,
 }
 
+#[cfg(feature = "alloc")]
 impl Drop for Guard<'_> {
     fn drop(&mut self) {
         unsafe {
@@ -107,6 +205,7 @@ impl Drop for Guard<'_> {
 // 2. We're passing a raw buffer to the function `f`, and it is expected that
 //    the function only *appends* bytes to the buffer. We'll get undefined
 //    behavior if existing bytes are overwritten to have non-UTF-8 data.
+#[cfg(feature = "alloc")]
 pub(crate) unsafe fn append_to_string<F>(buf: &mut String, f: F) -> Result<usize>
 where
     F: FnOnce(&mut Vec<u8>) -> Result<usize>,
@@ -129,6 +228,7 @@ where
 // of data to return. Simply tacking on an extra DEFAULT_BUF_SIZE space every
 // time is 4,500 times (!) slower than a default reservation size of 32 if the
 // reader has a very small amount of data to return.
+#[cfg(feature = "alloc")]
 pub(crate) fn default_read_to_end<R: Read + ?Sized>(r: &mut R, buf: &mut Vec<u8>) -> Result<usize> {
     let start_len = buf.len();
     let start_cap = buf.capacity();
@@ -187,6 +287,7 @@ pub(crate) fn default_read_to_end<R: Read + ?Sized>(r: &mut R, buf: &mut Vec<u8>
     }
 }
 
+#[cfg(feature = "alloc")]
 pub(crate) fn default_read_to_string<R: Read + ?Sized>(
     r: &mut R,
     buf: &mut String,
@@ -391,6 +492,10 @@ pub trait Read {
     /// `buf`.
     ///
     /// <!-- TODO ADD EXAMPLE CODE THAT DOES NOT USE FS -->
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
         default_read_to_end(self, buf)
     }
@@ -410,6 +515,10 @@ pub trait Read {
     /// [`read_to_end`]: Read::read_to_end
     ///
     /// <!-- TODO ADD EXAMPLE CODE THAT DOES NOT USE FS -->
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
     fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
         default_read_to_string(self, buf)
     }
@@ -472,7 +581,7 @@ pub trait Read {
             }
 
             if buf.filled().len() == prev_filled {
-                return Err(Error::new(ErrorKind::UnexpectedEof, "failed to fill buffer"));
+                return Err(Error::new_const(ErrorKind::UnexpectedEof, &"failed to fill buffer"));
             }
         }
 
@@ -571,6 +680,10 @@ pub trait Read {
 /// don't have to worry about your buffer being empty or partially full.
 ///
 /// <!-- TODO ADD EXAMPLE CODE THAT DOES NOT USE STDIN -->
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+#[cfg(feature = "alloc")]
 pub fn read_to_string<R: Read>(reader: &mut R) -> Result<String> {
     let mut buf = String::new();
     reader.read_to_string(&mut buf)?;
@@ -1191,6 +1304,7 @@ pub enum SeekFrom {
     Current(i64),
 }
 
+#[cfg(feature = "alloc")]
 fn read_until<R: BufRead + ?Sized>(r: &mut R, delim: u8, buf: &mut Vec<u8>) -> Result<usize> {
     let mut read = 0;
     loop {
@@ -1346,6 +1460,10 @@ pub trait BufRead: Read {
     /// assert_eq!(num_bytes, 0);
     /// assert_eq!(buf, b"");
     /// ```
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
     fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize> {
         read_until(self, byte, buf)
     }
@@ -1409,6 +1527,10 @@ pub trait BufRead: Read {
     /// assert_eq!(num_bytes, 0);
     /// assert_eq!(buf, "");
     /// ```
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
     fn read_line(&mut self, buf: &mut String) -> Result<usize> {
         // Note that we are not calling the `.read_until` method here, but
         // rather our hardcoded implementation. For more details as to why, see
@@ -1447,6 +1569,10 @@ pub trait BufRead: Read {
     /// assert_eq!(split_iter.next(), Some(b"dolor".to_vec()));
     /// assert_eq!(split_iter.next(), None);
     /// ```
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
     fn split(self, byte: u8) -> Split<Self>
     where
         Self: Sized,
@@ -1484,6 +1610,10 @@ pub trait BufRead: Read {
     /// # Errors
     ///
     /// Each line of the iterator has the same error semantics as [`BufRead::read_line`].
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    #[cfg(feature = "alloc")]
     fn lines(self) -> Lines<Self>
     where
         Self: Sized,
@@ -1818,7 +1948,7 @@ impl<T> SizeHint for &mut T {
     }
 }
 
-#[cfg(portable_io_unstable_all)] // unstable feature: size hint optimization (requires Rust nightly for min_specialization)
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))] // unstable feature: size hint optimization (requires Rust nightly for min_specialization)
 impl<T> SizeHint for Box<T> {
     #[inline]
     fn lower_bound(&self) -> usize {
@@ -1851,12 +1981,17 @@ impl SizeHint for &[u8] {
 /// Please see the documentation of [`split`] for more details.
 ///
 /// [`split`]: BufRead::split
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
 pub struct Split<B> {
     buf: B,
     delim: u8,
 }
 
+#[cfg(feature = "alloc")]
 impl<B: BufRead> Iterator for Split<B> {
     type Item = Result<Vec<u8>>;
 
@@ -1881,11 +2016,16 @@ impl<B: BufRead> Iterator for Split<B> {
 /// Please see the documentation of [`lines`] for more details.
 ///
 /// [`lines`]: BufRead::lines
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+#[cfg(feature = "alloc")]
 #[derive(Debug)]
 pub struct Lines<B> {
     buf: B,
 }
 
+#[cfg(feature = "alloc")]
 impl<B: BufRead> Iterator for Lines<B> {
     type Item = Result<String>;
 
@@ -0,0 +1,88 @@
+// (ADDED in this fork) helpers for the readers that pass on the bytes they
+// read, such as `HashingReader`
+
+use crate::{BufRead, Error, Read, ReadBuf, Result};
+
+/// Calls `read_buf` of `reader`, then `f` with the bytes that were newly
+/// filled in `buf`, even if there is an error.
+pub(crate) fn read_buf_with<R: Read + ?Sized>(
+    reader: &mut R,
+    buf: &mut ReadBuf<'_>,
+    f: impl FnOnce(&[u8]),
+) -> Result<()> {
+    let prev_filled = buf.filled_len();
+    let result = reader.read_buf(buf);
+    if buf.filled_len() > prev_filled {
+        f(&buf.filled()[prev_filled..]);
+    }
+    result
+}
+
+/// Keeps the number of bytes returned by the last call to `fill_buf` that are
+/// not consumed yet, to get the bytes given to `consume` without any I/O.
+#[derive(Debug, Default)]
+pub(crate) struct Unconsumed {
+    len: usize,
+    // (an error while getting the bytes given to `consume`, returned by the next call)
+    error: Option<Error>,
+}
+
+impl Unconsumed {
+    /// Returns the error while getting the bytes given to `consume`, if any,
+    /// to be returned by the next call to read.
+    pub(crate) fn take_error(&mut self) -> Result<()> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Calls `fill_buf` of `reader`, remembering how many bytes it returned,
+    /// unless there is an error from `take_error`.
+    pub(crate) fn fill_buf<'a, R: BufRead + ?Sized>(
+        &mut self,
+        reader: &'a mut R,
+    ) -> Result<&'a [u8]> {
+        self.take_error()?;
+        self.len = 0;
+        let available = reader.fill_buf()?;
+        self.len = available.len();
+        Ok(available)
+    }
+
+    /// Calls `f` with the bytes that are consumed, as far as they were returned
+    /// by the last call to `fill_buf`, then `consume` of `reader`.
+    ///
+    /// `f` is called with fewer than `amt` bytes (or none) if the caller
+    /// consumes bytes that it did not get from `fill_buf`, or if getting them
+    /// fails, in which case the error is kept for `take_error`.
+    pub(crate) fn consume<R: BufRead + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        amt: usize,
+        f: impl FnOnce(&[u8]),
+    ) {
+        let known = amt.min(self.len);
+        let consumed = match known {
+            0 => &[][..],
+            // (these bytes are still buffered, so calling `fill_buf` again
+            // returns them without any I/O)
+            _ => match reader.fill_buf() {
+                Ok(available) => &available[..known.min(available.len())],
+                Err(e) => {
+                    self.error.get_or_insert(e);
+                    &[][..]
+                }
+            },
+        };
+        f(consumed);
+        self.len -= known;
+        reader.consume(amt)
+    }
+
+    /// Forgets the bytes returned by `fill_buf`, for example when the
+    /// underlying reader may be used directly.
+    pub(crate) fn reset(&mut self) {
+        self.len = 0;
+    }
+}
@@ -0,0 +1,524 @@
+// (ADDED in this fork) a single-producer, single-consumer pipe of bytes built
+// on atomics, for example between an interrupt handler & a task
+
+#[cfg(all(test, loom))]
+mod loom_tests;
+#[cfg(all(test, not(loom)))]
+mod tests;
+
+use core::cell::UnsafeCell;
+use core::cmp;
+use core::fmt;
+use core::ops::Deref;
+use core::ptr;
+#[cfg(not(all(test, loom)))]
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+// (the atomics, mutex & thread parking are modeled by loom in its tests)
+#[cfg(all(test, loom))]
+use loom::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+extern crate alloc;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+use alloc::sync::Arc;
+
+#[cfg(feature = "std")]
+extern crate std;
+#[cfg(all(feature = "std", test, loom))]
+use loom::{
+    sync::Mutex,
+    sync::atomic::fence,
+    thread::{self, Thread},
+};
+#[cfg(feature = "std")]
+use std::sync::PoisonError;
+#[cfg(all(feature = "std", not(all(test, loom))))]
+use {
+    core::sync::atomic::fence,
+    std::sync::Mutex,
+    std::thread::{self, Thread},
+};
+
+use crate::{BufRead, Error, ErrorKind, Read, Result, Write};
+
+// Defines a `const fn`, except in the loom tests, as the atomics of loom
+// cannot be created in a const context
+macro_rules! const_fn {
+    ($(#[$attr:meta])* $vis:vis fn $($rest:tt)*) => {
+        #[cfg(not(all(test, loom)))]
+        $(#[$attr])* $vis const fn $($rest)*
+        #[cfg(all(test, loom))]
+        $(#[$attr])* $vis fn $($rest)*
+    };
+}
+
+/// Creates a new pipe with room for `N` bytes, returning the writer & the
+/// reader, which can be sent to other threads.
+///
+/// The storage is allocated on the heap; see [`Pipe`] for a pipe that does
+/// not use the `alloc` crate.
+///
+/// # Panics
+///
+/// Panics if `N` is 0.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{ErrorKind, Read, Write};
+///
+/// let (mut writer, mut reader) = portable_io::pipe::<8>();
+/// writer.write_all(b"some").unwrap();
+///
+/// let mut buf = [0; 8];
+/// assert_eq!(reader.read(&mut buf).unwrap(), 4);
+/// assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+///
+/// // (EOF once the writer is dropped)
+/// drop(writer);
+/// assert_eq!(reader.read(&mut buf).unwrap(), 0);
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub fn pipe<const N: usize>() -> (PipeWriter<'static, N>, PipeReader<'static, N>) {
+    let pipe = Arc::new(Pipe::new());
+    (PipeWriter::new(Shared::Owned(pipe.clone())), PipeReader::new(Shared::Owned(pipe)))
+}
+
+/// The storage of a pipe with room for `N` bytes, which is split into a
+/// [`PipeWriter`] & a [`PipeReader`].
+///
+/// The pipe is built on atomic loads & stores only (no compare & swap), &
+/// does not use the `alloc` crate, so that it can be kept on the stack or in
+/// static storage (split through a `&'static mut Pipe<N>`). [`pipe`](crate::pipe()) creates a pipe with heap storage
+/// instead, with halves that do not borrow the storage.
+///
+/// Reads from an empty pipe & writes to a full pipe return an error of the
+/// kind [`ErrorKind::WouldBlock`], unless the halves are in blocking mode
+/// (with the `std` feature). Once the writer is dropped, the reader returns
+/// the remaining bytes & then `Ok(0)`, for EOF. Once the reader is dropped,
+/// writing fails with an error of the kind [`ErrorKind::BrokenPipe`].
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{BufRead, Pipe, Write};
+///
+/// let mut pipe: Pipe<16> = Pipe::new();
+/// let (mut writer, mut reader) = pipe.split();
+/// writer.write_all(b"some data").unwrap();
+/// drop(writer);
+///
+/// assert_eq!(reader.fill_buf().unwrap(), b"some data");
+/// reader.consume(9);
+/// assert_eq!(reader.fill_buf().unwrap(), b"");
+/// ```
+pub struct Pipe<const N: usize> {
+    buf: UnsafeCell<[u8; N]>,
+    // (the positions of the writer & reader, counted modulo `2 * N` so that a
+    // full pipe can be told apart from an empty one)
+    write_pos: AtomicUsize,
+    read_pos: AtomicUsize,
+    writer_closed: AtomicBool,
+    reader_closed: AtomicBool,
+    #[cfg(feature = "std")]
+    writer_waiter: Waiter,
+    #[cfg(feature = "std")]
+    reader_waiter: Waiter,
+}
+
+// SAFETY: the bytes are only written by the single writer, to the free part of
+// the storage, & only read by the single reader, from the part that was
+// written, with the positions published by release stores & acquire loads
+unsafe impl<const N: usize> Sync for Pipe<N> {}
+
+impl<const N: usize> Pipe<N> {
+    const_fn! {
+        /// Creates a new, empty `Pipe<N>`.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `N` is 0.
+        pub fn new() -> Pipe<N> {
+            assert!(N > 0, "pipe capacity must not be 0");
+            Pipe {
+                buf: UnsafeCell::new([0; N]),
+                write_pos: AtomicUsize::new(0),
+                read_pos: AtomicUsize::new(0),
+                writer_closed: AtomicBool::new(false),
+                reader_closed: AtomicBool::new(false),
+                #[cfg(feature = "std")]
+                writer_waiter: Waiter::new(),
+                #[cfg(feature = "std")]
+                reader_waiter: Waiter::new(),
+            }
+        }
+    }
+
+    /// Returns the number of bytes this `Pipe<N>` can hold, which is `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Splits this `Pipe<N>` into a writer & a reader, which borrow it.
+    ///
+    /// The pipe is emptied first, so that it can be used again once the
+    /// halves of a previous split are dropped.
+    pub fn split(&mut self) -> (PipeWriter<'_, N>, PipeReader<'_, N>) {
+        // (stores rather than `get_mut`, which the atomics of loom do not have)
+        self.write_pos.store(0, Ordering::Relaxed);
+        self.read_pos.store(0, Ordering::Relaxed);
+        self.writer_closed.store(false, Ordering::Relaxed);
+        self.reader_closed.store(false, Ordering::Relaxed);
+        let pipe = &*self;
+        (PipeWriter::new(Shared::Borrowed(pipe)), PipeReader::new(Shared::Borrowed(pipe)))
+    }
+
+    fn len(&self, write_pos: usize, read_pos: usize) -> usize {
+        (write_pos + 2 * N - read_pos) % (2 * N)
+    }
+
+    fn advance(pos: usize, amt: usize) -> usize {
+        (pos + amt) % (2 * N)
+    }
+
+    // Returns the number of bytes to be read, as seen by the reader
+    fn readable(&self) -> usize {
+        let read_pos = self.read_pos.load(Ordering::Relaxed);
+        self.len(self.write_pos.load(Ordering::Acquire), read_pos)
+    }
+
+    // Returns the number of free bytes, as seen by the writer
+    fn writable(&self) -> usize {
+        let write_pos = self.write_pos.load(Ordering::Relaxed);
+        N - self.len(write_pos, self.read_pos.load(Ordering::Acquire))
+    }
+
+    // Returns the bytes to be read up to the end of the storage (reader only)
+    fn read_slice(&self) -> &[u8] {
+        let read_pos = self.read_pos.load(Ordering::Relaxed);
+        let len = self.len(self.write_pos.load(Ordering::Acquire), read_pos);
+        let start = read_pos % N;
+        // SAFETY: the writer does not write to the bytes between the read &
+        // write positions until the read position is moved past them
+        unsafe {
+            core::slice::from_raw_parts(
+                self.buf.get().cast::<u8>().add(start),
+                cmp::min(len, N - start),
+            )
+        }
+    }
+
+    // Moves the read position by up to `amt` bytes (reader only)
+    fn consume(&self, amt: usize) {
+        let amt = cmp::min(amt, self.readable());
+        let read_pos = self.read_pos.load(Ordering::Relaxed);
+        self.read_pos.store(Self::advance(read_pos, amt), Ordering::Release);
+        #[cfg(feature = "std")]
+        self.writer_waiter.wake();
+    }
+
+    // Copies as many bytes as there is room for to the storage (writer only)
+    fn push(&self, buf: &[u8]) -> usize {
+        let write_pos = self.write_pos.load(Ordering::Relaxed);
+        let amt = cmp::min(buf.len(), self.writable());
+        let start = write_pos % N;
+        let first_len = cmp::min(amt, N - start);
+        let storage = self.buf.get().cast::<u8>();
+        // SAFETY: the reader does not read the bytes between the write & read
+        // positions until the write position is moved past them
+        unsafe {
+            ptr::copy_nonoverlapping(buf.as_ptr(), storage.add(start), first_len);
+            ptr::copy_nonoverlapping(buf[first_len..].as_ptr(), storage, amt - first_len);
+        }
+        self.write_pos.store(Self::advance(write_pos, amt), Ordering::Release);
+        #[cfg(feature = "std")]
+        self.reader_waiter.wake();
+        amt
+    }
+}
+
+impl<const N: usize> Default for Pipe<N> {
+    fn default() -> Pipe<N> {
+        Pipe::new()
+    }
+}
+
+impl<const N: usize> fmt::Debug for Pipe<N> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len =
+            self.len(self.write_pos.load(Ordering::Acquire), self.read_pos.load(Ordering::Acquire));
+        fmt.debug_struct("Pipe")
+            .field("buffer", &format_args!("{}/{}", len, N))
+            .field("writer_closed", &self.writer_closed.load(Ordering::Relaxed))
+            .field("reader_closed", &self.reader_closed.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+// A thread parked until the other half of the pipe makes progress
+#[cfg(feature = "std")]
+struct Waiter {
+    waiting: AtomicBool,
+    thread: Mutex<Option<Thread>>,
+}
+
+#[cfg(feature = "std")]
+impl Waiter {
+    const_fn! {
+        fn new() -> Waiter {
+            Waiter { waiting: AtomicBool::new(false), thread: Mutex::new(None) }
+        }
+    }
+
+    // Parks the current thread until `ready` returns true
+    fn wait(&self, ready: impl Fn() -> bool) {
+        *self.thread.lock().unwrap_or_else(PoisonError::into_inner) = Some(thread::current());
+        self.waiting.store(true, Ordering::SeqCst);
+        // (either `ready` sees the progress of the other half, or the other
+        // half sees `waiting` after its own fence & unparks this thread)
+        fence(Ordering::SeqCst);
+        while !ready() {
+            thread::park();
+        }
+        self.waiting.store(false, Ordering::Relaxed);
+    }
+
+    // Unparks the waiting thread, if any, after progress was published
+    fn wake(&self) {
+        fence(Ordering::SeqCst);
+        if self.waiting.load(Ordering::Acquire) {
+            if let Some(thread) = &*self.thread.lock().unwrap_or_else(PoisonError::into_inner) {
+                thread.unpark();
+            }
+        }
+    }
+}
+
+// The storage of a pipe, borrowed or shared by the two halves
+enum Shared<'a, const N: usize> {
+    Borrowed(&'a Pipe<N>),
+    #[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+    Owned(Arc<Pipe<N>>),
+}
+
+impl<const N: usize> Deref for Shared<'_, N> {
+    type Target = Pipe<N>;
+
+    fn deref(&self) -> &Pipe<N> {
+        match self {
+            Shared::Borrowed(pipe) => pipe,
+            #[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+            Shared::Owned(pipe) => pipe,
+        }
+    }
+}
+
+/// The writing half of a pipe, from [`pipe`](crate::pipe()) or
+/// [`Pipe::split`].
+///
+/// Dropping the writer signals EOF to the reader.
+pub struct PipeWriter<'a, const N: usize> {
+    pipe: Shared<'a, N>,
+    nonblocking: bool,
+}
+
+impl<'a, const N: usize> PipeWriter<'a, N> {
+    fn new(pipe: Shared<'a, N>) -> PipeWriter<'a, N> {
+        PipeWriter { pipe, nonblocking: true }
+    }
+
+    /// Sets whether writing to a full pipe returns an error of the kind
+    /// [`ErrorKind::WouldBlock`] (the default), or parks the current thread
+    /// until the reader makes room or is dropped.
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>std</code></div>
+    #[cfg(feature = "std")]
+    pub fn set_nonblocking(&mut self, nonblocking: bool) {
+        self.nonblocking = nonblocking;
+    }
+
+    /// Returns whether this writer is in non-blocking mode.
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking
+    }
+
+    /// Returns the number of bytes the pipe can hold.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of bytes that can be written without blocking.
+    pub fn available(&self) -> usize {
+        self.pipe.writable()
+    }
+
+    /// Returns true if the reader was dropped.
+    pub fn is_closed(&self) -> bool {
+        self.pipe.reader_closed.load(Ordering::Acquire)
+    }
+
+    // Waits until there is room in the pipe, returning an error once the reader is dropped
+    fn wait_writable(&self) -> Result<()> {
+        loop {
+            if self.is_closed() {
+                return Err(Error::new_const(ErrorKind::BrokenPipe, &"pipe reader was dropped"));
+            }
+            if self.pipe.writable() > 0 {
+                return Ok(());
+            }
+            if self.nonblocking {
+                return Err(Error::new_const(ErrorKind::WouldBlock, &"pipe is full"));
+            }
+            #[cfg(feature = "std")]
+            self.pipe.writer_waiter.wait(|| self.is_closed() || self.pipe.writable() > 0);
+        }
+    }
+}
+
+impl<const N: usize> Write for PipeWriter<'_, N> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.is_empty() && !self.is_closed() {
+            return Ok(0);
+        }
+        self.wait_writable()?;
+        Ok(self.pipe.push(buf))
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<const N: usize> Drop for PipeWriter<'_, N> {
+    fn drop(&mut self) {
+        self.pipe.writer_closed.store(true, Ordering::Release);
+        #[cfg(feature = "std")]
+        self.pipe.reader_waiter.wake();
+    }
+}
+
+impl<const N: usize> fmt::Debug for PipeWriter<'_, N> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PipeWriter")
+            .field("pipe", &*self.pipe)
+            .field("nonblocking", &self.nonblocking)
+            .finish()
+    }
+}
+
+/// The reading half of a pipe, from [`pipe`](crate::pipe()) or
+/// [`Pipe::split`].
+///
+/// Dropping the reader makes further writes fail with an error of the kind
+/// [`ErrorKind::BrokenPipe`].
+pub struct PipeReader<'a, const N: usize> {
+    pipe: Shared<'a, N>,
+    nonblocking: bool,
+}
+
+impl<'a, const N: usize> PipeReader<'a, N> {
+    fn new(pipe: Shared<'a, N>) -> PipeReader<'a, N> {
+        PipeReader { pipe, nonblocking: true }
+    }
+
+    /// Sets whether reading from an empty pipe returns an error of the kind
+    /// [`ErrorKind::WouldBlock`] (the default), or parks the current thread
+    /// until the writer writes more bytes or is dropped.
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>std</code></div>
+    #[cfg(feature = "std")]
+    pub fn set_nonblocking(&mut self, nonblocking: bool) {
+        self.nonblocking = nonblocking;
+    }
+
+    /// Returns whether this reader is in non-blocking mode.
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking
+    }
+
+    /// Returns the number of bytes the pipe can hold.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of bytes that can be read without blocking.
+    pub fn available(&self) -> usize {
+        self.pipe.readable()
+    }
+
+    /// Returns true if the writer was dropped, even if there are bytes left
+    /// to be read.
+    pub fn is_closed(&self) -> bool {
+        self.pipe.writer_closed.load(Ordering::Acquire)
+    }
+
+    // Waits until there are bytes to be read, or the writer is dropped
+    fn wait_readable(&self) -> Result<()> {
+        loop {
+            if self.pipe.readable() > 0 || self.is_closed() {
+                // (bytes written before the writer was dropped are still read
+                // first, as `readable` is checked again by the caller)
+                return Ok(());
+            }
+            if self.nonblocking {
+                return Err(Error::new_const(ErrorKind::WouldBlock, &"pipe is empty"));
+            }
+            #[cfg(feature = "std")]
+            self.pipe.reader_waiter.wait(|| self.is_closed() || self.pipe.readable() > 0);
+        }
+    }
+}
+
+impl<const N: usize> Read for PipeReader<'_, N> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let available = self.fill_buf()?;
+        let amt = cmp::min(available.len(), buf.len());
+        buf[..amt].copy_from_slice(&available[..amt]);
+        self.consume(amt);
+        // (the rest of the bytes, if they wrap around the end of the storage)
+        if amt < buf.len() && self.pipe.readable() > 0 {
+            let rest = self.pipe.read_slice();
+            let rest_len = cmp::min(rest.len(), buf.len() - amt);
+            buf[amt..][..rest_len].copy_from_slice(&rest[..rest_len]);
+            self.consume(rest_len);
+            return Ok(amt + rest_len);
+        }
+        Ok(amt)
+    }
+}
+
+impl<const N: usize> BufRead for PipeReader<'_, N> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.wait_readable()?;
+        Ok(self.pipe.read_slice())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pipe.consume(amt)
+    }
+}
+
+impl<const N: usize> Drop for PipeReader<'_, N> {
+    fn drop(&mut self) {
+        self.pipe.reader_closed.store(true, Ordering::Release);
+        #[cfg(feature = "std")]
+        self.pipe.writer_waiter.wake();
+    }
+}
+
+impl<const N: usize> fmt::Debug for PipeReader<'_, N> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PipeReader")
+            .field("pipe", &*self.pipe)
+            .field("nonblocking", &self.nonblocking)
+            .finish()
+    }
+}
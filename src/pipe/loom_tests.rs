@@ -0,0 +1,102 @@
+// Models of the pipe with loom, which runs the two halves in every order of
+// their atomic operations & of the handshake of `Waiter`, with:
+// RUSTFLAGS="--cfg loom" cargo test --features std --lib pipe::
+//
+// (the bytes in the storage are not modeled, only the atomics that guard them)
+
+extern crate std;
+use loom::thread;
+use std::vec::Vec;
+
+use super::pipe;
+use crate::{ErrorKind, Read, Write};
+
+// Runs `f` in every order allowed by loom, with at most 4 preemptions (unless
+// set by LOOM_MAX_PREEMPTIONS), as the halves may wait for each other in a loop
+fn model(f: impl Fn() + Sync + Send + 'static) {
+    let mut builder = loom::model::Builder::new();
+    builder.preemption_bound.get_or_insert(4);
+    builder.check(f)
+}
+
+#[test]
+fn nonblocking_halves_see_consistent_positions() {
+    model(|| {
+        let (mut writer, mut reader) = pipe::<2>();
+        let sender = thread::spawn(move || {
+            let mut written = 0;
+            for part in [&b"ab"[..], b"c"] {
+                match writer.write(part) {
+                    Ok(n) => written += n,
+                    Err(e) => assert_eq!(e.kind(), ErrorKind::WouldBlock),
+                }
+            }
+            written
+        });
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let mut buf = [0; 2];
+            match reader.read(&mut buf) {
+                Ok(n) => received.extend_from_slice(&buf[..n]),
+                Err(e) => assert_eq!(e.kind(), ErrorKind::WouldBlock),
+            }
+        }
+        let written = sender.join().unwrap();
+        assert_eq!(reader.available(), written - received.len());
+        let mut rest = [0; 3];
+        let n = reader.read(&mut rest).unwrap();
+        received.extend_from_slice(&rest[..n]);
+        assert_eq!(&received[..], &b"abc"[..written]);
+        assert_eq!(reader.read(&mut rest).unwrap(), 0);
+    });
+}
+
+// (both halves run on threads of their own, as loom lets the unparking of a
+// thread by `Waiter::wake` after it stopped waiting end its wait in `join`)
+
+#[test]
+fn blocking_halves_do_not_miss_wakeups() {
+    model(|| {
+        let (mut writer, mut reader) = pipe::<1>();
+        writer.set_nonblocking(false);
+        reader.set_nonblocking(false);
+        let sender = thread::spawn(move || writer.write_all(b"ab").unwrap());
+        let receiver = thread::spawn(move || {
+            let mut received = Vec::new();
+            reader.read_to_end(&mut received).unwrap();
+            received
+        });
+        sender.join().unwrap();
+        assert_eq!(receiver.join().unwrap(), b"ab");
+    });
+}
+
+#[test]
+fn blocking_writer_wakes_up_on_drop_of_reader() {
+    model(|| {
+        let (mut writer, mut reader) = pipe::<1>();
+        writer.set_nonblocking(false);
+        reader.set_nonblocking(false);
+        // (the writer can only write 2 of the bytes before the reader is dropped)
+        let sender = thread::spawn(move || writer.write_all(b"abc"));
+        let receiver = thread::spawn(move || reader.read_exact(&mut [0; 1]).unwrap());
+        receiver.join().unwrap();
+        assert_eq!(sender.join().unwrap().unwrap_err().kind(), ErrorKind::BrokenPipe);
+    });
+}
+
+#[test]
+fn blocking_reader_wakes_up_on_drop_of_writer() {
+    model(|| {
+        let (mut writer, mut reader) = pipe::<1>();
+        reader.set_nonblocking(false);
+        let sender = thread::spawn(move || writer.write_all(b"a").unwrap());
+        let receiver = thread::spawn(move || {
+            let mut received = Vec::new();
+            reader.read_to_end(&mut received).unwrap();
+            received
+        });
+        sender.join().unwrap();
+        assert_eq!(receiver.join().unwrap(), b"a");
+    });
+}
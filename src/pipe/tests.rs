@@ -0,0 +1,341 @@
+use super::Pipe;
+use crate::{BufRead, ErrorKind, Read, Write};
+
+// Calls `f` with each of the orders in which the given numbers of writer &
+// reader calls can be made on one thread, as a list with `true` for a writer
+// call (the atomics inside the calls are modeled in `loom_tests.rs`)
+fn for_each_call_order(writer_steps: usize, reader_steps: usize, f: &mut impl FnMut(&[bool])) {
+    fn call_orders(
+        order: &mut [bool; 16],
+        len: usize,
+        writer_steps: usize,
+        reader_steps: usize,
+        f: &mut impl FnMut(&[bool]),
+    ) {
+        if writer_steps == 0 && reader_steps == 0 {
+            return f(&order[..len]);
+        }
+        if writer_steps > 0 {
+            order[len] = true;
+            call_orders(order, len + 1, writer_steps - 1, reader_steps, f);
+        }
+        if reader_steps > 0 {
+            order[len] = false;
+            call_orders(order, len + 1, writer_steps, reader_steps - 1, f);
+        }
+    }
+
+    call_orders(&mut [false; 16], 0, writer_steps, reader_steps, f)
+}
+
+#[test]
+fn write_read_and_eof() {
+    let mut pipe: Pipe<4> = Pipe::new();
+    assert_eq!(pipe.capacity(), 4);
+    let (mut writer, mut reader) = pipe.split();
+    assert!(writer.is_nonblocking() && reader.is_nonblocking());
+
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(reader.read(&mut []).unwrap(), 0);
+    assert_eq!(writer.write(b"abcdef").unwrap(), 4);
+    assert_eq!(writer.write(b"ef").unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(writer.write(b"").unwrap(), 0);
+    assert_eq!((writer.available(), reader.available()), (0, 4));
+
+    // (the bytes wrap around the end of the storage)
+    assert_eq!(reader.read(&mut buf[..3]).unwrap(), 3);
+    assert_eq!(writer.write(b"efg").unwrap(), 3);
+    assert_eq!(reader.fill_buf().unwrap(), b"d");
+    assert_eq!(reader.read(&mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"defg");
+
+    writer.write_all(b"h").unwrap();
+    assert!(!reader.is_closed());
+    drop(writer);
+    assert!(reader.is_closed());
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    assert_eq!(reader.fill_buf().unwrap(), b"");
+}
+
+#[test]
+fn broken_pipe() {
+    let mut pipe: Pipe<4> = Pipe::new();
+    let (mut writer, reader) = pipe.split();
+    writer.write_all(b"ab").unwrap();
+    assert!(!writer.is_closed());
+    drop(reader);
+    assert!(writer.is_closed());
+    assert_eq!(writer.write(b"c").unwrap_err().kind(), ErrorKind::BrokenPipe);
+    assert_eq!(writer.write(b"").unwrap_err().kind(), ErrorKind::BrokenPipe);
+    drop(writer);
+
+    // (splitting again empties the pipe)
+    let (mut writer, mut reader) = pipe.split();
+    assert_eq!(reader.read(&mut [0; 4]).unwrap_err().kind(), ErrorKind::WouldBlock);
+    writer.write_all(b"abcd").unwrap();
+}
+
+#[test]
+fn all_call_orders_until_eof() {
+    const DATA: &[u8] = b"abcdefg";
+    let mut count = 0;
+    // (5 writes & then the drop of the writer, against 6 reads)
+    for_each_call_order(6, 6, &mut |order| {
+        let mut pipe: Pipe<3> = Pipe::new();
+        let (writer, mut reader) = pipe.split();
+        let mut writer = Some(writer);
+        let (mut written, mut read, mut writer_steps) = (0, 0, 0);
+        let mut buf = [0; 2];
+        for &step in order {
+            if step {
+                writer_steps += 1;
+                if writer_steps == 6 {
+                    writer = None;
+                    continue;
+                }
+                let writer = writer.as_mut().unwrap();
+                let remaining = &DATA[written..][..DATA.len().min(written + 2) - written];
+                match writer.write(remaining) {
+                    Ok(n) => {
+                        assert_eq!(n, remaining.len().min(3 - (written - read)));
+                        written += n;
+                    }
+                    Err(e) => {
+                        assert_eq!(e.kind(), ErrorKind::WouldBlock);
+                        assert_eq!(written - read, 3);
+                    }
+                }
+                continue;
+            }
+            // (alternately with `read` & with `fill_buf`)
+            let result = if read % 2 == 0 {
+                reader.read(&mut buf)
+            } else {
+                reader.fill_buf().map(|available| {
+                    let n = available.len().min(1);
+                    buf[..n].copy_from_slice(&available[..n]);
+                    n
+                })
+            };
+            match result {
+                Ok(0) => assert!(writer.is_none() && read == written),
+                Ok(n) => {
+                    assert_eq!(&buf[..n], &DATA[read..][..n]);
+                    if read % 2 == 1 {
+                        reader.consume(n);
+                    }
+                    read += n;
+                }
+                Err(e) => {
+                    assert_eq!(e.kind(), ErrorKind::WouldBlock);
+                    assert!(writer.is_some() && read == written);
+                }
+            }
+        }
+        assert_eq!(reader.available(), written - read);
+        let mut rest = [0; 8];
+        let n = reader.read(&mut rest).unwrap();
+        assert_eq!(&rest[..n], &DATA[read..written]);
+        assert_eq!(reader.read(&mut rest).unwrap(), 0);
+        count += 1;
+    });
+    assert_eq!(count, 924);
+}
+
+#[test]
+fn all_call_orders_until_broken_pipe() {
+    let mut count = 0;
+    // (4 writes, against 2 reads & then the drop of the reader)
+    for_each_call_order(4, 3, &mut |order| {
+        let mut pipe: Pipe<2> = Pipe::new();
+        let (mut writer, reader) = pipe.split();
+        let mut reader = Some(reader);
+        let (mut pending, mut reader_steps) = (0, 0);
+        for &step in order {
+            if step {
+                match writer.write(b"ab") {
+                    Ok(n) => {
+                        assert!(reader.is_some());
+                        assert_eq!(n, 2 - pending);
+                        pending += n;
+                    }
+                    Err(e) if e.kind() == ErrorKind::BrokenPipe => assert!(reader.is_none()),
+                    Err(e) => {
+                        assert_eq!(e.kind(), ErrorKind::WouldBlock);
+                        assert!(reader.is_some() && pending == 2);
+                    }
+                }
+                continue;
+            }
+            reader_steps += 1;
+            if reader_steps == 3 {
+                reader = None;
+                continue;
+            }
+            match reader.as_mut().unwrap().read(&mut [0; 1]) {
+                Ok(n) => pending -= n,
+                Err(e) => assert!(e.kind() == ErrorKind::WouldBlock && pending == 0),
+            }
+        }
+        assert_eq!(writer.write(b"a").unwrap_err().kind(), ErrorKind::BrokenPipe);
+        count += 1;
+    });
+    assert_eq!(count, 35);
+}
+
+#[cfg(feature = "std")]
+mod threads {
+    extern crate alloc;
+    extern crate std;
+    use alloc::vec::Vec;
+    use std::sync::mpsc::{self, RecvTimeoutError};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::super::{Pipe, pipe};
+    use crate::{BufRead, ErrorKind, Read, Write};
+
+    fn data() -> Vec<u8> {
+        (0..20_000).map(|i| (i % 251) as u8).collect()
+    }
+
+    // Runs `f` on another thread, failing if it does not finish in time, as
+    // when a blocked half of the pipe is never woken up
+    fn with_timeout(f: impl FnOnce() + Send + 'static) {
+        let (done, finished) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            f();
+            let _ = done.send(());
+        });
+        if let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(Duration::from_secs(60)) {
+            panic!("a blocked half of the pipe was not woken up");
+        }
+        handle.join().unwrap();
+    }
+
+    // Sends `len` bytes through a blocking pipe of `N` bytes, so that both
+    // halves wait for each other many times, checking the order of the bytes
+    // as they are read with `read` & with `fill_buf`, in steps of varying sizes
+    fn transfer<const N: usize>(len: usize, seed: usize) {
+        let (mut writer, mut reader) = pipe::<N>();
+        writer.set_nonblocking(false);
+        reader.set_nonblocking(false);
+        let sender = thread::spawn(move || {
+            for part in data()[..len].chunks(seed % 4 + 1) {
+                writer.write_all(part).unwrap();
+            }
+        });
+        let data = data();
+        let mut read = 0;
+        for step in seed.. {
+            let mut buf = [0; 3];
+            let n = if step % 2 == 0 {
+                reader.read(&mut buf[..step % 3 + 1]).unwrap()
+            } else {
+                let available = reader.fill_buf().unwrap();
+                let n = available.len().min(step % 3 + 1);
+                buf[..n].copy_from_slice(&available[..n]);
+                reader.consume(n);
+                n
+            };
+            if n == 0 {
+                break;
+            }
+            assert_eq!(&buf[..n], &data[read..][..n]);
+            read += n;
+        }
+        sender.join().unwrap();
+        assert_eq!(read, len);
+    }
+
+    #[test]
+    fn blocking_halves_do_not_miss_wakeups() {
+        with_timeout(|| {
+            for seed in 0..500 {
+                transfer::<1>(64, seed);
+                transfer::<3>(64, seed);
+            }
+        });
+    }
+
+    #[test]
+    fn blocking_writer_wakes_up_on_drop_of_reader() {
+        with_timeout(|| {
+            for seed in 0..500 {
+                let (mut writer, mut reader) = pipe::<1>();
+                writer.set_nonblocking(false);
+                reader.set_nonblocking(false);
+                let sender = thread::spawn(move || writer.write_all(&data()));
+                reader.read_exact(&mut [0; 8][..seed % 8]).unwrap();
+                drop(reader);
+                assert_eq!(sender.join().unwrap().unwrap_err().kind(), ErrorKind::BrokenPipe);
+            }
+        });
+    }
+
+    #[test]
+    fn blocking_halves() {
+        for _ in 0..10 {
+            let (mut writer, mut reader) = pipe::<7>();
+            writer.set_nonblocking(false);
+            reader.set_nonblocking(false);
+            let sender = thread::spawn(move || {
+                let data = data();
+                // (in chunks of varying sizes, so that the bytes wrap around at different places)
+                for (i, chunk) in data.chunks(1000).enumerate() {
+                    for part in chunk.chunks(i % 9 + 1) {
+                        writer.write_all(part).unwrap();
+                    }
+                }
+            });
+            let mut received = Vec::new();
+            reader.read_to_end(&mut received).unwrap();
+            sender.join().unwrap();
+            assert_eq!(received, data());
+        }
+    }
+
+    #[test]
+    fn blocking_writer_gets_broken_pipe() {
+        let (mut writer, mut reader) = pipe::<4>();
+        writer.set_nonblocking(false);
+        reader.set_nonblocking(false);
+        let sender = thread::spawn(move || writer.write_all(&data()));
+        let mut buf = [0; 3];
+        reader.read_exact(&mut buf).unwrap();
+        drop(reader);
+        assert_eq!(sender.join().unwrap().unwrap_err().kind(), ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn nonblocking_borrowed_halves() {
+        let mut pipe: Pipe<5> = Pipe::new();
+        let (mut writer, mut reader) = pipe.split();
+        let mut received = Vec::new();
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                let data = data();
+                let mut written = 0;
+                while written < data.len() {
+                    match writer.write(&data[written..]) {
+                        Ok(n) => written += n,
+                        Err(e) if e.kind() == ErrorKind::WouldBlock => thread::yield_now(),
+                        Err(e) => panic!("{}", e),
+                    }
+                }
+            });
+            let mut buf = [0; 3];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => received.extend_from_slice(&buf[..n]),
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => thread::yield_now(),
+                    Err(e) => panic!("{}", e),
+                }
+            }
+        });
+        assert_eq!(received, data());
+    }
+}
@@ -255,4 +255,14 @@ impl<'a> ReadBuf<'a> {
     pub fn initialized_len(&self) -> usize {
         self.initialized
     }
+
+    // (ADDED in this fork) Returns the initialized part of the buffer, for the
+    // whole lifetime of the buffer, as used by `UninitCursor`
+    #[inline]
+    pub(crate) fn into_initialized(self) -> &'a mut [u8] {
+        let initialized = self.initialized;
+        let buf = self.buf;
+        // SAFETY: the first `initialized` bytes of the buffer are initialized
+        unsafe { slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, initialized) }
+    }
 }
@@ -0,0 +1,318 @@
+// (ADDED in this fork) a circular buffer of bytes, written by a producer &
+// read by a consumer, as used by interrupt-driven drivers
+
+#[cfg(test)]
+mod tests;
+
+use core::cmp;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+
+use crate::{BufRead, Error, ErrorKind, IoSlice, IoSliceMut, Read, ReadBuf, Result, Write};
+
+/// A circular buffer of bytes, which implements [`Write`] for the producer &
+/// [`Read`] & [`BufRead`] for the consumer.
+///
+/// The storage is any type that can be viewed as a slice of bytes, for example:
+///
+/// - `Box<[u8]>` - heap storage (used by default), from
+///   [`with_capacity`](RingBuffer::with_capacity)
+/// - `[u8; N]` - inline storage, with no use of `alloc`, from
+///   [`with_array`](RingBuffer::with_array)
+/// - `&mut [u8]` - storage owned by the caller
+///
+/// By default, reading from an empty buffer returns `Ok(0)`, like at EOF, &
+/// writing to a full buffer returns `Ok(0)`, so that
+/// [`write_all`](Write::write_all) fails with [`ErrorKind::WriteZero`]. In
+/// non-blocking mode (see [`set_nonblocking`](RingBuffer::set_nonblocking)),
+/// an error of the kind [`ErrorKind::WouldBlock`] is returned instead.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{ErrorKind, Read, RingBuffer, Write};
+///
+/// let mut ring: RingBuffer<[u8; 8]> = RingBuffer::with_array();
+/// ring.set_nonblocking(true);
+/// ring.write_all(b"some").unwrap();
+///
+/// let mut buf = [0; 8];
+/// assert_eq!(ring.read(&mut buf[..2]).unwrap(), 2);
+/// assert_eq!(&buf[..2], b"so");
+///
+/// // (the bytes wrap around the end of the storage)
+/// assert_eq!(ring.write(b"data!").unwrap(), 5);
+/// assert_eq!(ring.as_slices(), (&b"medata"[..], &b"!"[..]));
+///
+/// assert_eq!(ring.read(&mut buf).unwrap(), 7);
+/// assert_eq!(&buf[..7], b"medata!");
+/// assert_eq!(ring.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+/// ```
+pub struct RingBuffer<#[cfg(feature = "alloc")] B = Box<[u8]>, #[cfg(not(feature = "alloc"))] B> {
+    buf: B,
+    // (the index of the first byte to be read)
+    head: usize,
+    len: usize,
+    nonblocking: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl RingBuffer {
+    /// Creates a new `RingBuffer` with heap storage of the given capacity.
+    ///
+    /// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+    /// <div class="warning">REQUIRES feature to be enabled: <code>alloc</code></div>
+    pub fn with_capacity(capacity: usize) -> RingBuffer {
+        RingBuffer::with_buffer(vec![0; capacity].into_boxed_slice())
+    }
+}
+
+impl<const N: usize> RingBuffer<[u8; N]> {
+    /// Creates a new `RingBuffer` with inline storage of `N` bytes.
+    ///
+    /// This does not use the `alloc` crate, & can be used to initialize a
+    /// `static`.
+    pub const fn with_array() -> RingBuffer<[u8; N]> {
+        RingBuffer { buf: [0; N], head: 0, len: 0, nonblocking: false }
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> RingBuffer<B> {
+    /// Creates a new `RingBuffer` that uses the given storage, which is empty
+    /// whatever the contents of the storage.
+    pub fn with_buffer(buf: B) -> RingBuffer<B> {
+        RingBuffer { buf, head: 0, len: 0, nonblocking: false }
+    }
+
+    /// Sets whether reading from an empty buffer & writing to a full buffer
+    /// return an error of the kind [`ErrorKind::WouldBlock`].
+    pub fn set_nonblocking(&mut self, nonblocking: bool) {
+        self.nonblocking = nonblocking;
+    }
+
+    /// Returns whether this `RingBuffer` is in non-blocking mode.
+    pub fn is_nonblocking(&self) -> bool {
+        self.nonblocking
+    }
+
+    /// Returns the number of bytes this `RingBuffer` can hold.
+    pub fn capacity(&self) -> usize {
+        self.buf.as_ref().len()
+    }
+
+    /// Returns the number of bytes in this `RingBuffer`, to be read.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no bytes to be read.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if there is no room to write more bytes.
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Discards all of the bytes in this `RingBuffer`.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Returns the bytes to be read, as two slices, the second of which is
+    /// empty unless the bytes wrap around the end of the storage.
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        let buf = self.buf.as_ref();
+        let first_len = cmp::min(self.len, buf.len() - self.head);
+        (&buf[self.head..][..first_len], &buf[..self.len - first_len])
+    }
+
+    /// Returns the bytes to be read as two [`IoSlice`]s, for example to write
+    /// them to another writer with [`write_vectored`](Write::write_vectored).
+    ///
+    /// The bytes that were written that way can then be discarded with
+    /// [`consume`](BufRead::consume).
+    ///
+    /// # Example code
+    ///
+    /// ```
+    /// use portable_io::{BufRead, RingBuffer, Write};
+    ///
+    /// let mut ring: RingBuffer<[u8; 16]> = RingBuffer::with_array();
+    /// ring.write_all(b"some data").unwrap();
+    ///
+    /// let mut storage = [0; 16];
+    /// let n = (&mut storage[..]).write_vectored(&ring.as_io_slices()).unwrap();
+    /// ring.consume(n);
+    /// assert!(ring.is_empty());
+    /// ```
+    pub fn as_io_slices(&self) -> [IoSlice<'_>; 2] {
+        let (first, second) = self.as_slices();
+        [IoSlice::new(first), IoSlice::new(second)]
+    }
+
+    /// Gets a reference to the storage.
+    pub fn get_ref(&self) -> &B {
+        &self.buf
+    }
+
+    /// Unwraps this `RingBuffer`, returning the storage.
+    ///
+    /// Note that the bytes to be read are not at the start of the storage,
+    /// unless [`make_contiguous`](RingBuffer::make_contiguous) is called first.
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+
+    /// Moves the bytes to be read to the start of the storage, & returns them
+    /// as a single slice.
+    pub fn make_contiguous(&mut self) -> &mut [u8] {
+        let head = self.head;
+        let buf = self.buf.as_mut();
+        buf.rotate_left(head);
+        self.head = 0;
+        &mut buf[..self.len]
+    }
+
+    fn would_block(&self) -> Result<usize> {
+        if self.nonblocking {
+            Err(Error::new_const(ErrorKind::WouldBlock, &"ring buffer is empty or full"))
+        } else {
+            Ok(0)
+        }
+    }
+
+    // Returns the free space as two slices, the first of which starts at the end of the bytes
+    fn free_slices(&mut self) -> (&mut [u8], &mut [u8]) {
+        let (head, len) = (self.head, self.len);
+        let buf = self.buf.as_mut();
+        let capacity = buf.len();
+        if head + len < capacity {
+            let (before, after) = buf.split_at_mut(head + len);
+            (after, &mut before[..head])
+        } else {
+            let tail = head + len - capacity;
+            (&mut buf[tail..head], &mut [])
+        }
+    }
+
+    fn consume_bytes(&mut self, amt: usize) {
+        let amt = cmp::min(amt, self.len);
+        self.len -= amt;
+        // (back to the start of the storage when empty, for longer contiguous slices)
+        self.head = if self.len == 0 { 0 } else { (self.head + amt) % self.capacity() };
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Read for RingBuffer<B> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.is_empty() && !buf.is_empty() {
+            return self.would_block();
+        }
+        let (first, second) = self.as_slices();
+        let first_len = cmp::min(first.len(), buf.len());
+        buf[..first_len].copy_from_slice(&first[..first_len]);
+        let second_len = cmp::min(second.len(), buf.len() - first_len);
+        buf[first_len..][..second_len].copy_from_slice(&second[..second_len]);
+        self.consume_bytes(first_len + second_len);
+        Ok(first_len + second_len)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        if self.is_empty() && bufs.iter().any(|b| !b.is_empty()) {
+            return self.would_block();
+        }
+        let mut n = 0;
+        for buf in bufs {
+            if self.is_empty() {
+                break;
+            }
+            n += self.read(buf)?;
+        }
+        Ok(n)
+    }
+
+    fn is_read_vectored(&self) -> bool {
+        true
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> Result<()> {
+        if self.is_empty() && buf.remaining() > 0 {
+            return self.would_block().map(drop);
+        }
+        let (first, second) = self.as_slices();
+        let first_len = cmp::min(first.len(), buf.remaining());
+        buf.append(&first[..first_len]);
+        let second_len = cmp::min(second.len(), buf.remaining());
+        buf.append(&second[..second_len]);
+        self.consume_bytes(first_len + second_len);
+        Ok(())
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> BufRead for RingBuffer<B> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.is_empty() {
+            self.would_block()?;
+        }
+        Ok(self.as_slices().0)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.consume_bytes(amt)
+    }
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Write for RingBuffer<B> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if self.is_full() && !buf.is_empty() {
+            return self.would_block();
+        }
+        let (first, second) = self.free_slices();
+        let first_len = cmp::min(first.len(), buf.len());
+        first[..first_len].copy_from_slice(&buf[..first_len]);
+        let second_len = cmp::min(second.len(), buf.len() - first_len);
+        second[..second_len].copy_from_slice(&buf[first_len..][..second_len]);
+        self.len += first_len + second_len;
+        Ok(first_len + second_len)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        if self.is_full() && bufs.iter().any(|b| !b.is_empty()) {
+            return self.would_block();
+        }
+        let mut n = 0;
+        for buf in bufs {
+            if self.is_full() {
+                break;
+            }
+            n += self.write(buf)?;
+        }
+        Ok(n)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<B: AsRef<[u8]>> fmt::Debug for RingBuffer<B> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("RingBuffer")
+            .field("buffer", &format_args!("{}/{}", self.len, self.buf.as_ref().len()))
+            .field("nonblocking", &self.nonblocking)
+            .finish()
+    }
+}
@@ -0,0 +1,113 @@
+use super::RingBuffer;
+use crate::{BufRead, ErrorKind, IoSlice, IoSliceMut, Read, ReadBuf, Write};
+
+// Returns a ring buffer of 8 bytes holding `b"abcdef"`, which wraps around the
+// end of the storage after the first 4 bytes
+fn wrapped() -> RingBuffer<[u8; 8]> {
+    let mut ring = RingBuffer::with_array();
+    ring.write_all(b"xxxxab").unwrap();
+    ring.read_exact(&mut [0; 4]).unwrap();
+    ring.write_all(b"cdef").unwrap();
+    assert_eq!(ring.as_slices(), (&b"abcd"[..], &b"ef"[..]));
+    ring
+}
+
+#[test]
+fn write_and_read_around_the_end() {
+    let mut ring = wrapped();
+    assert_eq!((ring.len(), ring.capacity()), (6, 8));
+    assert_eq!(ring.write(b"ghijk").unwrap(), 2);
+    assert!(ring.is_full());
+    assert_eq!(ring.write(b"k").unwrap(), 0);
+    assert_eq!(ring.write_all(b"k").unwrap_err().kind(), ErrorKind::WriteZero);
+
+    let mut buf = [0; 16];
+    assert_eq!(ring.read(&mut buf).unwrap(), 8);
+    assert_eq!(&buf[..8], b"abcdefgh");
+    assert!(ring.is_empty());
+    assert_eq!(ring.read(&mut buf).unwrap(), 0);
+
+    // (back to the start of the storage once empty)
+    ring.write_all(b"12345678").unwrap();
+    assert_eq!(ring.as_slices(), (&b"12345678"[..], &b""[..]));
+}
+
+#[test]
+fn nonblocking() {
+    let mut ring = RingBuffer::with_buffer([0; 4]);
+    assert!(!ring.is_nonblocking());
+    ring.set_nonblocking(true);
+
+    let mut buf = [0; 4];
+    assert_eq!(ring.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(ring.fill_buf().unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(ring.read(&mut []).unwrap(), 0);
+
+    assert_eq!(ring.write(b"abcdef").unwrap(), 4);
+    assert_eq!(ring.write(b"g").unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(ring.write(b"").unwrap(), 0);
+}
+
+#[test]
+fn vectored_io() {
+    let mut ring = wrapped();
+    let mut storage = [0; 16];
+    let mut writer = &mut storage[..];
+    assert_eq!(writer.write_vectored(&ring.as_io_slices()).unwrap(), 6);
+    ring.consume(6);
+    assert_eq!(&storage[..6], b"abcdef");
+    assert!(ring.is_empty());
+
+    let mut ring = wrapped();
+    let (mut first, mut second) = ([0; 5], [0; 5]);
+    let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+    assert_eq!(ring.read_vectored(&mut bufs).unwrap(), 6);
+    assert_eq!((&first, &second[..1]), (b"abcde", &b"f"[..]));
+
+    let bufs = [IoSlice::new(b"gh"), IoSlice::new(b""), IoSlice::new(b"ijk")];
+    assert_eq!(ring.write_vectored(&bufs).unwrap(), 5);
+    ring.set_nonblocking(true);
+    assert_eq!(ring.write_vectored(&bufs).unwrap(), 3);
+    assert_eq!(ring.write_vectored(&bufs).unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert_eq!(ring.make_contiguous(), b"ghijkghi");
+}
+
+#[test]
+fn buf_read_and_read_buf() {
+    let mut ring = wrapped();
+    assert_eq!(ring.fill_buf().unwrap(), b"abcd");
+    ring.consume(3);
+    assert_eq!(ring.fill_buf().unwrap(), b"d");
+    ring.consume(1);
+    assert_eq!(ring.fill_buf().unwrap(), b"ef");
+
+    let mut ring = wrapped();
+    let mut storage = [0; 8];
+    let mut buf = ReadBuf::new(&mut storage);
+    buf.append(b"xxx");
+    ring.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled(), b"xxxabcde");
+    assert_eq!(ring.as_slices(), (&b"f"[..], &b""[..]));
+    assert_eq!(ring.len(), 1);
+}
+
+#[test]
+fn zero_capacity() {
+    let mut ring = RingBuffer::with_buffer(&mut [][..]);
+    assert!(ring.is_empty() && ring.is_full());
+    assert_eq!(ring.write(b"a").unwrap(), 0);
+    assert_eq!(ring.read(&mut [0; 1]).unwrap(), 0);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn heap_storage() {
+    let mut ring = RingBuffer::with_capacity(3);
+    ring.write_all(b"abc").unwrap();
+    let mut buf = [0; 2];
+    ring.read_exact(&mut buf).unwrap();
+    ring.write_all(b"de").unwrap();
+    ring.clear();
+    assert!(ring.is_empty());
+    assert_eq!(ring.into_inner().len(), 3);
+}
@@ -0,0 +1,344 @@
+// (ADDED in this fork) adapters between the traits of this crate & the traits of `std::io`
+
+#[cfg(test)]
+mod tests;
+
+use core::array;
+use core::fmt;
+
+extern crate alloc;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+extern crate std;
+
+use crate::{BufRead, IoSlice, IoSliceMut, Read, ReadBuf, Result, Seek, SeekFrom, Write};
+
+// The number of buffers passed on by a single vectored read or write,
+// converted in an array on the stack. Vectored reads & writes may always
+// transfer less than the total length of the buffers.
+pub(crate) const MAX_BUFS: usize = 16;
+
+/// Adapts a reader or writer from `std::io` to the traits of this crate.
+///
+/// `FromStd<T>` implements [`Read`], [`Write`], [`Seek`] & [`BufRead`] of this
+/// crate if `T` implements the same trait from `std::io`, converting any
+/// [`std::io::Error`] into an [`Error`](crate::Error).
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{FromStd, Read};
+///
+/// let mut reader = FromStd::new(std::io::Cursor::new(b"hello"));
+/// let mut buffer = [0; 5];
+/// reader.read_exact(&mut buffer).unwrap();
+/// assert_eq!(&buffer, b"hello");
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>std</code></div>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FromStd<T> {
+    inner: T,
+}
+
+impl<T> FromStd<T> {
+    /// Creates a new `FromStd` that wraps the given reader or writer from `std::io`.
+    pub const fn new(inner: T) -> FromStd<T> {
+        FromStd { inner }
+    }
+
+    /// Gets a reference to the underlying reader or writer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader or writer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this `FromStd`, returning the underlying reader or writer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: std::io::Read> Read for FromStd<T> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        Ok(self.inner.read(buf)?)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        let mut std_bufs: [std::io::IoSliceMut<'_>; MAX_BUFS] =
+            array::from_fn(|_| std::io::IoSliceMut::new(&mut []));
+        let mut n = 0;
+        for (std_buf, buf) in std_bufs.iter_mut().zip(bufs.iter_mut()) {
+            *std_buf = std::io::IoSliceMut::new(buf);
+            n += 1;
+        }
+        Ok(self.inner.read_vectored(&mut std_bufs[..n])?)
+    }
+
+    // (the std `read_buf` is unstable, so the unfilled part is initialized
+    // before it is passed on to `read`)
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> Result<()> {
+        let n = self.inner.read(buf.initialize_unfilled())?;
+        buf.add_filled(n);
+        Ok(())
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        Ok(self.inner.read_exact(buf)?)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        Ok(self.inner.read_to_end(buf)?)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
+        Ok(self.inner.read_to_string(buf)?)
+    }
+}
+
+impl<T: std::io::BufRead> BufRead for FromStd<T> {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        Ok(self.inner.fill_buf()?)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+
+    #[inline]
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize> {
+        Ok(self.inner.read_until(byte, buf)?)
+    }
+
+    #[inline]
+    fn read_line(&mut self, buf: &mut String) -> Result<usize> {
+        Ok(self.inner.read_line(buf)?)
+    }
+}
+
+impl<T: std::io::Write> Write for FromStd<T> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        Ok(self.inner.write(buf)?)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+        let mut std_bufs: [std::io::IoSlice<'_>; MAX_BUFS] =
+            array::from_fn(|_| std::io::IoSlice::new(&[]));
+        let mut n = 0;
+        for (std_buf, buf) in std_bufs.iter_mut().zip(bufs.iter()) {
+            *std_buf = std::io::IoSlice::new(buf);
+            n += 1;
+        }
+        Ok(self.inner.write_vectored(&std_bufs[..n])?)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        Ok(self.inner.flush()?)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        Ok(self.inner.write_all(buf)?)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> Result<()> {
+        Ok(self.inner.write_fmt(fmt)?)
+    }
+}
+
+impl<T: std::io::Seek> Seek for FromStd<T> {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        Ok(self.inner.seek(to_std_seek_from(pos))?)
+    }
+
+    #[inline]
+    fn rewind(&mut self) -> Result<()> {
+        Ok(self.inner.rewind()?)
+    }
+
+    #[inline]
+    fn stream_position(&mut self) -> Result<u64> {
+        Ok(self.inner.stream_position()?)
+    }
+}
+
+/// Adapts a reader or writer of this crate to the traits of `std::io`.
+///
+/// `ToStd<T>` implements [`std::io::Read`], [`std::io::Write`],
+/// [`std::io::Seek`] & [`std::io::BufRead`] if `T` implements the same trait
+/// of this crate, converting any [`Error`](crate::Error) into a
+/// [`std::io::Error`].
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{Cursor, ToStd};
+///
+/// let mut writer = ToStd::new(Cursor::new(Vec::new()));
+/// std::io::copy(&mut &b"hello"[..], &mut writer).unwrap();
+/// assert_eq!(writer.get_ref().get_ref(), b"hello");
+/// ```
+///
+/// <!-- TODO: use Rust (nightly) doc_cfg feature to document feature & cfg option requirements (if possible) -->
+/// <div class="warning">REQUIRES feature to be enabled: <code>std</code></div>
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ToStd<T> {
+    inner: T,
+}
+
+impl<T> ToStd<T> {
+    /// Creates a new `ToStd` that wraps the given reader or writer of this crate.
+    pub const fn new(inner: T) -> ToStd<T> {
+        ToStd { inner }
+    }
+
+    /// Gets a reference to the underlying reader or writer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader or writer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this `ToStd`, returning the underlying reader or writer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Read> std::io::Read for ToStd<T> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(self.inner.read(buf)?)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> std::io::Result<usize> {
+        let mut our_bufs: [IoSliceMut<'_>; MAX_BUFS] = array::from_fn(|_| IoSliceMut::new(&mut []));
+        let mut n = 0;
+        for (our_buf, buf) in our_bufs.iter_mut().zip(bufs.iter_mut()) {
+            *our_buf = IoSliceMut::new(buf);
+            n += 1;
+        }
+        Ok(self.inner.read_vectored(&mut our_bufs[..n])?)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+        Ok(self.inner.read_exact(buf)?)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> std::io::Result<usize> {
+        Ok(self.inner.read_to_end(buf)?)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> std::io::Result<usize> {
+        Ok(self.inner.read_to_string(buf)?)
+    }
+}
+
+impl<T: BufRead> std::io::BufRead for ToStd<T> {
+    #[inline]
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.inner.fill_buf()?)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+
+    #[inline]
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> std::io::Result<usize> {
+        Ok(self.inner.read_until(byte, buf)?)
+    }
+
+    #[inline]
+    fn read_line(&mut self, buf: &mut String) -> std::io::Result<usize> {
+        Ok(self.inner.read_line(buf)?)
+    }
+}
+
+impl<T: Write> std::io::Write for ToStd<T> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(self.inner.write(buf)?)
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        let mut our_bufs: [IoSlice<'_>; MAX_BUFS] = array::from_fn(|_| IoSlice::new(&[]));
+        let mut n = 0;
+        for (our_buf, buf) in our_bufs.iter_mut().zip(bufs.iter()) {
+            *our_buf = IoSlice::new(buf);
+            n += 1;
+        }
+        Ok(self.inner.write_vectored(&our_bufs[..n])?)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(self.inner.flush()?)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        Ok(self.inner.write_all(buf)?)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> std::io::Result<()> {
+        Ok(self.inner.write_fmt(fmt)?)
+    }
+}
+
+impl<T: Seek> std::io::Seek for ToStd<T> {
+    #[inline]
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        Ok(self.inner.seek(from_std_seek_from(pos))?)
+    }
+
+    #[inline]
+    fn rewind(&mut self) -> std::io::Result<()> {
+        Ok(self.inner.rewind()?)
+    }
+
+    #[inline]
+    fn stream_position(&mut self) -> std::io::Result<u64> {
+        Ok(self.inner.stream_position()?)
+    }
+}
+
+pub(crate) fn to_std_seek_from(pos: SeekFrom) -> std::io::SeekFrom {
+    match pos {
+        SeekFrom::Start(n) => std::io::SeekFrom::Start(n),
+        SeekFrom::End(n) => std::io::SeekFrom::End(n),
+        SeekFrom::Current(n) => std::io::SeekFrom::Current(n),
+    }
+}
+
+pub(crate) fn from_std_seek_from(pos: std::io::SeekFrom) -> SeekFrom {
+    match pos {
+        std::io::SeekFrom::Start(n) => SeekFrom::Start(n),
+        std::io::SeekFrom::End(n) => SeekFrom::End(n),
+        std::io::SeekFrom::Current(n) => SeekFrom::Current(n),
+    }
+}
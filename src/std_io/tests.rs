@@ -0,0 +1,286 @@
+use core::fmt;
+
+extern crate alloc;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+extern crate std;
+use std::io::{BufRead as _, Read as _, Seek as _, Write as _};
+
+use super::{FromStd, ToStd};
+use crate::{
+    BufRead, Cursor, Error, ErrorKind, IoSlice, IoSliceMut, Read, ReadBuf, Seek, SeekFrom, Write,
+};
+
+#[test]
+fn from_std_read() {
+    let mut reader = FromStd::new(std::io::Cursor::new(b"hello world\nbye".to_vec()));
+
+    let mut buf = [0; 5];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    let mut buf1 = [0; 1];
+    let mut buf2 = [0; 2];
+    let mut bufs = [IoSliceMut::new(&mut buf1), IoSliceMut::new(&mut buf2)];
+    assert_eq!(reader.read_vectored(&mut bufs).unwrap(), 3);
+    assert_eq!(&buf1, b" ");
+    assert_eq!(&buf2, b"wo");
+
+    let mut storage = [0; 3];
+    let mut buf = ReadBuf::new(&mut storage);
+    reader.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled(), b"rld");
+
+    let mut line = String::new();
+    assert_eq!(reader.read_line(&mut line).unwrap(), 1);
+    assert_eq!(line, "\n");
+
+    let mut rest = Vec::new();
+    assert_eq!(reader.read_to_end(&mut rest).unwrap(), 3);
+    assert_eq!(rest, b"bye");
+}
+
+#[test]
+fn from_std_write_seek() {
+    let mut writer = FromStd::new(std::io::Cursor::new(Vec::new()));
+
+    writer.write_all(b"hello").unwrap();
+    let bufs = [IoSlice::new(b" "), IoSlice::new(b"world")];
+    assert_eq!(writer.write_vectored(&bufs).unwrap(), 6);
+    write!(writer, "{}", 42).unwrap();
+    writer.flush().unwrap();
+
+    assert_eq!(writer.seek(SeekFrom::Start(6)).unwrap(), 6);
+    writer.write_all(b"W").unwrap();
+    assert_eq!(writer.stream_position().unwrap(), 7);
+    writer.rewind().unwrap();
+    assert_eq!(writer.seek(SeekFrom::End(-2)).unwrap(), 11);
+
+    assert_eq!(writer.into_inner().into_inner(), b"hello World42");
+}
+
+#[test]
+fn to_std_read() {
+    let mut reader = ToStd::new(Cursor::new(b"hello world\nbye".to_vec()));
+
+    let mut buf = [0; 5];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    let mut buf1 = [0; 1];
+    let mut buf2 = [0; 2];
+    let mut bufs = [std::io::IoSliceMut::new(&mut buf1), std::io::IoSliceMut::new(&mut buf2)];
+    assert_eq!(reader.read_vectored(&mut bufs).unwrap(), 3);
+    assert_eq!(&buf1, b" ");
+    assert_eq!(&buf2, b"wo");
+
+    let mut line = String::new();
+    assert_eq!(reader.read_line(&mut line).unwrap(), 4);
+    assert_eq!(line, "rld\n");
+
+    let mut rest = String::new();
+    assert_eq!(reader.read_to_string(&mut rest).unwrap(), 3);
+    assert_eq!(rest, "bye");
+}
+
+#[test]
+fn to_std_write_seek() {
+    let mut writer = ToStd::new(Cursor::new(Vec::new()));
+
+    writer.write_all(b"hello").unwrap();
+    let bufs = [std::io::IoSlice::new(b" "), std::io::IoSlice::new(b"world")];
+    assert_eq!(writer.write_vectored(&bufs).unwrap(), 6);
+    write!(writer, "{}", 42).unwrap();
+    writer.flush().unwrap();
+
+    assert_eq!(writer.seek(std::io::SeekFrom::Start(6)).unwrap(), 6);
+    writer.write_all(b"W").unwrap();
+    assert_eq!(writer.stream_position().unwrap(), 7);
+
+    assert_eq!(writer.into_inner().into_inner(), b"hello World42");
+}
+
+#[test]
+fn round_trip_through_std() {
+    let data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+    let mut reader = FromStd::new(ToStd::new(&data[..]));
+    let mut writer = FromStd::new(ToStd::new(Vec::new()));
+
+    assert_eq!(crate::copy(&mut reader, &mut writer).unwrap(), 10_000);
+    assert_eq!(writer.into_inner().into_inner(), data);
+}
+
+#[test]
+fn vectored_with_many_buffers() {
+    let data = [7; 64];
+    let mut reader = FromStd::new(&data[..]);
+    let mut storage = [[0; 2]; 32];
+    let mut bufs: Vec<IoSliceMut<'_>> = storage.iter_mut().map(|b| IoSliceMut::new(b)).collect();
+
+    // (only some of the buffers are passed on, which is allowed for a vectored read)
+    let n = reader.read_vectored(&mut bufs).unwrap();
+    assert!(n > 0 && n <= 64);
+    assert!(storage.iter().flatten().take(n).all(|&b| b == 7));
+}
+
+#[derive(Debug)]
+struct TestError;
+
+impl fmt::Display for TestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("test error")
+    }
+}
+
+impl core::error::Error for TestError {}
+
+#[test]
+fn error_kind_conversions() {
+    assert_eq!(std::io::ErrorKind::from(ErrorKind::NotFound), std::io::ErrorKind::NotFound);
+    assert_eq!(
+        std::io::ErrorKind::from(ErrorKind::UnexpectedEof),
+        std::io::ErrorKind::UnexpectedEof
+    );
+    assert_eq!(std::io::ErrorKind::from(ErrorKind::FilesystemLoop), std::io::ErrorKind::Other);
+    assert_eq!(ErrorKind::from(std::io::ErrorKind::WouldBlock), ErrorKind::WouldBlock);
+    assert_eq!(ErrorKind::from(std::io::ErrorKind::Other), ErrorKind::Other);
+}
+
+#[test]
+fn error_from_std() {
+    let err = Error::from(std::io::Error::new(std::io::ErrorKind::InvalidData, TestError));
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.get_ref().unwrap().is::<TestError>());
+
+    let err = Error::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    assert_eq!(err.to_string(), std::io::Error::from(std::io::ErrorKind::TimedOut).to_string());
+}
+
+#[test]
+fn error_to_std() {
+    let err = std::io::Error::from(Error::new(ErrorKind::InvalidData, TestError));
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.get_ref().unwrap().is::<TestError>());
+
+    let err = std::io::Error::from(Error::from(ErrorKind::BrokenPipe));
+    assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+    assert!(err.get_ref().is_none());
+
+    let err = std::io::Error::from(Error::new_const_with_code(ErrorKind::Other, 7, &"oh no"));
+    assert_eq!(err.kind(), std::io::ErrorKind::Other);
+    assert_eq!(err.to_string(), "oh no (code 7)");
+}
+
+#[test]
+fn error_round_trip() {
+    let err = Error::new_const_with_code(ErrorKind::InvalidInput, 7, &"oh no");
+    let err = Error::from(std::io::Error::from(err));
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(err.code(), Some(7));
+
+    #[allow(clippy::io_other_error)] // (`Error::other` is not in the nightly MSRV)
+    let err = std::io::Error::new(std::io::ErrorKind::Other, "from std");
+    let err = std::io::Error::from(Error::from(err));
+    assert_eq!(err.kind(), std::io::ErrorKind::Other);
+    assert_eq!(err.to_string(), "from std");
+    assert!(err.get_ref().unwrap().downcast_ref::<std::io::Error>().is_none());
+}
+
+#[test]
+fn error_round_trip_keeps_every_kind() {
+    let kinds = [
+        ErrorKind::NotFound,
+        ErrorKind::PermissionDenied,
+        ErrorKind::ConnectionRefused,
+        ErrorKind::ConnectionReset,
+        ErrorKind::HostUnreachable,
+        ErrorKind::NetworkUnreachable,
+        ErrorKind::ConnectionAborted,
+        ErrorKind::NotConnected,
+        ErrorKind::AddrInUse,
+        ErrorKind::AddrNotAvailable,
+        ErrorKind::NetworkDown,
+        ErrorKind::BrokenPipe,
+        ErrorKind::AlreadyExists,
+        ErrorKind::WouldBlock,
+        ErrorKind::NotADirectory,
+        ErrorKind::IsADirectory,
+        ErrorKind::DirectoryNotEmpty,
+        ErrorKind::ReadOnlyFilesystem,
+        ErrorKind::FilesystemLoop,
+        ErrorKind::StaleNetworkFileHandle,
+        ErrorKind::InvalidInput,
+        ErrorKind::InvalidData,
+        ErrorKind::TimedOut,
+        ErrorKind::WriteZero,
+        ErrorKind::StorageFull,
+        ErrorKind::NotSeekable,
+        ErrorKind::FilesystemQuotaExceeded,
+        ErrorKind::FileTooLarge,
+        ErrorKind::ResourceBusy,
+        ErrorKind::ExecutableFileBusy,
+        ErrorKind::Deadlock,
+        ErrorKind::CrossesDevices,
+        ErrorKind::TooManyLinks,
+        ErrorKind::FilenameTooLong,
+        ErrorKind::ArgumentListTooLong,
+        ErrorKind::Interrupted,
+        ErrorKind::Unsupported,
+        ErrorKind::UnexpectedEof,
+        ErrorKind::OutOfMemory,
+        ErrorKind::Other,
+        ErrorKind::Uncategorized,
+    ];
+    for kind in kinds {
+        let errors =
+            [Error::from(kind), Error::new(kind, "custom"), Error::new_const(kind, &"message")];
+        for err in errors {
+            let err = std::io::Error::from(err);
+            assert_eq!(err.kind(), std::io::ErrorKind::from(kind));
+            assert_eq!(Error::from(err).kind(), kind);
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "os-error"))]
+#[test]
+fn os_error_round_trip() {
+    let err = Error::from(std::io::Error::from_raw_os_error(2));
+    assert_eq!(err.raw_os_error(), Some(2));
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let err = std::io::Error::from(Error::from_raw_os_error(2));
+    assert_eq!(err.raw_os_error(), Some(2));
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+fn errors_pass_through_adapters() {
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> crate::Result<usize> {
+            Err(Error::new_const(ErrorKind::StorageFull, &"disk full"))
+        }
+
+        fn flush(&mut self) -> crate::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut writer = FromStd::new(ToStd::new(FailingWriter));
+    let err = writer.write_all(b"data").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::StorageFull);
+    assert_eq!(err.to_string(), "disk full");
+
+    let mut reader = ToStd::new(&b""[..]);
+    let err = reader.read_exact(&mut [0; 1]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    let mut v = vec![0; 0];
+    assert_eq!(ToStd::new(Cursor::new(&b"abc"[..])).read_to_end(&mut v).unwrap(), 3);
+    assert_eq!(v, b"abc");
+}
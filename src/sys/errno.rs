@@ -0,0 +1,333 @@
+// Built-in table of OS error codes, using the Linux (& POSIX) numbering.
+//
+// based on:
+// - https://github.com/rust-lang/rust/blob/1.59.0/library/std/src/sys/unix/mod.rs (`decode_error_kind`)
+// - https://github.com/torvalds/linux/blob/v5.15/include/uapi/asm-generic/errno-base.h
+// - https://github.com/torvalds/linux/blob/v5.15/include/uapi/asm-generic/errno.h
+//
+// Messages are the same as from `strerror` in the GNU C Library.
+
+use crate::ErrorKind;
+
+pub(crate) const EPERM: i32 = 1;
+pub(crate) const ENOENT: i32 = 2;
+pub(crate) const ESRCH: i32 = 3;
+pub(crate) const EINTR: i32 = 4;
+pub(crate) const EIO: i32 = 5;
+pub(crate) const ENXIO: i32 = 6;
+pub(crate) const E2BIG: i32 = 7;
+pub(crate) const ENOEXEC: i32 = 8;
+pub(crate) const EBADF: i32 = 9;
+pub(crate) const ECHILD: i32 = 10;
+pub(crate) const EAGAIN: i32 = 11;
+pub(crate) const ENOMEM: i32 = 12;
+pub(crate) const EACCES: i32 = 13;
+pub(crate) const EFAULT: i32 = 14;
+pub(crate) const ENOTBLK: i32 = 15;
+pub(crate) const EBUSY: i32 = 16;
+pub(crate) const EEXIST: i32 = 17;
+pub(crate) const EXDEV: i32 = 18;
+pub(crate) const ENODEV: i32 = 19;
+pub(crate) const ENOTDIR: i32 = 20;
+pub(crate) const EISDIR: i32 = 21;
+pub(crate) const EINVAL: i32 = 22;
+pub(crate) const ENFILE: i32 = 23;
+pub(crate) const EMFILE: i32 = 24;
+pub(crate) const ENOTTY: i32 = 25;
+pub(crate) const ETXTBSY: i32 = 26;
+pub(crate) const EFBIG: i32 = 27;
+pub(crate) const ENOSPC: i32 = 28;
+pub(crate) const ESPIPE: i32 = 29;
+pub(crate) const EROFS: i32 = 30;
+pub(crate) const EMLINK: i32 = 31;
+pub(crate) const EPIPE: i32 = 32;
+pub(crate) const EDOM: i32 = 33;
+pub(crate) const ERANGE: i32 = 34;
+pub(crate) const EDEADLK: i32 = 35;
+pub(crate) const ENAMETOOLONG: i32 = 36;
+pub(crate) const ENOLCK: i32 = 37;
+pub(crate) const ENOSYS: i32 = 38;
+pub(crate) const ENOTEMPTY: i32 = 39;
+pub(crate) const ELOOP: i32 = 40;
+pub(crate) const ENOMSG: i32 = 42;
+pub(crate) const EIDRM: i32 = 43;
+pub(crate) const ECHRNG: i32 = 44;
+pub(crate) const EL2NSYNC: i32 = 45;
+pub(crate) const EL3HLT: i32 = 46;
+pub(crate) const EL3RST: i32 = 47;
+pub(crate) const ELNRNG: i32 = 48;
+pub(crate) const EUNATCH: i32 = 49;
+pub(crate) const ENOCSI: i32 = 50;
+pub(crate) const EL2HLT: i32 = 51;
+pub(crate) const EBADE: i32 = 52;
+pub(crate) const EBADR: i32 = 53;
+pub(crate) const EXFULL: i32 = 54;
+pub(crate) const ENOANO: i32 = 55;
+pub(crate) const EBADRQC: i32 = 56;
+pub(crate) const EBADSLT: i32 = 57;
+pub(crate) const EBFONT: i32 = 59;
+pub(crate) const ENOSTR: i32 = 60;
+pub(crate) const ENODATA: i32 = 61;
+pub(crate) const ETIME: i32 = 62;
+pub(crate) const ENOSR: i32 = 63;
+pub(crate) const ENONET: i32 = 64;
+pub(crate) const ENOPKG: i32 = 65;
+pub(crate) const EREMOTE: i32 = 66;
+pub(crate) const ENOLINK: i32 = 67;
+pub(crate) const EADV: i32 = 68;
+pub(crate) const ESRMNT: i32 = 69;
+pub(crate) const ECOMM: i32 = 70;
+pub(crate) const EPROTO: i32 = 71;
+pub(crate) const EMULTIHOP: i32 = 72;
+pub(crate) const EDOTDOT: i32 = 73;
+pub(crate) const EBADMSG: i32 = 74;
+pub(crate) const EOVERFLOW: i32 = 75;
+pub(crate) const ENOTUNIQ: i32 = 76;
+pub(crate) const EBADFD: i32 = 77;
+pub(crate) const EREMCHG: i32 = 78;
+pub(crate) const ELIBACC: i32 = 79;
+pub(crate) const ELIBBAD: i32 = 80;
+pub(crate) const ELIBSCN: i32 = 81;
+pub(crate) const ELIBMAX: i32 = 82;
+pub(crate) const ELIBEXEC: i32 = 83;
+pub(crate) const EILSEQ: i32 = 84;
+pub(crate) const ERESTART: i32 = 85;
+pub(crate) const ESTRPIPE: i32 = 86;
+pub(crate) const EUSERS: i32 = 87;
+pub(crate) const ENOTSOCK: i32 = 88;
+pub(crate) const EDESTADDRREQ: i32 = 89;
+pub(crate) const EMSGSIZE: i32 = 90;
+pub(crate) const EPROTOTYPE: i32 = 91;
+pub(crate) const ENOPROTOOPT: i32 = 92;
+pub(crate) const EPROTONOSUPPORT: i32 = 93;
+pub(crate) const ESOCKTNOSUPPORT: i32 = 94;
+pub(crate) const EOPNOTSUPP: i32 = 95;
+pub(crate) const EPFNOSUPPORT: i32 = 96;
+pub(crate) const EAFNOSUPPORT: i32 = 97;
+pub(crate) const EADDRINUSE: i32 = 98;
+pub(crate) const EADDRNOTAVAIL: i32 = 99;
+pub(crate) const ENETDOWN: i32 = 100;
+pub(crate) const ENETUNREACH: i32 = 101;
+pub(crate) const ENETRESET: i32 = 102;
+pub(crate) const ECONNABORTED: i32 = 103;
+pub(crate) const ECONNRESET: i32 = 104;
+pub(crate) const ENOBUFS: i32 = 105;
+pub(crate) const EISCONN: i32 = 106;
+pub(crate) const ENOTCONN: i32 = 107;
+pub(crate) const ESHUTDOWN: i32 = 108;
+pub(crate) const ETOOMANYREFS: i32 = 109;
+pub(crate) const ETIMEDOUT: i32 = 110;
+pub(crate) const ECONNREFUSED: i32 = 111;
+pub(crate) const EHOSTDOWN: i32 = 112;
+pub(crate) const EHOSTUNREACH: i32 = 113;
+pub(crate) const EALREADY: i32 = 114;
+pub(crate) const EINPROGRESS: i32 = 115;
+pub(crate) const ESTALE: i32 = 116;
+pub(crate) const EUCLEAN: i32 = 117;
+pub(crate) const ENOTNAM: i32 = 118;
+pub(crate) const ENAVAIL: i32 = 119;
+pub(crate) const EISNAM: i32 = 120;
+pub(crate) const EREMOTEIO: i32 = 121;
+pub(crate) const EDQUOT: i32 = 122;
+pub(crate) const ENOMEDIUM: i32 = 123;
+pub(crate) const EMEDIUMTYPE: i32 = 124;
+pub(crate) const ECANCELED: i32 = 125;
+pub(crate) const ENOKEY: i32 = 126;
+pub(crate) const EKEYEXPIRED: i32 = 127;
+pub(crate) const EKEYREVOKED: i32 = 128;
+pub(crate) const EKEYREJECTED: i32 = 129;
+pub(crate) const EOWNERDEAD: i32 = 130;
+pub(crate) const ENOTRECOVERABLE: i32 = 131;
+pub(crate) const ERFKILL: i32 = 132;
+pub(crate) const EHWPOISON: i32 = 133;
+#[cfg_attr(all(unix, feature = "os-error-libc"), allow(dead_code))]
+pub(crate) const EWOULDBLOCK: i32 = EAGAIN;
+
+// (not used with `os-error-libc` on Unix, which decodes with the error constants from libc)
+#[cfg_attr(all(unix, feature = "os-error-libc"), allow(dead_code))]
+pub(crate) fn decode_error_kind(errno: i32) -> ErrorKind {
+    use ErrorKind::*;
+    match errno {
+        E2BIG => ArgumentListTooLong,
+        EADDRINUSE => AddrInUse,
+        EADDRNOTAVAIL => AddrNotAvailable,
+        EBUSY => ResourceBusy,
+        ECONNABORTED => ConnectionAborted,
+        ECONNREFUSED => ConnectionRefused,
+        ECONNRESET => ConnectionReset,
+        EDEADLK => Deadlock,
+        EDQUOT => FilesystemQuotaExceeded,
+        EEXIST => AlreadyExists,
+        EFBIG => FileTooLarge,
+        EHOSTUNREACH => HostUnreachable,
+        EINTR => Interrupted,
+        EINVAL => InvalidInput,
+        EISDIR => IsADirectory,
+        ELOOP => FilesystemLoop,
+        ENOENT => NotFound,
+        ENOMEM => OutOfMemory,
+        ENOSPC => StorageFull,
+        ENOSYS => Unsupported,
+        EMLINK => TooManyLinks,
+        ENAMETOOLONG => FilenameTooLong,
+        ENETDOWN => NetworkDown,
+        ENETUNREACH => NetworkUnreachable,
+        ENOTCONN => NotConnected,
+        ENOTDIR => NotADirectory,
+        ENOTEMPTY => DirectoryNotEmpty,
+        EPIPE => BrokenPipe,
+        EROFS => ReadOnlyFilesystem,
+        ESPIPE => NotSeekable,
+        ESTALE => StaleNetworkFileHandle,
+        ETIMEDOUT => TimedOut,
+        ETXTBSY => ExecutableFileBusy,
+        EXDEV => CrossesDevices,
+
+        EACCES | EPERM => PermissionDenied,
+
+        // These two constants can have the same value on some systems,
+        // but different values on others, so we can't use a match
+        // clause
+        x if x == EAGAIN || x == EWOULDBLOCK => WouldBlock,
+
+        _ => Uncategorized,
+    }
+}
+
+/// Gets a static description of an OS error code.
+pub(crate) fn error_string(errno: i32) -> &'static str {
+    match errno {
+        EPERM => "Operation not permitted",
+        ENOENT => "No such file or directory",
+        ESRCH => "No such process",
+        EINTR => "Interrupted system call",
+        EIO => "Input/output error",
+        ENXIO => "No such device or address",
+        E2BIG => "Argument list too long",
+        ENOEXEC => "Exec format error",
+        EBADF => "Bad file descriptor",
+        ECHILD => "No child processes",
+        EAGAIN => "Resource temporarily unavailable",
+        ENOMEM => "Cannot allocate memory",
+        EACCES => "Permission denied",
+        EFAULT => "Bad address",
+        ENOTBLK => "Block device required",
+        EBUSY => "Device or resource busy",
+        EEXIST => "File exists",
+        EXDEV => "Invalid cross-device link",
+        ENODEV => "No such device",
+        ENOTDIR => "Not a directory",
+        EISDIR => "Is a directory",
+        EINVAL => "Invalid argument",
+        ENFILE => "Too many open files in system",
+        EMFILE => "Too many open files",
+        ENOTTY => "Inappropriate ioctl for device",
+        ETXTBSY => "Text file busy",
+        EFBIG => "File too large",
+        ENOSPC => "No space left on device",
+        ESPIPE => "Illegal seek",
+        EROFS => "Read-only file system",
+        EMLINK => "Too many links",
+        EPIPE => "Broken pipe",
+        EDOM => "Numerical argument out of domain",
+        ERANGE => "Numerical result out of range",
+        EDEADLK => "Resource deadlock avoided",
+        ENAMETOOLONG => "File name too long",
+        ENOLCK => "No locks available",
+        ENOSYS => "Function not implemented",
+        ENOTEMPTY => "Directory not empty",
+        ELOOP => "Too many levels of symbolic links",
+        ENOMSG => "No message of desired type",
+        EIDRM => "Identifier removed",
+        ECHRNG => "Channel number out of range",
+        EL2NSYNC => "Level 2 not synchronized",
+        EL3HLT => "Level 3 halted",
+        EL3RST => "Level 3 reset",
+        ELNRNG => "Link number out of range",
+        EUNATCH => "Protocol driver not attached",
+        ENOCSI => "No CSI structure available",
+        EL2HLT => "Level 2 halted",
+        EBADE => "Invalid exchange",
+        EBADR => "Invalid request descriptor",
+        EXFULL => "Exchange full",
+        ENOANO => "No anode",
+        EBADRQC => "Invalid request code",
+        EBADSLT => "Invalid slot",
+        EBFONT => "Bad font file format",
+        ENOSTR => "Device not a stream",
+        ENODATA => "No data available",
+        ETIME => "Timer expired",
+        ENOSR => "Out of streams resources",
+        ENONET => "Machine is not on the network",
+        ENOPKG => "Package not installed",
+        EREMOTE => "Object is remote",
+        ENOLINK => "Link has been severed",
+        EADV => "Advertise error",
+        ESRMNT => "Srmount error",
+        ECOMM => "Communication error on send",
+        EPROTO => "Protocol error",
+        EMULTIHOP => "Multihop attempted",
+        EDOTDOT => "RFS specific error",
+        EBADMSG => "Bad message",
+        EOVERFLOW => "Value too large for defined data type",
+        ENOTUNIQ => "Name not unique on network",
+        EBADFD => "File descriptor in bad state",
+        EREMCHG => "Remote address changed",
+        ELIBACC => "Can not access a needed shared library",
+        ELIBBAD => "Accessing a corrupted shared library",
+        ELIBSCN => ".lib section in a.out corrupted",
+        ELIBMAX => "Attempting to link in too many shared libraries",
+        ELIBEXEC => "Cannot exec a shared library directly",
+        EILSEQ => "Invalid or incomplete multibyte or wide character",
+        ERESTART => "Interrupted system call should be restarted",
+        ESTRPIPE => "Streams pipe error",
+        EUSERS => "Too many users",
+        ENOTSOCK => "Socket operation on non-socket",
+        EDESTADDRREQ => "Destination address required",
+        EMSGSIZE => "Message too long",
+        EPROTOTYPE => "Protocol wrong type for socket",
+        ENOPROTOOPT => "Protocol not available",
+        EPROTONOSUPPORT => "Protocol not supported",
+        ESOCKTNOSUPPORT => "Socket type not supported",
+        EOPNOTSUPP => "Operation not supported",
+        EPFNOSUPPORT => "Protocol family not supported",
+        EAFNOSUPPORT => "Address family not supported by protocol",
+        EADDRINUSE => "Address already in use",
+        EADDRNOTAVAIL => "Cannot assign requested address",
+        ENETDOWN => "Network is down",
+        ENETUNREACH => "Network is unreachable",
+        ENETRESET => "Network dropped connection on reset",
+        ECONNABORTED => "Software caused connection abort",
+        ECONNRESET => "Connection reset by peer",
+        ENOBUFS => "No buffer space available",
+        EISCONN => "Transport endpoint is already connected",
+        ENOTCONN => "Transport endpoint is not connected",
+        ESHUTDOWN => "Cannot send after transport endpoint shutdown",
+        ETOOMANYREFS => "Too many references: cannot splice",
+        ETIMEDOUT => "Connection timed out",
+        ECONNREFUSED => "Connection refused",
+        EHOSTDOWN => "Host is down",
+        EHOSTUNREACH => "No route to host",
+        EALREADY => "Operation already in progress",
+        EINPROGRESS => "Operation now in progress",
+        ESTALE => "Stale file handle",
+        EUCLEAN => "Structure needs cleaning",
+        ENOTNAM => "Not a XENIX named type file",
+        ENAVAIL => "No XENIX semaphores available",
+        EISNAM => "Is a named type file",
+        EREMOTEIO => "Remote I/O error",
+        EDQUOT => "Disk quota exceeded",
+        ENOMEDIUM => "No medium found",
+        EMEDIUMTYPE => "Wrong medium type",
+        ECANCELED => "Operation canceled",
+        ENOKEY => "Required key not available",
+        EKEYEXPIRED => "Key has expired",
+        EKEYREVOKED => "Key has been revoked",
+        EKEYREJECTED => "Key was rejected by service",
+        EOWNERDEAD => "Owner died",
+        ENOTRECOVERABLE => "State not recoverable",
+        ERFKILL => "Operation not possible due to RF-kill",
+        EHWPOISON => "Memory page has hardware error",
+        _ => "Unknown error",
+    }
+}
@@ -1,3 +1,8 @@
+#[cfg(feature = "os-error")]
+pub(crate) mod errno;
+#[cfg(feature = "os-error")]
+pub(crate) mod os;
+
 #[cfg(not(feature = "unix-iovec"))]
 pub(crate) mod io_default;
 
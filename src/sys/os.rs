@@ -0,0 +1,182 @@
+// based on:
+// - https://github.com/rust-lang/rust/blob/1.59.0/library/std/src/sys/unix/os.rs
+// - https://github.com/rust-lang/rust/blob/1.59.0/library/std/src/sys/unix/mod.rs (`decode_error_kind`)
+// - https://github.com/rust-lang/rust/blob/1.59.0/library/std/src/sys/unsupported/os.rs
+
+#[cfg(not(all(unix, feature = "os-error-libc")))]
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::ErrorKind;
+
+// (ADDED in this fork, for platforms where errno is not read via libc)
+static LAST_OS_ERROR_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+pub(crate) fn set_last_os_error_hook(hook: fn() -> i32) {
+    LAST_OS_ERROR_HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+// (any other Unix platform is rejected with a compile error, in lib.rs)
+#[cfg(all(unix, feature = "os-error-libc"))]
+extern "C" {
+    #[cfg_attr(
+        any(
+            target_os = "linux",
+            target_os = "emscripten",
+            target_os = "fuchsia",
+            target_os = "l4re"
+        ),
+        link_name = "__errno_location"
+    )]
+    #[cfg_attr(
+        any(
+            target_os = "netbsd",
+            target_os = "openbsd",
+            target_os = "android",
+            target_os = "redox",
+            target_env = "newlib"
+        ),
+        link_name = "__errno"
+    )]
+    #[cfg_attr(any(target_os = "solaris", target_os = "illumos"), link_name = "___errno")]
+    #[cfg_attr(
+        any(target_os = "macos", target_os = "ios", target_os = "freebsd", target_os = "watchos"),
+        link_name = "__error"
+    )]
+    #[cfg_attr(target_os = "haiku", link_name = "_errnop")]
+    fn errno_location() -> *mut libc::c_int;
+}
+
+/// Returns the platform-specific value of errno
+#[cfg(all(unix, feature = "os-error-libc"))]
+pub(crate) fn errno() -> i32 {
+    unsafe { *errno_location() }
+}
+
+/// Returns the value from the registered hook, or 0 if there is no hook
+#[cfg(not(all(unix, feature = "os-error-libc")))]
+pub(crate) fn errno() -> i32 {
+    let hook = LAST_OS_ERROR_HOOK.load(Ordering::Acquire);
+    if hook.is_null() {
+        return 0;
+    }
+
+    // SAFETY: the only non-null value ever stored is a `fn() -> i32`
+    let hook = unsafe { mem::transmute::<*mut (), fn() -> i32>(hook) };
+    hook()
+}
+
+#[cfg(all(unix, feature = "os-error-libc"))]
+pub(crate) fn decode_error_kind(errno: i32) -> ErrorKind {
+    use ErrorKind::*;
+    match errno as libc::c_int {
+        libc::E2BIG => ArgumentListTooLong,
+        libc::EADDRINUSE => AddrInUse,
+        libc::EADDRNOTAVAIL => AddrNotAvailable,
+        libc::EBUSY => ResourceBusy,
+        libc::ECONNABORTED => ConnectionAborted,
+        libc::ECONNREFUSED => ConnectionRefused,
+        libc::ECONNRESET => ConnectionReset,
+        libc::EDEADLK => Deadlock,
+        libc::EDQUOT => FilesystemQuotaExceeded,
+        libc::EEXIST => AlreadyExists,
+        libc::EFBIG => FileTooLarge,
+        libc::EHOSTUNREACH => HostUnreachable,
+        libc::EINTR => Interrupted,
+        libc::EINVAL => InvalidInput,
+        libc::EISDIR => IsADirectory,
+        libc::ELOOP => FilesystemLoop,
+        libc::ENOENT => NotFound,
+        libc::ENOMEM => OutOfMemory,
+        libc::ENOSPC => StorageFull,
+        libc::ENOSYS => Unsupported,
+        libc::EMLINK => TooManyLinks,
+        libc::ENAMETOOLONG => FilenameTooLong,
+        libc::ENETDOWN => NetworkDown,
+        libc::ENETUNREACH => NetworkUnreachable,
+        libc::ENOTCONN => NotConnected,
+        libc::ENOTDIR => NotADirectory,
+        libc::ENOTEMPTY => DirectoryNotEmpty,
+        libc::EPIPE => BrokenPipe,
+        libc::EROFS => ReadOnlyFilesystem,
+        libc::ESPIPE => NotSeekable,
+        libc::ESTALE => StaleNetworkFileHandle,
+        libc::ETIMEDOUT => TimedOut,
+        libc::ETXTBSY => ExecutableFileBusy,
+        libc::EXDEV => CrossesDevices,
+
+        libc::EACCES | libc::EPERM => PermissionDenied,
+
+        // These two constants can have the same value on some systems,
+        // but different values on others, so we can't use a match
+        // clause
+        x if x == libc::EAGAIN || x == libc::EWOULDBLOCK => WouldBlock,
+
+        _ => Uncategorized,
+    }
+}
+
+#[cfg(not(all(unix, feature = "os-error-libc")))]
+pub(crate) fn decode_error_kind(errno: i32) -> ErrorKind {
+    super::errno::decode_error_kind(errno)
+}
+
+/// Calls `f` with a description of an OS error code, from `strerror_r`.
+///
+/// (ADAPTED to use a buffer on the stack, with the message from the built-in
+/// table if `strerror_r` fails, rather than allocating a `String`)
+///
+/// The built-in table has the error codes of Linux, so on other Unix platforms
+/// this is also used with the `os-error-libc` feature alone, for the messages
+/// to match the error kinds decoded with the constants from libc.
+#[cfg(any(
+    all(unix, feature = "os-error-strerror"),
+    all(unix, not(target_os = "linux"), feature = "os-error-libc")
+))]
+pub(crate) fn with_error_string<R>(errno: i32, f: impl FnOnce(&str) -> R) -> R {
+    use core::ffi::CStr;
+    use core::str;
+
+    use libc::{c_char, c_int, size_t};
+
+    const TMPBUF_SZ: usize = 128;
+
+    extern "C" {
+        #[cfg_attr(any(target_os = "linux", target_env = "newlib"), link_name = "__xpg_strerror_r")]
+        fn strerror_r(errnum: c_int, buf: *mut c_char, buflen: size_t) -> c_int;
+    }
+
+    let mut buf = [0 as c_char; TMPBUF_SZ];
+
+    unsafe {
+        if strerror_r(errno as c_int, buf.as_mut_ptr(), buf.len()) < 0 {
+            return f(builtin_error_string(errno));
+        }
+
+        let p = buf.as_ptr() as *const _;
+        match str::from_utf8(CStr::from_ptr(p).to_bytes()) {
+            Ok(message) => f(message),
+            Err(_) => f(builtin_error_string(errno)),
+        }
+    }
+}
+
+/// Returns the message for an OS error code from the built-in table, which
+/// only has the error codes of Linux.
+#[cfg(any(
+    all(unix, feature = "os-error-strerror"),
+    all(unix, not(target_os = "linux"), feature = "os-error-libc")
+))]
+fn builtin_error_string(errno: i32) -> &'static str {
+    if cfg!(target_os = "linux") { super::errno::error_string(errno) } else { "Unknown error" }
+}
+
+/// Calls `f` with a description of an OS error code, from the built-in table.
+#[cfg(not(any(
+    all(unix, feature = "os-error-strerror"),
+    all(unix, not(target_os = "linux"), feature = "os-error-libc")
+)))]
+pub(crate) fn with_error_string<R>(errno: i32, f: impl FnOnce(&str) -> R) -> R {
+    f(super::errno::error_string(errno))
+}
@@ -0,0 +1,329 @@
+// (ADDED in this fork) a reader that copies the bytes it reads to a writer, &
+// a writer that writes the same bytes to several writers
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::observe::{Unconsumed, read_buf_with};
+use crate::{BufRead, Error, IoSliceMut, Read, ReadBuf, Result, Write};
+
+/// Copies the bytes read from a reader to a writer, for example to log the raw
+/// bytes of a stream while it is parsed.
+///
+/// All of the ways to read are covered, including vectored reads &
+/// [`read_buf`](Read::read_buf). With [`BufRead`], the bytes are copied once
+/// they are consumed, rather than when they are returned by
+/// [`fill_buf`](BufRead::fill_buf).
+///
+/// The bytes are written with [`write_all`](Write::write_all), before they are
+/// returned. If that fails, the bytes that were read are returned all the
+/// same, so that none of them are lost, & the error is returned by the next
+/// call to read; the same goes for the bytes given to
+/// [`consume`](BufRead::consume). The copy misses the bytes that are read
+/// until the error is returned, after which copying resumes.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{Read, TeeReader};
+///
+/// let mut log = [0; 16];
+/// let mut reader = TeeReader::new(&b"some data"[..], &mut log[..]);
+/// let mut buf = [0; 4];
+/// reader.read_exact(&mut buf).unwrap();
+/// assert_eq!(&buf, b"some");
+/// assert_eq!(&log[..4], b"some");
+/// ```
+#[derive(Debug)]
+pub struct TeeReader<R, W> {
+    reader: R,
+    writer: W,
+    // (an error while copying the bytes, returned by the next call)
+    error: Option<Error>,
+    unconsumed: Unconsumed,
+}
+
+impl<R, W> TeeReader<R, W> {
+    /// Creates a new `TeeReader<R, W>`, which copies the bytes read from
+    /// `reader` to `writer`.
+    pub fn new(reader: R, writer: W) -> TeeReader<R, W> {
+        TeeReader { reader, writer, error: None, unconsumed: Unconsumed::default() }
+    }
+
+    /// Gets references to the underlying reader & writer.
+    pub fn get_ref(&self) -> (&R, &W) {
+        (&self.reader, &self.writer)
+    }
+
+    /// Gets mutable references to the underlying reader & writer.
+    ///
+    /// The bytes read directly from the underlying reader are not copied.
+    pub fn get_mut(&mut self) -> (&mut R, &mut W) {
+        self.unconsumed.reset();
+        (&mut self.reader, &mut self.writer)
+    }
+
+    /// Unwraps this `TeeReader<R, W>`, returning the underlying reader &
+    /// writer.
+    pub fn into_inner(self) -> (R, W) {
+        (self.reader, self.writer)
+    }
+
+    fn take_error(&mut self) -> Result<()> {
+        self.unconsumed.take_error()?;
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+// Copies bytes to the writer of a `TeeReader`, keeping the first error for the
+// next call; nothing more is copied until that call, which skips the bytes read
+// in between, & copying resumes after it
+fn copy_to<W: Write>(writer: &mut W, error: &mut Option<Error>, bytes: &[u8]) {
+    if error.is_none() {
+        if let Err(e) = writer.write_all(bytes) {
+            *error = Some(e);
+        }
+    }
+}
+
+impl<R: Read, W: Write> Read for TeeReader<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.take_error()?;
+        let n = self.reader.read(buf)?;
+        copy_to(&mut self.writer, &mut self.error, &buf[..n]);
+        Ok(n)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        self.take_error()?;
+        let n = self.reader.read_vectored(bufs)?;
+        let mut remaining = n;
+        for buf in bufs.iter() {
+            let len = buf.len().min(remaining);
+            copy_to(&mut self.writer, &mut self.error, &buf[..len]);
+            remaining -= len;
+        }
+        Ok(n)
+    }
+
+    fn is_read_vectored(&self) -> bool {
+        self.reader.is_read_vectored()
+    }
+
+    fn read_buf(&mut self, buf: &mut ReadBuf<'_>) -> Result<()> {
+        self.take_error()?;
+        // (the bytes that were filled are copied, even if there is an error)
+        read_buf_with(&mut self.reader, buf, |filled| {
+            copy_to(&mut self.writer, &mut self.error, filled)
+        })
+    }
+}
+
+impl<R: BufRead, W: Write> BufRead for TeeReader<R, W> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        self.take_error()?;
+        self.unconsumed.fill_buf(&mut self.reader)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.unconsumed.consume(&mut self.reader, amt, |consumed| {
+            copy_to(&mut self.writer, &mut self.error, consumed)
+        })
+    }
+}
+
+/// What a [`Broadcast`] writer does when one of its writers returns an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BroadcastPolicy {
+    /// Stop at the first error, which is returned; the writers after the one
+    /// that failed do not get the bytes.
+    #[default]
+    FailFast,
+    /// Write to all of the writers, even after an error, & then return the
+    /// first error, if any.
+    BestEffort,
+}
+
+/// A collection of writers, to which a [`Broadcast`] writer writes.
+///
+/// This is implemented for arrays, slices & (with the `alloc` feature)
+/// vectors of writers, for tuples of two to four writers, & for mutable
+/// references to any of them.
+pub trait BroadcastWriters {
+    /// Returns the number of writers.
+    fn len(&self) -> usize;
+
+    /// Returns true if there are no writers.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets a mutable reference to the writer at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not less than [`len`](BroadcastWriters::len).
+    fn writer_mut(&mut self, index: usize) -> &mut dyn Write;
+}
+
+impl<T: BroadcastWriters + ?Sized> BroadcastWriters for &mut T {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn writer_mut(&mut self, index: usize) -> &mut dyn Write {
+        (**self).writer_mut(index)
+    }
+}
+
+impl<W: Write> BroadcastWriters for [W] {
+    fn len(&self) -> usize {
+        <[W]>::len(self)
+    }
+
+    fn writer_mut(&mut self, index: usize) -> &mut dyn Write {
+        &mut self[index]
+    }
+}
+
+impl<W: Write, const N: usize> BroadcastWriters for [W; N] {
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn writer_mut(&mut self, index: usize) -> &mut dyn Write {
+        &mut self[index]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<W: Write> BroadcastWriters for Vec<W> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn writer_mut(&mut self, index: usize) -> &mut dyn Write {
+        &mut self[index]
+    }
+}
+
+macro_rules! tuple_writers {
+    ($len:literal; $($name:ident $index:tt),+) => {
+        impl<$($name: Write),+> BroadcastWriters for ($($name,)+) {
+            fn len(&self) -> usize {
+                $len
+            }
+
+            fn writer_mut(&mut self, index: usize) -> &mut dyn Write {
+                match index {
+                    $($index => &mut self.$index,)+
+                    _ => panic!("writer index {} out of range for {} writers", index, $len),
+                }
+            }
+        }
+    };
+}
+
+tuple_writers!(2; A 0, B 1);
+tuple_writers!(3; A 0, B 1, C 2);
+tuple_writers!(4; A 0, B 1, C 2, D 3);
+
+/// Writes the same bytes to several writers, for example to a device & to a
+/// debug log.
+///
+/// Each call to [`write`](Write::write) writes the whole buffer to each of the
+/// writers in turn with [`write_all`](Write::write_all), so that partial
+/// writes are completed & all of the writers get the same bytes. Errors are
+/// handled according to the [`BroadcastPolicy`]; after an error, some of the
+/// writers may have the bytes while others do not, so writing them again may
+/// repeat them. As with `write_all`, a writer that returns
+/// [`ErrorKind::WouldBlock`](crate::ErrorKind::WouldBlock) is not supported.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{Broadcast, Write};
+///
+/// let mut device = [0; 16];
+/// let mut log = [0; 16];
+/// let mut writer = Broadcast::new((&mut device[..], &mut log[..]));
+/// writer.write_all(b"some data").unwrap();
+/// assert_eq!(&device[..9], b"some data");
+/// assert_eq!(&log[..9], b"some data");
+/// ```
+#[derive(Debug)]
+pub struct Broadcast<T> {
+    writers: T,
+    policy: BroadcastPolicy,
+}
+
+impl<T: BroadcastWriters> Broadcast<T> {
+    /// Creates a new `Broadcast<T>`, with the [`FailFast`](BroadcastPolicy::FailFast)
+    /// policy.
+    pub fn new(writers: T) -> Broadcast<T> {
+        Broadcast::with_policy(BroadcastPolicy::FailFast, writers)
+    }
+
+    /// Creates a new `Broadcast<T>`, with the given policy.
+    pub fn with_policy(policy: BroadcastPolicy, writers: T) -> Broadcast<T> {
+        Broadcast { writers, policy }
+    }
+
+    /// Returns the policy of this `Broadcast<T>`.
+    pub fn policy(&self) -> BroadcastPolicy {
+        self.policy
+    }
+
+    /// Gets a reference to the underlying writers.
+    pub fn get_ref(&self) -> &T {
+        &self.writers
+    }
+
+    /// Gets a mutable reference to the underlying writers.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.writers
+    }
+
+    /// Unwraps this `Broadcast<T>`, returning the underlying writers.
+    pub fn into_inner(self) -> T {
+        self.writers
+    }
+
+    // Calls `f` for each of the writers, handling errors according to the policy
+    fn for_each(&mut self, mut f: impl FnMut(&mut dyn Write) -> Result<()>) -> Result<()> {
+        let mut first_error = None;
+        for index in 0..self.writers.len() {
+            if let Err(e) = f(self.writers.writer_mut(index)) {
+                match self.policy {
+                    BroadcastPolicy::FailFast => return Err(e),
+                    BroadcastPolicy::BestEffort => {
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: BroadcastWriters> Write for Broadcast<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.for_each(|writer| writer.write_all(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.for_each(|writer| writer.flush())
+    }
+}
@@ -0,0 +1,146 @@
+use super::{Broadcast, BroadcastPolicy, BroadcastWriters, TeeReader};
+use crate::{BufRead, BufReader, Cursor, ErrorKind, IoSliceMut, Read, ReadBuf, Write};
+
+const DATA: &[u8] = b"some data";
+
+#[test]
+fn tee_read_vectored_and_read_buf() {
+    let mut log = [0; 16];
+    let mut reader = TeeReader::new(DATA, Cursor::new(&mut log[..]));
+    let mut buf = [0; 2];
+    assert_eq!(reader.read(&mut buf).unwrap(), 2);
+
+    let (mut first, mut second) = ([0; 2], [0; 1]);
+    let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+    assert_eq!(reader.read_vectored(&mut bufs).unwrap(), 3);
+
+    let mut storage = [0; 8];
+    let mut buf = ReadBuf::new(&mut storage);
+    buf.append(b"xx");
+    reader.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled(), b"xxdata");
+
+    let (_, writer) = reader.into_inner();
+    let len = writer.position() as usize;
+    assert_eq!(&log[..len], DATA);
+}
+
+#[test]
+fn tee_only_consumed_bytes() {
+    let mut storage = [0; 4];
+    let mut log = [0; 16];
+    let buffered = BufReader::with_slice(&mut storage, DATA);
+    let mut reader = TeeReader::new(buffered, Cursor::new(&mut log[..]));
+    assert_eq!(reader.fill_buf().unwrap(), b"some");
+    reader.consume(1);
+    assert_eq!(reader.get_ref().1.position(), 1);
+
+    // (bytes returned by `fill_buf` again are not copied twice)
+    assert_eq!(reader.fill_buf().unwrap(), b"ome");
+    reader.consume(3);
+    crate::copy_buf(&mut reader, &mut crate::sink()).unwrap();
+    let len = reader.get_ref().1.position() as usize;
+    assert_eq!(&log[..len], DATA);
+}
+
+#[test]
+fn tee_write_error() {
+    // (the log only has room for part of the data)
+    let mut log = [0; 4];
+    let mut reader = TeeReader::new(DATA, Cursor::new(&mut log[..]));
+    let mut buf = [0; 6];
+    // (the bytes that were read are returned, & the error by the next call)
+    assert_eq!(reader.read(&mut buf).unwrap(), 6);
+    assert_eq!(&buf, b"some d");
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(reader.get_ref().1.get_ref(), b"some");
+    // (copying resumes after the error, here from the start of the log again)
+    reader.get_mut().1.set_position(0);
+    assert_eq!(reader.read(&mut buf).unwrap(), 3);
+    assert_eq!(&buf[..3], b"ata");
+    assert_eq!(reader.get_ref().1.get_ref(), b"atae");
+
+    let mut log = [0; 2];
+    let mut reader = TeeReader::new(DATA, &mut log[..]);
+    let (mut first, mut second) = ([0; 3], [0; 3]);
+    let mut bufs = [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)];
+    assert_eq!(reader.read_vectored(&mut bufs).unwrap(), 6);
+    assert_eq!(reader.read_vectored(&mut bufs).unwrap_err().kind(), ErrorKind::WriteZero);
+
+    // (an error while copying consumed bytes is returned by the next call)
+    let mut log = [0; 4];
+    let mut reader = TeeReader::new(DATA, &mut log[..]);
+    assert_eq!(reader.fill_buf().unwrap(), DATA);
+    reader.consume(6);
+    assert_eq!(reader.fill_buf().unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(reader.fill_buf().unwrap(), b"ata");
+}
+
+#[test]
+fn broadcast_to_tuple_array_and_slice() {
+    let (mut first, mut second, mut third) = ([0; 16], [0; 16], [0; 16]);
+    let mut writer = Broadcast::new((&mut first[..], Cursor::new(&mut second[..]), &mut third[..]));
+    assert_eq!(writer.get_ref().len(), 3);
+    writer.write_all(DATA).unwrap();
+    writer.flush().unwrap();
+    assert_eq!(&first[..9], DATA);
+    assert_eq!(&second[..9], DATA);
+    assert_eq!(&third[..9], DATA);
+
+    let (mut first, mut second) = ([0; 16], [0; 16]);
+    let mut writer = Broadcast::new([Cursor::new(&mut first[..]), Cursor::new(&mut second[..])]);
+    writer.write_all(DATA).unwrap();
+    assert!(writer.into_inner().iter().all(|cursor| cursor.position() == 9));
+
+    let (mut first, mut second) = ([0; 16], [0; 16]);
+    let mut cursors = [Cursor::new(&mut first[..]), Cursor::new(&mut second[..])];
+    let mut writer = Broadcast::new(&mut cursors[..]);
+    writer.write_all(DATA).unwrap();
+    assert!(cursors.iter().all(|cursor| cursor.position() == 9));
+
+    let writers: [&mut [u8]; 0] = [];
+    let mut writer = Broadcast::new(writers);
+    assert!(writer.get_ref().is_empty());
+    assert_eq!(writer.write(DATA).unwrap(), 9);
+}
+
+#[test]
+fn broadcast_completes_partial_writes() {
+    // (a writer that accepts at most one byte at a time)
+    struct OneByte<'a>(&'a mut [u8]);
+
+    impl Write for OneByte<'_> {
+        fn write(&mut self, buf: &[u8]) -> crate::Result<usize> {
+            let len = buf.len().min(1);
+            self.0.write(&buf[..len])
+        }
+
+        fn flush(&mut self) -> crate::Result<()> {
+            Ok(())
+        }
+    }
+
+    let (mut first, mut second) = ([0; 16], [0; 16]);
+    let mut writer = Broadcast::new((OneByte(&mut first[..]), &mut second[..]));
+    assert_eq!(writer.write(DATA).unwrap(), 9);
+    assert_eq!(&first[..9], DATA);
+    assert_eq!(&second[..9], DATA);
+}
+
+#[test]
+fn broadcast_policy() {
+    // (the first writer only has room for part of the data)
+    let (mut small, mut large) = ([0; 4], [0; 16]);
+    let mut writer = Broadcast::new((&mut small[..], Cursor::new(&mut large[..])));
+    assert_eq!(writer.policy(), BroadcastPolicy::FailFast);
+    assert_eq!(writer.write(DATA).unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(writer.get_ref().1.position(), 0);
+
+    let (mut small, mut large) = ([0; 4], [0; 16]);
+    let mut writer = Broadcast::with_policy(
+        BroadcastPolicy::BestEffort,
+        (&mut small[..], Cursor::new(&mut large[..])),
+    );
+    assert_eq!(writer.write(DATA).unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(writer.get_ref().1.position(), 9);
+}
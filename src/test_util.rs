@@ -0,0 +1,72 @@
+// (ADDED in this fork) helpers shared by the tests of several modules
+
+use crate::{BufRead, Error, ErrorKind, Read, Write};
+
+// Returns `WouldBlock`, then `Interrupted`, then does at most one byte, in turn
+pub(crate) struct Flaky<T> {
+    pub(crate) inner: T,
+    calls: usize,
+}
+
+impl<T> Flaky<T> {
+    pub(crate) fn new(inner: T) -> Flaky<T> {
+        Flaky { inner, calls: 0 }
+    }
+
+    fn next_error(&mut self) -> Option<Error> {
+        self.calls += 1;
+        match self.calls % 3 {
+            1 => Some(Error::new_const(ErrorKind::WouldBlock, &"would block")),
+            2 => Some(Error::new_const(ErrorKind::Interrupted, &"interrupted")),
+            _ => None,
+        }
+    }
+}
+
+impl<R: Read> Read for Flaky<R> {
+    fn read(&mut self, buf: &mut [u8]) -> crate::Result<usize> {
+        match self.next_error() {
+            Some(e) => Err(e),
+            None => {
+                let len = buf.len().min(1);
+                self.inner.read(&mut buf[..len])
+            }
+        }
+    }
+}
+
+impl BufRead for Flaky<&[u8]> {
+    fn fill_buf(&mut self) -> crate::Result<&[u8]> {
+        match self.next_error() {
+            Some(e) => Err(e),
+            None => Ok(&self.inner[..self.inner.len().min(1)]),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner = &self.inner[amt..];
+    }
+}
+
+impl<W: Write> Write for Flaky<W> {
+    fn write(&mut self, buf: &[u8]) -> crate::Result<usize> {
+        match self.next_error() {
+            Some(e) => Err(e),
+            None => self.inner.write(&buf[..buf.len().min(1)]),
+        }
+    }
+
+    fn flush(&mut self) -> crate::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// Calls `f` until it returns something other than `WouldBlock`
+pub(crate) fn retry<T>(mut f: impl FnMut() -> crate::Result<T>) -> crate::Result<T> {
+    loop {
+        match f() {
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+            result => return result,
+        }
+    }
+}
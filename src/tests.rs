@@ -1,15 +1,22 @@
 use core::ops::Deref;
 
 extern crate alloc;
+#[cfg(feature = "alloc")]
 use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
 
-use crate::cmp::{self, min};
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))]
+// for unstable feature: size hint optimization
+use crate::BufReader;
+#[cfg(feature = "alloc")]
+use crate::cmp;
+use crate::cmp::min;
 use crate::{self as io, IoSlice, IoSliceMut};
 use crate::{BufRead, Read, Seek, Write};
 use crate::{Cursor, ReadBuf, SeekFrom};
 
+#[cfg(feature = "alloc")]
 #[test]
 #[cfg_attr(target_os = "emscripten", ignore)]
 fn read_until() {
@@ -30,6 +37,7 @@ fn read_until() {
     assert_eq!(v, []);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn split() {
     let buf = Cursor::new(&b"12"[..]);
@@ -44,6 +52,7 @@ fn split() {
     assert!(s.next().is_none());
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn read_line() {
     let mut buf = Cursor::new(&b"12"[..]);
@@ -63,6 +72,7 @@ fn read_line() {
     assert_eq!(v, "");
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn lines() {
     let buf = Cursor::new(&b"12\r"[..]);
@@ -87,6 +97,7 @@ fn buf_read_has_data_left() {
     assert!(!buf.has_data_left().unwrap());
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn read_to_end() {
     let mut c = Cursor::new(&b""[..]);
@@ -108,6 +119,7 @@ fn read_to_end() {
     assert_eq!(v, data);
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn read_to_string() {
     let mut c = Cursor::new(&b""[..]);
@@ -236,9 +248,26 @@ fn chain_bufread() {
     cmp_bufread(chain1, chain2, &testdata[..]);
 }
 
-// TODO TEST SIZE HINT WITH WITH MISSING FUNCTIONALITY: BufReader
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))] // for unstable feature: size hint optimization
+#[test]
+fn bufreader_size_hint() {
+    let testdata = b"ABCDEFGHIJKL";
+    let mut buf_reader = BufReader::new(&testdata[..]);
+    assert_eq!(buf_reader.buffer().len(), 0);
 
-// TODO TEST SIZE HINT WITH WITH MISSING FUNCTIONALITY: empty()
+    let buffer_length = testdata.len();
+    buf_reader.fill_buf().unwrap();
+
+    let buf_reader_size_hint = buf_reader.bytes().size_hint();
+    assert_eq!(buf_reader_size_hint, (buffer_length, Some(buffer_length),));
+}
+
+#[cfg(portable_io_unstable_all)] // for unstable feature: size hint optimization
+#[test]
+fn empty_size_hint() {
+    let size_hint = io::empty().bytes().size_hint();
+    assert_eq!(size_hint, (0, Some(0)));
+}
 
 #[cfg(portable_io_unstable_all)] // for unstable feature: size hint optimization
 #[test]
@@ -256,13 +285,34 @@ fn take_size_hint() {
     let size_hint = (&[1, 2, 3]).take(4).bytes().size_hint();
     assert_eq!(size_hint, (3, Some(3)));
 
-    // TODO TEST WITH MISSING FUNCTIONALITY: repeat()
+    let size_hint = io::repeat(0).take(3).bytes().size_hint();
+    assert_eq!(size_hint, (3, Some(3)));
 }
 
-// TODO TEST CHAIN SIZE HINT WITH WITH MISSING FUNCTIONALITY: empty()
+#[cfg(portable_io_unstable_all)] // for unstable feature: size hint optimization
+#[test]
+fn chain_empty_size_hint() {
+    let chain = io::empty().chain(io::empty());
+    let size_hint = chain.bytes().size_hint();
+    assert_eq!(size_hint, (0, Some(0)));
+}
+
+#[cfg(all(portable_io_unstable_all, feature = "alloc"))] // for unstable feature: size hint optimization
+#[test]
+fn chain_size_hint() {
+    let testdata = b"ABCDEFGHIJKL";
+    let mut buf_reader_1 = BufReader::new(&testdata[..6]);
+    let mut buf_reader_2 = BufReader::new(&testdata[6..]);
 
-// TODO TEST CHAIN SIZE HINT WITH WITH MISSING FUNCTIONALITY: BufReader
+    buf_reader_1.fill_buf().unwrap();
+    buf_reader_2.fill_buf().unwrap();
+
+    let chain = buf_reader_1.chain(buf_reader_2);
+    let size_hint = chain.bytes().size_hint();
+    assert_eq!(size_hint, (testdata.len(), Some(testdata.len())));
+}
 
+#[cfg(feature = "alloc")]
 #[test]
 fn chain_zero_length_read_is_not_eof() {
     let a = b"A";
@@ -325,10 +375,12 @@ fn seek_position() -> io::Result<()> {
 
 // A simple example reader which uses the default implementation of
 // read_to_end.
+#[cfg(feature = "alloc")]
 struct ExampleSliceReader<'a> {
     slice: &'a [u8],
 }
 
+#[cfg(feature = "alloc")]
 impl<'a> Read for ExampleSliceReader<'a> {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         let len = cmp::min(self.slice.len(), buf.len());
@@ -338,6 +390,7 @@ impl<'a> Read for ExampleSliceReader<'a> {
     }
 }
 
+#[cfg(feature = "alloc")]
 #[test]
 fn test_read_to_end_capacity() -> io::Result<()> {
     let input = &b"foo"[..];
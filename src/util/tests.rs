@@ -0,0 +1,236 @@
+use core::mem::MaybeUninit;
+
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::prelude::*;
+use crate::{self as io, IoSlice, IoSliceMut, ReadBuf, SeekFrom, copy};
+#[cfg(feature = "alloc")]
+use crate::{BufReader, copy_buf};
+use crate::{empty, repeat, sink};
+
+#[test]
+fn copy_copies() {
+    let mut r = repeat(0).take(4);
+    let mut w = sink();
+    assert_eq!(copy(&mut r, &mut w).unwrap(), 4);
+
+    let mut r = repeat(0).take(1 << 17);
+    assert_eq!(copy(&mut r as &mut dyn Read, &mut w as &mut dyn Write).unwrap(), 1 << 17);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn copy_to_vec() {
+    let mut r = repeat(7).take(1 << 17);
+    let mut w = Vec::new();
+    assert_eq!(copy(&mut r, &mut w).unwrap(), 1 << 17);
+    assert_eq!(w.len(), 1 << 17);
+    assert!(w.iter().all(|b| *b == 7));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn copy_from_slice() {
+    let mut r: &[u8] = b"hello world";
+    let mut w = Vec::new();
+    assert_eq!(copy(&mut r, &mut w).unwrap(), 11);
+    assert_eq!(w, b"hello world");
+    assert!(r.is_empty());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn copy_from_bufreader() {
+    let data: Vec<u8> = (0..=255).cycle().take(20_000).collect();
+
+    let mut r = BufReader::new(&data[..]);
+    let mut w = io::Cursor::new(Vec::new());
+    assert_eq!(copy(&mut r, &mut w).unwrap(), data.len() as u64);
+    assert_eq!(w.into_inner(), data);
+
+    let mut r: BufReader<_, [MaybeUninit<u8>; 64]> = BufReader::with_array(&data[..]);
+    let mut w = Vec::new();
+    assert_eq!(copy(&mut r, &mut w).unwrap(), data.len() as u64);
+    assert_eq!(w, data);
+}
+
+/// A reader that returns `Interrupted` before every successful read.
+#[cfg(feature = "alloc")]
+struct InterruptingReader<R> {
+    inner: R,
+    interrupt: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<R: Read> Read for InterruptingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn copy_retries_interrupted() {
+    let mut r = InterruptingReader { inner: &b"hello"[..], interrupt: false };
+    let mut w = io::Cursor::new(Vec::new());
+    assert_eq!(copy(&mut r, &mut w).unwrap(), 5);
+    assert_eq!(w.into_inner(), b"hello");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn copy_buf_copies() {
+    let data: Vec<u8> = (0..=255).cycle().take(20_000).collect();
+
+    let mut r = BufReader::with_capacity(100, &data[..]);
+    let mut w = Vec::new();
+    assert_eq!(copy_buf(&mut r, &mut w).unwrap(), data.len() as u64);
+    assert_eq!(w, data);
+
+    let mut r = BufReader::new(InterruptingReader { inner: &b"hello"[..], interrupt: false });
+    let mut w = Vec::new();
+    assert_eq!(copy_buf(&mut r, &mut w).unwrap(), 5);
+    assert_eq!(w, b"hello");
+}
+
+#[test]
+fn copy_write_error() {
+    let mut r = repeat(0).take(10);
+    let mut storage = [0u8; 4];
+    let mut w = &mut storage[..];
+    assert_eq!(copy(&mut r, &mut w).unwrap_err().kind(), io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn sink_sinks() {
+    let mut s = sink();
+    assert_eq!(s.write(&[]).unwrap(), 0);
+    assert_eq!(s.write(&[0]).unwrap(), 1);
+    assert_eq!(s.write(&[0; 1024]).unwrap(), 1024);
+    assert_eq!(s.by_ref().write(&[0; 1024]).unwrap(), 1024);
+    assert_eq!((&s).write(&[0; 16]).unwrap(), 16);
+}
+
+#[test]
+fn sink_sinks_vectored() {
+    let mut s = sink();
+    assert!(s.is_write_vectored());
+    assert_eq!(s.write_vectored(&[]).unwrap(), 0);
+    assert_eq!(s.write_vectored(&[IoSlice::new(&[0; 3]), IoSlice::new(&[0; 5])]).unwrap(), 8);
+}
+
+#[test]
+fn empty_reads() {
+    let mut e = empty();
+    assert_eq!(e.read(&mut []).unwrap(), 0);
+    assert_eq!(e.read(&mut [0]).unwrap(), 0);
+    assert_eq!(e.read(&mut [0; 1024]).unwrap(), 0);
+    assert_eq!(e.by_ref().read(&mut [0; 1024]).unwrap(), 0);
+
+    let mut buf = [];
+    let mut buf = ReadBuf::uninit(&mut buf);
+    e.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled_len(), 0);
+    assert_eq!(buf.initialized_len(), 0);
+
+    let mut buf = [MaybeUninit::uninit()];
+    let mut buf = ReadBuf::uninit(&mut buf);
+    e.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled_len(), 0);
+    assert_eq!(buf.initialized_len(), 0);
+
+    let mut buf = [MaybeUninit::uninit(); 1024];
+    let mut buf = ReadBuf::uninit(&mut buf);
+    e.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled_len(), 0);
+    assert_eq!(buf.initialized_len(), 0);
+
+    let mut buf = [MaybeUninit::uninit(); 1024];
+    let mut buf = ReadBuf::uninit(&mut buf);
+    e.by_ref().read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled_len(), 0);
+    assert_eq!(buf.initialized_len(), 0);
+
+    assert_eq!(e.fill_buf().unwrap(), b"");
+}
+
+#[test]
+fn empty_seeks() {
+    let mut e = empty();
+    assert!(matches!(e.seek(SeekFrom::Start(0)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::Start(1)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::Start(u64::MAX)), Ok(0)));
+
+    assert!(matches!(e.seek(SeekFrom::End(i64::MIN)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::End(-1)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::End(0)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::End(1)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::End(i64::MAX)), Ok(0)));
+
+    assert!(matches!(e.seek(SeekFrom::Current(i64::MIN)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::Current(-1)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::Current(0)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::Current(1)), Ok(0)));
+    assert!(matches!(e.seek(SeekFrom::Current(i64::MAX)), Ok(0)));
+
+    assert!(matches!(e.stream_len(), Ok(0)));
+    assert!(matches!(e.stream_position(), Ok(0)));
+}
+
+#[test]
+fn repeat_repeats() {
+    let mut r = repeat(4);
+    let mut b = [0; 1024];
+    assert_eq!(r.read(&mut b).unwrap(), 1024);
+    assert!(b.iter().all(|b| *b == 4));
+}
+
+#[test]
+fn repeat_read_buf() {
+    let mut r = repeat(4);
+
+    let mut buf = [MaybeUninit::uninit(); 16];
+    let mut buf = ReadBuf::uninit(&mut buf);
+    buf.append(&[1, 2]);
+    r.read_buf(&mut buf).unwrap();
+    assert_eq!(buf.filled_len(), 16);
+    assert_eq!(buf.initialized_len(), 16);
+    assert_eq!(&buf.filled()[..4], [1, 2, 4, 4]);
+    assert!(buf.filled()[2..].iter().all(|b| *b == 4));
+}
+
+#[test]
+fn repeat_read_vectored() {
+    let mut r = repeat(4);
+    assert!(r.is_read_vectored());
+
+    let mut a = [0; 3];
+    let mut b = [0; 5];
+    let n = r.read_vectored(&mut [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)]).unwrap();
+    assert_eq!(n, 8);
+    assert_eq!(a, [4; 3]);
+    assert_eq!(b, [4; 5]);
+}
+
+#[test]
+fn take_some_bytes() {
+    assert_eq!(repeat(4).take(100).bytes().count(), 100);
+    assert_eq!(repeat(4).take(100).bytes().next().unwrap().unwrap(), 4);
+    assert_eq!(repeat(1).take(10).chain(repeat(2).take(10)).bytes().count(), 20);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn chain_with_empty() {
+    let mut v = vec![];
+    empty().chain(&b"abc"[..]).chain(empty()).read_to_end(&mut v).unwrap();
+    assert_eq!(v, b"abc");
+}
@@ -0,0 +1,386 @@
+// (ADDED in this fork) extension traits to read & write variable-length integers:
+// LEB128 (as used by WebAssembly & DWARF), zigzag (as used by protobuf) & prefix varints
+
+#[cfg(test)]
+mod tests;
+
+use crate::{BufRead, Error, ErrorKind, Read, Result, Write};
+
+// The maximum length of a LEB128 encoded 64-bit integer
+pub(crate) const MAX_LEB128_LEN: usize = 10;
+
+// The maximum length of a prefix varint encoded 64-bit integer
+const MAX_PREFIX_VARINT_LEN: usize = 9;
+
+/// Extends [`Read`] with methods to read variable-length integers.
+///
+/// The supported encodings are:
+///
+/// - unsigned & signed [LEB128](https://en.wikipedia.org/wiki/LEB128), as used
+///   by WebAssembly & DWARF
+/// - zigzag, as used by protobuf for `sint32` & `sint64`: a signed integer is
+///   mapped to an unsigned integer (`0, -1, 1, -2, ...` to `0, 1, 2, 3, ...`),
+///   which is then encoded as unsigned LEB128
+/// - prefix varint: the number of trailing zero bits in the first byte gives
+///   the number of bytes that follow, which are little-endian, so that the
+///   length is known after the first byte; a 64-bit integer takes 1 to 9 bytes
+///
+/// # Errors
+///
+/// If an encoded value does not fit in the integer type, or if the encoding
+/// is longer than the maximum length for the integer type, an error of the kind
+/// [`ErrorKind::InvalidData`] is returned. Otherwise, errors are returned the
+/// same way as [`read_exact`](Read::read_exact), including an error of the kind
+/// [`ErrorKind::UnexpectedEof`] if the reader reaches EOF in the middle of a
+/// value.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::ReadVarintExt;
+///
+/// let mut reader: &[u8] = &[0xe5, 0x8e, 0x26, 0x7f, 0x03];
+/// assert_eq!(reader.read_uleb128_u32().unwrap(), 624485);
+/// assert_eq!(reader.read_sleb128_i64().unwrap(), -1);
+/// assert_eq!(reader.read_zigzag_i32().unwrap(), -2);
+/// ```
+pub trait ReadVarintExt: Read {
+    /// Reads an unsigned LEB128 encoded `u32` from this reader.
+    fn read_uleb128_u32(&mut self) -> Result<u32> {
+        Ok(read_leb128(self, Leb128::new(32, false))? as u32)
+    }
+
+    /// Reads an unsigned LEB128 encoded `u64` from this reader.
+    fn read_uleb128_u64(&mut self) -> Result<u64> {
+        read_leb128(self, Leb128::new(64, false))
+    }
+
+    /// Reads a signed LEB128 encoded `i32` from this reader.
+    fn read_sleb128_i32(&mut self) -> Result<i32> {
+        Ok(read_leb128(self, Leb128::new(32, true))? as i32)
+    }
+
+    /// Reads a signed LEB128 encoded `i64` from this reader.
+    fn read_sleb128_i64(&mut self) -> Result<i64> {
+        Ok(read_leb128(self, Leb128::new(64, true))? as i64)
+    }
+
+    /// Reads a zigzag encoded `i32` from this reader.
+    fn read_zigzag_i32(&mut self) -> Result<i32> {
+        Ok(zigzag_decode(read_leb128(self, Leb128::new(32, false))?) as i32)
+    }
+
+    /// Reads a zigzag encoded `i64` from this reader.
+    fn read_zigzag_i64(&mut self) -> Result<i64> {
+        Ok(zigzag_decode(read_leb128(self, Leb128::new(64, false))?))
+    }
+
+    /// Reads a prefix varint encoded `u32` from this reader.
+    fn read_prefix_varint_u32(&mut self) -> Result<u32> {
+        narrow_u32(self.read_prefix_varint_u64()?)
+    }
+
+    /// Reads a prefix varint encoded `u64` from this reader.
+    fn read_prefix_varint_u64(&mut self) -> Result<u64> {
+        let mut buf = [0; MAX_PREFIX_VARINT_LEN];
+        self.read_exact(&mut buf[..1])?;
+        let len = prefix_varint_len(buf[0]);
+        self.read_exact(&mut buf[1..len])?;
+        Ok(prefix_varint_decode(&buf[..len]))
+    }
+}
+
+impl<R: Read + ?Sized> ReadVarintExt for R {}
+
+/// Extends [`BufRead`] with methods to decode variable-length integers
+/// straight out of the internal buffer.
+///
+/// These methods work like the methods of [`ReadVarintExt`], with the same
+/// encodings & errors, but decode the bytes returned by
+/// [`fill_buf`](BufRead::fill_buf) without copying them, and
+/// [`consume`](BufRead::consume) exactly the bytes of the value.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{BufRead, BufReadVarintExt};
+///
+/// let mut reader: &[u8] = &[0xac, 0x02, 0xff];
+/// assert_eq!(reader.decode_uleb128_u64().unwrap(), 300);
+/// assert_eq!(reader.fill_buf().unwrap(), [0xff]);
+/// ```
+pub trait BufReadVarintExt: BufRead {
+    /// Decodes an unsigned LEB128 encoded `u32` from the buffer of this reader.
+    fn decode_uleb128_u32(&mut self) -> Result<u32> {
+        Ok(decode_leb128(self, Leb128::new(32, false))? as u32)
+    }
+
+    /// Decodes an unsigned LEB128 encoded `u64` from the buffer of this reader.
+    fn decode_uleb128_u64(&mut self) -> Result<u64> {
+        decode_leb128(self, Leb128::new(64, false))
+    }
+
+    /// Decodes a signed LEB128 encoded `i32` from the buffer of this reader.
+    fn decode_sleb128_i32(&mut self) -> Result<i32> {
+        Ok(decode_leb128(self, Leb128::new(32, true))? as i32)
+    }
+
+    /// Decodes a signed LEB128 encoded `i64` from the buffer of this reader.
+    fn decode_sleb128_i64(&mut self) -> Result<i64> {
+        Ok(decode_leb128(self, Leb128::new(64, true))? as i64)
+    }
+
+    /// Decodes a zigzag encoded `i32` from the buffer of this reader.
+    fn decode_zigzag_i32(&mut self) -> Result<i32> {
+        Ok(zigzag_decode(decode_leb128(self, Leb128::new(32, false))?) as i32)
+    }
+
+    /// Decodes a zigzag encoded `i64` from the buffer of this reader.
+    fn decode_zigzag_i64(&mut self) -> Result<i64> {
+        Ok(zigzag_decode(decode_leb128(self, Leb128::new(64, false))?))
+    }
+
+    /// Decodes a prefix varint encoded `u32` from the buffer of this reader.
+    fn decode_prefix_varint_u32(&mut self) -> Result<u32> {
+        narrow_u32(self.decode_prefix_varint_u64()?)
+    }
+
+    /// Decodes a prefix varint encoded `u64` from the buffer of this reader.
+    ///
+    /// If the value is split across the end of the buffer, it is read with
+    /// [`read_prefix_varint_u64`](ReadVarintExt::read_prefix_varint_u64).
+    fn decode_prefix_varint_u64(&mut self) -> Result<u64> {
+        let available = loop {
+            match self.fill_buf() {
+                Ok(buf) => break buf,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        };
+        match available.first() {
+            Some(&first) if prefix_varint_len(first) <= available.len() => {
+                let len = prefix_varint_len(first);
+                let value = prefix_varint_decode(&available[..len]);
+                self.consume(len);
+                Ok(value)
+            }
+            _ => self.read_prefix_varint_u64(),
+        }
+    }
+}
+
+impl<R: BufRead + ?Sized> BufReadVarintExt for R {}
+
+/// Extends [`Write`] with methods to write variable-length integers.
+///
+/// See [`ReadVarintExt`] for the supported encodings. Values are always
+/// written with the shortest encoding.
+///
+/// # Errors
+///
+/// All methods are built on [`write_all`](Write::write_all), so any error is
+/// returned the same way.
+///
+/// # Example code
+///
+/// ```
+/// use portable_io::{Cursor, WriteVarintExt};
+///
+/// let mut storage = [0; 4];
+/// let mut writer = Cursor::new(&mut storage[..]);
+/// writer.write_uleb128_u32(300).unwrap();
+/// writer.write_sleb128_i64(-128).unwrap();
+/// assert_eq!(storage, [0xac, 0x02, 0x80, 0x7f]);
+/// ```
+pub trait WriteVarintExt: Write {
+    /// Writes a `u32` to this writer as unsigned LEB128.
+    #[inline]
+    fn write_uleb128_u32(&mut self, n: u32) -> Result<()> {
+        self.write_uleb128_u64(n.into())
+    }
+
+    /// Writes a `u64` to this writer as unsigned LEB128.
+    fn write_uleb128_u64(&mut self, mut n: u64) -> Result<()> {
+        let mut buf = [0; MAX_LEB128_LEN];
+        let mut len = 0;
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                buf[len] = byte;
+                len += 1;
+                break;
+            }
+            buf[len] = byte | 0x80;
+            len += 1;
+        }
+        self.write_all(&buf[..len])
+    }
+
+    /// Writes an `i32` to this writer as signed LEB128.
+    #[inline]
+    fn write_sleb128_i32(&mut self, n: i32) -> Result<()> {
+        self.write_sleb128_i64(n.into())
+    }
+
+    /// Writes an `i64` to this writer as signed LEB128.
+    fn write_sleb128_i64(&mut self, mut n: i64) -> Result<()> {
+        let mut buf = [0; MAX_LEB128_LEN];
+        let mut len = 0;
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            // (done once the rest is only made of sign bits, including the
+            // highest bit of this byte)
+            if (n == 0 && byte & 0x40 == 0) || (n == -1 && byte & 0x40 != 0) {
+                buf[len] = byte;
+                len += 1;
+                break;
+            }
+            buf[len] = byte | 0x80;
+            len += 1;
+        }
+        self.write_all(&buf[..len])
+    }
+
+    /// Writes an `i32` to this writer as zigzag.
+    #[inline]
+    fn write_zigzag_i32(&mut self, n: i32) -> Result<()> {
+        self.write_zigzag_i64(n.into())
+    }
+
+    /// Writes an `i64` to this writer as zigzag.
+    #[inline]
+    fn write_zigzag_i64(&mut self, n: i64) -> Result<()> {
+        self.write_uleb128_u64(((n << 1) ^ (n >> 63)) as u64)
+    }
+
+    /// Writes a `u32` to this writer as a prefix varint.
+    #[inline]
+    fn write_prefix_varint_u32(&mut self, n: u32) -> Result<()> {
+        self.write_prefix_varint_u64(n.into())
+    }
+
+    /// Writes a `u64` to this writer as a prefix varint.
+    fn write_prefix_varint_u64(&mut self, n: u64) -> Result<()> {
+        let bits = 64 - (n | 1).leading_zeros() as usize;
+        let len = 1 + (bits - 1) / 7;
+        if len >= MAX_PREFIX_VARINT_LEN {
+            let mut buf = [0; MAX_PREFIX_VARINT_LEN];
+            buf[1..].copy_from_slice(&n.to_le_bytes());
+            return self.write_all(&buf);
+        }
+        let encoded = (n << len) | (1 << (len - 1));
+        self.write_all(&encoded.to_le_bytes()[..len])
+    }
+}
+
+impl<W: Write + ?Sized> WriteVarintExt for W {}
+
+// Decodes LEB128, one byte at a time
+struct Leb128 {
+    value: u64,
+    shift: u32,
+    bits: u32,
+    signed: bool,
+}
+
+impl Leb128 {
+    fn new(bits: u32, signed: bool) -> Leb128 {
+        Leb128 { value: 0, shift: 0, bits, signed }
+    }
+
+    // Returns the value (sign-extended to 64 bits if signed) after the last byte
+    fn push(&mut self, byte: u8) -> Result<Option<u64>> {
+        let payload = u64::from(byte & 0x7f);
+        let remaining = self.bits - self.shift;
+        if remaining <= 7 {
+            // (the last byte that may be part of a value of this integer type)
+            if byte & 0x80 != 0 {
+                return Err(Error::new_const(ErrorKind::InvalidData, &"LEB128 value is too long"));
+            }
+            let fits = if self.signed {
+                // (the unused bits must be copies of the sign bit)
+                let sign_and_unused = payload >> (remaining - 1);
+                sign_and_unused == 0 || sign_and_unused == 0x7f >> (remaining - 1)
+            } else {
+                payload >> remaining == 0
+            };
+            if !fits {
+                return Err(Error::new_const(
+                    ErrorKind::InvalidData,
+                    &"LEB128 value does not fit in the integer type",
+                ));
+            }
+        }
+        self.value |= payload << self.shift;
+        self.shift += 7;
+        if byte & 0x80 != 0 {
+            return Ok(None);
+        }
+        if self.signed && self.shift < 64 && payload & 0x40 != 0 {
+            self.value |= !0 << self.shift;
+        }
+        Ok(Some(self.value))
+    }
+}
+
+fn read_leb128<R: Read + ?Sized>(reader: &mut R, mut leb128: Leb128) -> Result<u64> {
+    loop {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        if let Some(value) = leb128.push(byte[0])? {
+            return Ok(value);
+        }
+    }
+}
+
+fn decode_leb128<R: BufRead + ?Sized>(reader: &mut R, mut leb128: Leb128) -> Result<u64> {
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(buf) => buf,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return Err(Error::new_const(ErrorKind::UnexpectedEof, &"failed to fill whole buffer"));
+        }
+        let mut used = 0;
+        let mut result = Ok(None);
+        for &byte in available {
+            used += 1;
+            result = leb128.push(byte);
+            if !matches!(result, Ok(None)) {
+                break;
+            }
+        }
+        reader.consume(used);
+        if let Some(value) = result? {
+            return Ok(value);
+        }
+    }
+}
+
+fn zigzag_decode(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+pub(crate) fn prefix_varint_len(first: u8) -> usize {
+    if first == 0 { MAX_PREFIX_VARINT_LEN } else { first.trailing_zeros() as usize + 1 }
+}
+
+fn prefix_varint_decode(buf: &[u8]) -> u64 {
+    let mut bytes = [0; 8];
+    if buf.len() == MAX_PREFIX_VARINT_LEN {
+        bytes.copy_from_slice(&buf[1..]);
+        return u64::from_le_bytes(bytes);
+    }
+    bytes[..buf.len()].copy_from_slice(buf);
+    u64::from_le_bytes(bytes) >> buf.len()
+}
+
+fn narrow_u32(n: u64) -> Result<u32> {
+    u32::try_from(n).map_err(|_| {
+        Error::new_const(ErrorKind::InvalidData, &"varint value does not fit in the integer type")
+    })
+}
@@ -0,0 +1,230 @@
+use core::mem::MaybeUninit;
+
+use super::{BufReadVarintExt, ReadVarintExt, WriteVarintExt};
+use crate::{BufReader, Cursor, ErrorKind};
+
+// Powers of two & their neighbours, for all bit widths, followed by all 16-bit values
+fn test_values() -> impl Iterator<Item = u64> {
+    (0..64)
+        .flat_map(|k| {
+            let p = 1u64 << k;
+            [p - 1, p, p + 1, !p, !(p - 1)]
+        })
+        .chain(0..=u64::from(u16::MAX))
+}
+
+// The shortest length of LEB128 for a value with the given number of significant bits
+fn leb128_len(bits: u32) -> u64 {
+    1 + (u64::from(bits.max(1)) - 1) / 7
+}
+
+fn prefix_varint_len(n: u64) -> u64 {
+    let bits = u64::from(64 - n.leading_zeros()).max(1);
+    if bits > 56 { 9 } else { 1 + (bits - 1) / 7 }
+}
+
+// Reads a value with `read`, then again with `decode` through a `BufReader` with
+// a buffer small enough to split most values
+fn check_round_trip<T: PartialEq + core::fmt::Debug + Copy>(
+    value: T,
+    expected_len: u64,
+    write: impl Fn(&mut Cursor<&mut [u8]>, T) -> crate::Result<()>,
+    read: impl Fn(&mut &[u8]) -> crate::Result<T>,
+    decode: impl Fn(&mut BufReader<&[u8], [MaybeUninit<u8>; 3]>) -> crate::Result<T>,
+) {
+    let mut storage = [0; 32];
+    let mut writer = Cursor::new(&mut storage[..]);
+    write(&mut writer, value).unwrap();
+    write(&mut writer, value).unwrap();
+    assert_eq!(writer.position(), 2 * expected_len, "length for {:?}", value);
+
+    let mut reader = &storage[..2 * expected_len as usize];
+    assert_eq!(read(&mut reader).unwrap(), value);
+    assert_eq!(read(&mut reader).unwrap(), value);
+    assert!(reader.is_empty());
+
+    let mut reader = BufReader::with_array(&storage[..2 * expected_len as usize]);
+    assert_eq!(decode(&mut reader).unwrap(), value);
+    assert_eq!(decode(&mut reader).unwrap(), value);
+    assert_eq!(crate::BufRead::fill_buf(&mut reader).unwrap(), []);
+}
+
+#[test]
+fn round_trip_uleb128() {
+    for n in test_values() {
+        check_round_trip(
+            n,
+            leb128_len(64 - n.leading_zeros()),
+            |w, n| w.write_uleb128_u64(n),
+            |r| r.read_uleb128_u64(),
+            |r| r.decode_uleb128_u64(),
+        );
+        let n = n as u32;
+        check_round_trip(
+            n,
+            leb128_len(32 - n.leading_zeros()),
+            |w, n| w.write_uleb128_u32(n),
+            |r| r.read_uleb128_u32(),
+            |r| r.decode_uleb128_u32(),
+        );
+    }
+}
+
+#[test]
+fn round_trip_sleb128() {
+    for n in test_values() {
+        let n = n as i64;
+        // (the sign takes one more bit)
+        let bits = 65 - if n < 0 { (!n).leading_zeros() } else { n.leading_zeros() };
+        check_round_trip(
+            n,
+            leb128_len(bits),
+            |w, n| w.write_sleb128_i64(n),
+            |r| r.read_sleb128_i64(),
+            |r| r.decode_sleb128_i64(),
+        );
+        let n = n as i32;
+        let bits = 33 - if n < 0 { (!n).leading_zeros() } else { n.leading_zeros() };
+        check_round_trip(
+            n,
+            leb128_len(bits),
+            |w, n| w.write_sleb128_i32(n),
+            |r| r.read_sleb128_i32(),
+            |r| r.decode_sleb128_i32(),
+        );
+    }
+}
+
+#[test]
+fn round_trip_zigzag() {
+    for n in test_values() {
+        let n = n as i64;
+        let zigzag = ((n << 1) ^ (n >> 63)) as u64;
+        check_round_trip(
+            n,
+            leb128_len(64 - zigzag.leading_zeros()),
+            |w, n| w.write_zigzag_i64(n),
+            |r| r.read_zigzag_i64(),
+            |r| r.decode_zigzag_i64(),
+        );
+        let n = n as i32;
+        let zigzag = ((n << 1) ^ (n >> 31)) as u32;
+        check_round_trip(
+            n,
+            leb128_len(32 - zigzag.leading_zeros()),
+            |w, n| w.write_zigzag_i32(n),
+            |r| r.read_zigzag_i32(),
+            |r| r.decode_zigzag_i32(),
+        );
+    }
+}
+
+#[test]
+fn round_trip_prefix_varint() {
+    for n in test_values() {
+        check_round_trip(
+            n,
+            prefix_varint_len(n),
+            |w, n| w.write_prefix_varint_u64(n),
+            |r| r.read_prefix_varint_u64(),
+            |r| r.decode_prefix_varint_u64(),
+        );
+        let n = n as u32;
+        check_round_trip(
+            n,
+            prefix_varint_len(n.into()),
+            |w, n| w.write_prefix_varint_u32(n),
+            |r| r.read_prefix_varint_u32(),
+            |r| r.decode_prefix_varint_u32(),
+        );
+    }
+}
+
+#[test]
+fn known_encodings() {
+    let mut storage = [0; 32];
+    let mut writer = Cursor::new(&mut storage[..]);
+    writer.write_uleb128_u64(624485).unwrap();
+    writer.write_sleb128_i64(-123456).unwrap();
+    writer.write_zigzag_i32(i32::MIN).unwrap();
+    writer.write_prefix_varint_u64(300).unwrap();
+    writer.write_prefix_varint_u64(u64::MAX).unwrap();
+    let len = writer.position() as usize;
+    assert_eq!(
+        storage[..len],
+        [
+            0xe5, 0x8e, 0x26, // 624485
+            0xc0, 0xbb, 0x78, // -123456
+            0xff, 0xff, 0xff, 0xff, 0x0f, // i32::MIN
+            0xb2, 0x04, // 300 (= 0x12c << 2 | 0b10)
+            0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // u64::MAX
+        ]
+    );
+}
+
+#[test]
+fn non_canonical_encodings() {
+    // (padded encodings are accepted, up to the maximum length)
+    let mut reader: &[u8] = &[0x81, 0x80, 0x80, 0x80, 0x00];
+    assert_eq!(reader.read_uleb128_u32().unwrap(), 1);
+    let mut reader: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0x7f];
+    assert_eq!(reader.read_sleb128_i32().unwrap(), -1);
+}
+
+type ReadFn = fn(&mut &[u8]) -> crate::Result<()>;
+
+#[test]
+fn overflow() {
+    let cases: &[(&[u8], ReadFn)] = &[
+        (&[0xff, 0xff, 0xff, 0xff, 0x10], |r| r.read_uleb128_u32().map(drop)),
+        (&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02], |r| {
+            r.read_uleb128_u64().map(drop)
+        }),
+        (&[0xff, 0xff, 0xff, 0xff, 0x08], |r| r.read_sleb128_i32().map(drop)),
+        (&[0x80, 0x80, 0x80, 0x80, 0x70], |r| r.read_sleb128_i32().map(drop)),
+        (&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7e], |r| {
+            r.read_sleb128_i64().map(drop)
+        }),
+        (&[0xff, 0xff, 0xff, 0xff, 0x1f], |r| r.read_zigzag_i32().map(drop)),
+        (&[0x20, 0x00, 0x00, 0x00, 0x00, 0x01], |r| r.read_prefix_varint_u32().map(drop)),
+    ];
+    for (bytes, read) in cases {
+        let error = read(&mut &bytes[..]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData, "for {:x?}", bytes);
+    }
+
+    let mut reader: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0x10];
+    assert_eq!(reader.decode_uleb128_u32().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // (the limits themselves are fine)
+    let mut reader: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0x0f, 0x80, 0x80, 0x80, 0x80, 0x78];
+    assert_eq!(reader.read_uleb128_u32().unwrap(), u32::MAX);
+    assert_eq!(reader.read_sleb128_i32().unwrap(), i32::MIN);
+}
+
+#[test]
+fn too_long() {
+    let mut reader: &[u8] = &[0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+    let error = reader.read_uleb128_u32().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert_eq!(reader.len(), 1);
+
+    let mut reader: &[u8] = &[0x80; 16];
+    assert_eq!(reader.read_sleb128_i64().unwrap_err().kind(), ErrorKind::InvalidData);
+    let mut reader: &[u8] = &[0x80; 16];
+    assert_eq!(reader.decode_zigzag_i64().unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn unexpected_eof() {
+    let mut reader: &[u8] = &[0x80, 0x80];
+    assert_eq!(reader.read_uleb128_u64().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    let mut reader: &[u8] = &[0x80, 0x80];
+    assert_eq!(reader.decode_sleb128_i64().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    let mut reader: &[u8] = &[0b100, 0x00];
+    assert_eq!(reader.read_prefix_varint_u64().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    let mut reader: &[u8] = &[0b100, 0x00];
+    assert_eq!(reader.decode_prefix_varint_u64().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    let mut reader: &[u8] = &[];
+    assert_eq!(reader.decode_uleb128_u32().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}